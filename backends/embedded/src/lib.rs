@@ -0,0 +1,560 @@
+//! A `no_std` [`Backend`] for merfolk communicating over any [`embedded_io`] port, e.g. the UART of a microcontroller.
+//!
+//! The frames are the same as the frames of the [`SerialPort`](https://docs.rs/merfolk_backend_serialport) backend so firmware using the [`Embedded`] backend can serve procedures to and call procedures of a host using the [`SerialPort`](https://docs.rs/merfolk_backend_serialport) backend.
+//! Both sides have to use the same [`Codec`], [`Framing`] and addresses.
+//!
+//! The [`Embedded`] backend has no runtime, incoming calls are answered by calling [`poll`](Embedded::poll) from the main loop of the firmware.
+//!
+//! ```no_run
+//! # use merfolk_backend_embedded::{Embedded, Framing};
+//! # use merfolk_codecs::Postcard;
+//! # fn firmware<P: embedded_io::Read + embedded_io::ReadReady + embedded_io::Write + Send + 'static>(uart: P) {
+//! let register = merfolk_frontend_register::Register::builder().build().unwrap();
+//! register.register("add", |(a, b): (i32, i32)| a + b).unwrap();
+//!
+//! let merfolk = merfolk::Mer::builder()
+//!   .backend(Embedded::<_, Postcard>::builder().port(uart).framing(Framing::Cobs).build().unwrap())
+//!   .frontend(register)
+//!   .build()
+//!   .unwrap();
+//!
+//! loop {
+//!   merfolk.backend(|b| b.poll()).unwrap().unwrap();
+//! }
+//! # }
+//! ```
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub use merfolk_framing::Framing;
+use merfolk_framing::{self as framing, Route, SelfCall, SelfReply, SelfReplyFrame};
+
+use alloc::{
+  boxed::Box,
+  collections::VecDeque,
+  format,
+  string::{String, ToString},
+  vec::Vec,
+};
+use core::{fmt, marker::PhantomData, time::Duration};
+
+use anyhow::Result;
+use embedded_io::{Read, ReadReady, Write};
+use log::{debug, trace, warn};
+use merfolk::{
+  interfaces::{Backend, Codec, Encoded},
+  Call, Metadata, RemoteError, Reply, ReplyStream,
+};
+
+#[derive(Debug)]
+/// Error type for [`Embedded`] errors.
+pub enum Error {
+  NoReceiver,
+  Io(embedded_io::ErrorKind),
+  Closed,
+  MalformedFrame(String),
+  NoTarget,
+  InvalidTarget(String),
+  Broadcast,
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Error::NoReceiver => write!(f, "no receiver was registered by init()"),
+      Error::Io(kind) => write!(f, "port failed: {:?}", kind),
+      Error::Closed => write!(f, "port was closed"),
+      Error::MalformedFrame(reason) => write!(f, "malformed frame: {}", reason),
+      Error::NoTarget => write!(f, "no target node was selected for the call"),
+      Error::InvalidTarget(target) => write!(f, "invalid target address {}", target),
+      Error::Broadcast => write!(f, "only notifications can be broadcast"),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(not(feature = "std"))]
+impl From<Error> for anyhow::Error {
+  fn from(e: Error) -> Self {
+    anyhow::Error::msg(e)
+  }
+}
+
+/// Converts an error of the port.
+fn io<E: embedded_io::Error>(e: E) -> Error {
+  Error::Io(e.kind())
+}
+
+/// Key of the [`Metadata`] selecting the address of the node a [`Call`] is sent to, overriding the `target` of the [`Embedded`] backend.
+pub const TARGET: &str = "target";
+
+/// Address of all nodes on a multi-drop bus. Only notifications can be sent to it, as the replies of the nodes would collide.
+pub const BROADCAST: u8 = 0xff;
+
+/// Calls received on the port are answered by [`poll`](Embedded::poll), outgoing calls block until their reply was received.
+///
+/// The timeouts of outgoing [`Call`]s are only enforced if a [`clock`](EmbeddedBuilder::clock) is set, otherwise a call waits until its reply was received. Streaming replies are sent for streaming calls, outgoing streams and uploads are not supported.
+#[derive(derive_builder::Builder)]
+#[builder(pattern = "owned")]
+#[cfg_attr(not(feature = "std"), builder(no_std))]
+pub struct Embedded<P, C: Codec> {
+  #[builder(private, default = "PhantomData")]
+  __phantom: PhantomData<C>,
+
+  /// The port the frames are read from and written to.
+  port: P,
+
+  /// [`Framing`] of the frames sent over the port.
+  #[builder(default)]
+  framing: Framing,
+
+  /// Address of this node on a multi-drop bus. Frames addressed to other nodes are ignored.
+  ///
+  /// All nodes on the bus have to set an address, [`BROADCAST`] must not be used.
+  #[builder(setter(strip_option), default = "None")]
+  address: Option<u8>,
+
+  /// Address of the node [`Call`]s are sent to if their [`Metadata`] does not select one with the [`TARGET`] key.
+  #[builder(setter(strip_option), default = "None")]
+  target: Option<u8>,
+
+  #[builder(private, default = "0")]
+  next_id: u64,
+
+  /// Bytes read from the port, frames are kept until they are complete.
+  #[builder(private, default = "Vec::new()")]
+  read: Vec<u8>,
+
+  /// Calls received while waiting for a reply, they are answered by the next [`poll`](Embedded::poll).
+  #[builder(private, default = "VecDeque::new()")]
+  deferred: VecDeque<(Option<Route>, Vec<u8>)>,
+
+  /// Maximum number of calls received while waiting for a reply which are kept for the next [`poll`](Embedded::poll). Further calls are answered with an error. Defaults to `16`.
+  #[builder(default = "16")]
+  max_deferred: usize,
+
+  #[allow(clippy::type_complexity)]
+  #[builder(setter(name = "clock_setter", strip_option), private, default = "None")]
+  clock: Option<Box<dyn Fn() -> Duration + Send + Sync>>,
+
+  #[builder(private, default = "0")]
+  malformed: u64,
+
+  #[allow(clippy::type_complexity)]
+  #[builder(private, default = "None")]
+  receiver: Option<Box<dyn Fn(Call<C::Intermediate>) -> Result<Reply<C::Intermediate>> + Send + Sync>>,
+
+  #[allow(clippy::type_complexity)]
+  #[builder(private, default = "None")]
+  stream_receiver: Option<Box<dyn Fn(Call<C::Intermediate>) -> Result<ReplyStream<C::Intermediate>> + Send + Sync>>,
+}
+
+impl<P, C: Codec> EmbeddedBuilder<P, C> {
+  /// Uses `clock` to enforce the timeouts of outgoing [`Call`]s. It returns the time elapsed since any fixed instant, e.g. the boot of the device.
+  pub fn clock<F: Fn() -> Duration + Send + Sync + 'static>(self, clock: F) -> Self {
+    self.clock_setter(Box::new(clock))
+  }
+}
+
+impl<P, C: Codec> Embedded<P, C> {
+  /// Creates a builder for an [`Embedded`] backend using the [`Codec`] `C`, e.g. `Embedded::<_, Postcard>::builder()`.
+  pub fn builder() -> EmbeddedBuilder<P, C> {
+    EmbeddedBuilder::default()
+  }
+}
+
+impl<P: Read + ReadReady + Write, C: Codec> Embedded<P, C> {
+  /// Answers the calls received on the port without blocking. It has to be called regularly, e.g. from the main loop.
+  pub fn poll(&mut self) -> Result<()> {
+    while let Some((route, body)) = self.deferred.pop_front() {
+      self.answer(route, body)?;
+    }
+
+    loop {
+      while let Some((kind, route, body)) = self.frame() {
+        match kind {
+          framing::CALL => self.answer(route, body)?,
+          framing::REPLY => warn!("dropping reply as no call is waiting"),
+          kind => self.unsupported(kind),
+        }
+      }
+
+      if !self.port.read_ready().map_err(io)? {
+        return Ok(());
+      }
+      self.fill()?;
+    }
+  }
+
+  /// Returns the number of malformed frames which were dropped since the [`Embedded`] backend was built.
+  pub fn malformed_frames(&self) -> u64 {
+    self.malformed
+  }
+
+  /// Reads the bytes available on the port, blocking until at least one byte was read.
+  fn fill(&mut self) -> Result<()> {
+    let mut buf = [0; 64];
+
+    match self.port.read(&mut buf).map_err(io)? {
+      0 => Err(Error::Closed.into()),
+      n => {
+        trace!("read {} bytes", n);
+        self.read.extend_from_slice(&buf[..n]);
+        Ok(())
+      }
+    }
+  }
+
+  /// Removes the next complete frame for this node from the bytes read and returns its kind, [`Route`] and body.
+  fn frame(&mut self) -> Option<(u8, Option<Route>, Vec<u8>)> {
+    while let Some(frame) = self.framing.next(&mut self.read) {
+      let (kind, body) = match frame {
+        Ok(frame) => frame,
+        Err(e) => {
+          warn!("dropping malformed frame: {}", e);
+          self.malformed += 1;
+          continue;
+        }
+      };
+
+      // frames of addressed nodes start with their route, frames for other nodes are ignored
+      let address = match self.address {
+        None => return Some((kind, None, body)),
+        Some(address) => address,
+      };
+      match self.framing.route(body) {
+        Ok((route, _)) if route.destination != address && route.destination != BROADCAST => trace!("ignoring frame for node {}", route.destination),
+        Ok((route, _)) if route.destination == BROADCAST && kind != framing::CALL => {
+          warn!("dropping broadcast frame of kind {:?}", kind as char);
+          self.malformed += 1;
+        }
+        Ok((route, body)) => return Some((kind, Some(route), body)),
+        Err(e) => {
+          warn!("dropping frame without route: {}", e);
+          self.malformed += 1;
+        }
+      }
+    }
+
+    None
+  }
+
+  /// Drops a frame of a `kind` which is not answered.
+  fn unsupported(&mut self, kind: u8) {
+    match kind {
+      framing::CHUNK | framing::ACK => warn!("dropping frame of kind {:?} as uploads are not supported", kind as char),
+      kind => {
+        warn!("dropping frame of unknown kind {:?}", kind as char);
+        self.malformed += 1;
+      }
+    }
+  }
+
+  /// Answers the call in the `body` of a `c:` frame with an error without calling the receiver, as too many calls were deferred.
+  fn reject(&mut self, route: Option<Route>, body: Vec<u8>) -> Result<()> {
+    let self_call = match deserialize::<C, SelfCall<C::Intermediate>>(self.framing, &body) {
+      Ok(self_call) => self_call,
+      Err(e) => {
+        warn!("dropping malformed call: {}", e);
+        self.malformed += 1;
+        return Ok(());
+      }
+    };
+
+    warn!("rejecting call {} as {} calls are deferred", self_call.procedure, self.deferred.len());
+    let broadcast = route.is_some_and(|route| route.destination == BROADCAST);
+    if broadcast || self_call.metadata.is_notification() {
+      return Ok(());
+    }
+
+    let reply_route = self.address.zip(route).map(|(source, route)| Route { source, destination: route.source });
+    let busy = RemoteError::new(RemoteError::SERVER_ERROR, format!("{} calls are deferred while waiting for a reply", self.deferred.len()));
+    self.write(&reply_frame::<C>(self.framing, reply_route, Some(self_call.id), Err(busy), false)?)
+  }
+
+  /// Writes a frame to the port.
+  fn write(&mut self, frame: &[u8]) -> Result<()> {
+    self.port.write_all(frame).map_err(io)?;
+    self.port.flush().map_err(io)?;
+    Ok(())
+  }
+
+  /// Calls the receiver with the call in the `body` of a `c:` frame and writes the reply.
+  fn answer(&mut self, route: Option<Route>, body: Vec<u8>) -> Result<()> {
+    let broadcast = route.is_some_and(|route| route.destination == BROADCAST);
+    // replies are sent back to the source of the call
+    let reply_route = self.address.zip(route).map(|(source, route)| Route { source, destination: route.source });
+
+    let self_call = match deserialize::<C, SelfCall<C::Intermediate>>(self.framing, &body) {
+      // broadcasts are never answered as the replies would collide on the bus
+      Ok(self_call) if broadcast && !self_call.metadata.is_notification() => {
+        warn!("dropping broadcast call {} which is not a notification", self_call.procedure);
+        self.malformed += 1;
+        return Ok(());
+      }
+      Ok(self_call) => self_call,
+      Err(e) if broadcast => {
+        warn!("dropping malformed broadcast call: {}", e);
+        self.malformed += 1;
+        return Ok(());
+      }
+      Err(e) => {
+        self.malformed += 1;
+        let reply = reply_frame::<C>(self.framing, reply_route, None, Err(RemoteError::new(RemoteError::PARSE_ERROR, e)), false)?;
+        return self.write(&reply);
+      }
+    };
+
+    debug!("read call {}", self_call.procedure);
+
+    let id = self_call.id;
+    let procedure = self_call.procedure.clone();
+    let call = Call {
+      procedure: self_call.procedure,
+      payload: self_call.payload,
+      metadata: self_call.metadata,
+    };
+    let receiver = self.receiver.as_ref().ok_or(Error::NoReceiver)?;
+
+    if call.metadata.is_notification() {
+      // notifications are not answered with a `r:` frame
+      if let Err(e) = receiver(call) {
+        warn!("notification {} failed: {}", procedure, e);
+      }
+      return Ok(());
+    }
+
+    if call.metadata.is_upload() {
+      let unsupported = RemoteError::from(anyhow::Error::from(merfolk::Error::Unsupported("uploads".into())));
+      return self.write(&reply_frame::<C>(self.framing, reply_route, Some(id), Err(unsupported), false)?);
+    }
+
+    if call.metadata.is_stream() {
+      let stream = match &self.stream_receiver {
+        Some(stream_receiver) => stream_receiver(call),
+        None => receiver(call).map(|reply| ReplyStream::once(Ok(reply.payload))),
+      };
+      let items: Box<dyn Iterator<Item = Result<C::Intermediate>>> = match stream {
+        Ok(stream) => Box::new(stream),
+        Err(e) => Box::new(core::iter::once(Err(e))),
+      };
+
+      for item in items {
+        let self_reply = item.map(|payload| SelfReply { payload, metadata: Metadata::new() }).map_err(RemoteError::from);
+        self.write(&reply_frame::<C>(self.framing, reply_route, Some(id), self_reply, false)?)?;
+      }
+
+      let end = SelfReply {
+        payload: C::encode(&())?,
+        metadata: Metadata::new(),
+      };
+      return self.write(&reply_frame::<C>(self.framing, reply_route, Some(id), Ok(end), true)?);
+    }
+
+    let self_reply = receiver(call)
+      .map(|r| SelfReply {
+        payload: r.payload,
+        metadata: r.metadata,
+      })
+      .map_err(RemoteError::from);
+
+    self.write(&reply_frame::<C>(self.framing, reply_route, Some(id), self_reply, false)?)
+  }
+
+  /// Returns the [`Route`] to the node selected by the [`TARGET`] key of the [`Metadata`] or the `target` if this node is addressed. The [`TARGET`] key is removed from the `call`.
+  fn route(&self, call: &mut Call<C::Intermediate>) -> Result<Option<Route>> {
+    let target = call.metadata.remove(TARGET);
+
+    let source = match self.address {
+      None => return Ok(None),
+      Some(source) => source,
+    };
+
+    let destination = match target {
+      Some(target) => target.parse().map_err(|_| Error::InvalidTarget(target))?,
+      None => self.target.ok_or(Error::NoTarget)?,
+    };
+    if destination == BROADCAST && !call.metadata.is_notification() {
+      return Err(Error::Broadcast.into());
+    }
+
+    Ok(Some(Route { source, destination }))
+  }
+}
+
+/// Serializes a [`SelfReplyFrame`] to a `r:` frame.
+fn reply_frame<C: Codec>(framing: Framing, route: Option<Route>, id: Option<u64>, reply: Result<SelfReply<C::Intermediate>, RemoteError>, end: bool) -> Result<Vec<u8>> {
+  match serialize::<C, _>(framing, framing::REPLY, route, &SelfReplyFrame { id, reply, end }) {
+    Ok(ser) => Ok(ser),
+    Err(e) => serialize::<C, _>(
+      framing,
+      framing::REPLY,
+      route,
+      &SelfReplyFrame::<C::Intermediate> {
+        id,
+        reply: Err(RemoteError::new(RemoteError::INTERNAL_ERROR, e)),
+        end,
+      },
+    ),
+  }
+}
+
+/// Encodes `from` with the [`Codec`] `C` and wraps it in a frame of the `kind` with the [`Framing`].
+fn serialize<C: Codec, T: serde::Serialize>(framing: Framing, kind: u8, route: Option<Route>, from: &T) -> Result<Vec<u8>> {
+  trace!("serialize from");
+
+  let encoded = C::encode(from)?;
+  match framing {
+    Framing::Lines => framing.wrap(kind, route, encoded.into_line().as_bytes()),
+    _ => framing.wrap(kind, route, &encoded.into_bytes()),
+  }
+}
+
+/// Decodes the body of a frame unwrapped by the [`Framing`] with the [`Codec`] `C`.
+fn deserialize<C: Codec, T>(framing: Framing, from: &[u8]) -> Result<T>
+where
+  T: for<'de> serde::Deserialize<'de>,
+{
+  trace!("deserialize from");
+
+  let encoded = match framing {
+    Framing::Lines => C::Intermediate::from_line(core::str::from_utf8(from).map_err(|e| Error::MalformedFrame(e.to_string()))?)?,
+    _ => C::Intermediate::from_bytes(from.to_vec())?,
+  };
+  C::decode(&encoded)
+}
+
+impl<P: Read + ReadReady + Write + Send, C: Codec> Backend for Embedded<P, C> {
+  type Intermediate = C::Intermediate;
+
+  fn register<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<Self::Intermediate>) -> Result<Reply<Self::Intermediate>> + Send + Sync + 'static,
+  {
+    trace!("register receiver");
+
+    self.receiver = Some(Box::new(receiver));
+    Ok(())
+  }
+
+  fn register_stream<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<Self::Intermediate>) -> Result<ReplyStream<Self::Intermediate>> + Send + Sync + 'static,
+  {
+    trace!("register stream receiver");
+
+    self.stream_receiver = Some(Box::new(receiver));
+    Ok(())
+  }
+
+  /// Sends the `call` and blocks until its reply was received. Calls received meanwhile are answered by the next [`poll`](Embedded::poll).
+  fn call(&mut self, mut call: Call<Self::Intermediate>) -> Result<Reply<Self::Intermediate>> {
+    trace!("call {}", call.procedure);
+
+    if call.metadata.is_stream() {
+      return Err(merfolk::Error::Unsupported("streams".into()).into());
+    }
+
+    let route = self.route(&mut call)?;
+
+    let id = self.next_id;
+    self.next_id = self.next_id.wrapping_add(1);
+
+    let notification = call.metadata.is_notification();
+    let call_timeout = call.metadata.timeout();
+    let procedure = call.procedure.clone();
+
+    let frame = serialize::<C, _>(
+      self.framing,
+      framing::CALL,
+      route,
+      &SelfCall {
+        id,
+        procedure: call.procedure,
+        payload: call.payload,
+        metadata: call.metadata,
+      },
+    )?;
+    self.write(&frame)?;
+
+    if notification {
+      debug!("sent notification c: {}", id);
+
+      return Ok(Reply {
+        payload: C::encode(&())?,
+        metadata: Metadata::new(),
+      });
+    }
+
+    debug!("sent c: {}", id);
+
+    let deadline = self.clock.as_ref().zip(call_timeout).map(|(clock, timeout)| clock() + timeout);
+
+    loop {
+      let (kind, route, body) = match self.frame() {
+        Some(frame) => frame,
+        None => {
+          // without a deadline the port is read blocking, otherwise only the available bytes are read until the deadline passed
+          match (deadline, &self.clock) {
+            (Some(deadline), Some(clock)) if !self.port.read_ready().map_err(io)? => {
+              if clock() >= deadline {
+                return Err(
+                  merfolk::Error::Timeout {
+                    procedure,
+                    timeout: call_timeout.unwrap_or_default(),
+                  }
+                  .into(),
+                );
+              }
+            }
+            _ => self.fill()?,
+          }
+          continue;
+        }
+      };
+
+      match kind {
+        framing::REPLY => {
+          let frame = match deserialize::<C, SelfReplyFrame<C::Intermediate>>(self.framing, &body) {
+            Ok(frame) => frame,
+            Err(e) => {
+              warn!("dropping malformed reply: {}", e);
+              self.malformed += 1;
+              continue;
+            }
+          };
+
+          // a reply without id answers the call which could not be deserialized, which can only be the waiting call
+          if frame.id.is_some_and(|reply_id| reply_id != id) {
+            warn!("dropping reply for unknown call {:?}", frame.id);
+            continue;
+          }
+
+          let self_reply = frame.reply.map_err(|e| e.into_error(&procedure, call_timeout))?;
+          return Ok(Reply {
+            payload: self_reply.payload,
+            metadata: self_reply.metadata,
+          });
+        }
+        framing::CALL if self.deferred.len() < self.max_deferred => self.deferred.push_back((route, body)),
+        framing::CALL => self.reject(route, body)?,
+        kind => self.unsupported(kind),
+      }
+    }
+  }
+
+  fn serialize<T: serde::Serialize>(from: &T) -> Result<Self::Intermediate> {
+    C::encode(from)
+  }
+
+  fn deserialize<T>(from: &Self::Intermediate) -> Result<T>
+  where
+    T: for<'de> serde::Deserialize<'de>,
+  {
+    C::decode(from)
+  }
+}
@@ -0,0 +1,286 @@
+use std::{
+  collections::VecDeque,
+  sync::{
+    atomic::{AtomicBool, AtomicI32, Ordering},
+    mpsc, Arc, Mutex,
+  },
+  thread,
+  time::Duration,
+};
+
+use merfolk::*;
+use merfolk_backend_embedded::{Embedded, Framing};
+use merfolk_codecs::Postcard;
+
+fn add(a: i32, b: i32) -> i32 {
+  a + b
+}
+
+/// End of an in-memory pipe reading the bytes written to the other end.
+struct Pipe {
+  rx: Arc<Mutex<VecDeque<u8>>>,
+  tx: Arc<Mutex<VecDeque<u8>>>,
+}
+
+fn pipe() -> (Pipe, Pipe) {
+  let (a, b) = (Arc::new(Mutex::new(VecDeque::new())), Arc::new(Mutex::new(VecDeque::new())));
+
+  (
+    Pipe {
+      rx: Arc::clone(&a),
+      tx: Arc::clone(&b),
+    },
+    Pipe { rx: b, tx: a },
+  )
+}
+
+impl embedded_io::ErrorType for Pipe {
+  type Error = core::convert::Infallible;
+}
+
+impl embedded_io::Read for Pipe {
+  fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+    // blocks until bytes were written to the other end
+    loop {
+      let mut rx = self.rx.lock().unwrap();
+      if !rx.is_empty() {
+        let n = buf.len().min(rx.len());
+        buf.iter_mut().zip(rx.drain(..n)).for_each(|(b, r)| *b = r);
+        return Ok(n);
+      }
+      drop(rx);
+      thread::sleep(Duration::from_millis(1));
+    }
+  }
+}
+
+impl embedded_io::ReadReady for Pipe {
+  fn read_ready(&mut self) -> Result<bool, Self::Error> {
+    Ok(!self.rx.lock().unwrap().is_empty())
+  }
+}
+
+impl embedded_io::Write for Pipe {
+  fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+    self.tx.lock().unwrap().extend(buf);
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> Result<(), Self::Error> {
+    Ok(())
+  }
+}
+
+/// UART of the device connected to the host by a pseudo terminal.
+#[cfg(all(unix, not(target_arch = "arm")))]
+struct Uart(serialport::TTYPort);
+
+#[cfg(all(unix, not(target_arch = "arm")))]
+impl embedded_io::ErrorType for Uart {
+  type Error = std::io::Error;
+}
+
+#[cfg(all(unix, not(target_arch = "arm")))]
+impl embedded_io::Read for Uart {
+  fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+    loop {
+      match std::io::Read::read(&mut self.0, buf) {
+        Err(e) if e.kind() == std::io::ErrorKind::TimedOut => continue,
+        read => return read,
+      }
+    }
+  }
+}
+
+#[cfg(all(unix, not(target_arch = "arm")))]
+impl embedded_io::ReadReady for Uart {
+  fn read_ready(&mut self) -> Result<bool, Self::Error> {
+    Ok(serialport::SerialPort::bytes_to_read(&self.0)? > 0)
+  }
+}
+
+#[cfg(all(unix, not(target_arch = "arm")))]
+impl embedded_io::Write for Uart {
+  fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+    std::io::Write::write(&mut self.0, buf)
+  }
+
+  fn flush(&mut self) -> Result<(), Self::Error> {
+    std::io::Write::flush(&mut self.0)
+  }
+}
+
+#[test]
+fn register_embedded() {
+  let (port_caller, port_receiver) = pipe();
+
+  let stored = Arc::new(AtomicI32::new(0));
+  let stop = Arc::new(AtomicBool::new(false));
+
+  // the receiving device polls its backend in its main loop
+  let device = thread::spawn({
+    let (stored, stop) = (Arc::clone(&stored), Arc::clone(&stop));
+    move || {
+      let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+      register_receiver.register("add", |(a, b)| add(a, b)).unwrap();
+      register_receiver.register("store", move |value: i32| stored.store(value, Ordering::Relaxed)).unwrap();
+
+      let merfolk_receiver = Mer::builder()
+        .backend(Embedded::<_, Postcard>::builder().port(port_receiver).build().unwrap())
+        .frontend(register_receiver)
+        .build()
+        .unwrap();
+
+      while !stop.load(Ordering::Relaxed) {
+        merfolk_receiver.backend(|b| b.poll()).unwrap().unwrap();
+        thread::sleep(Duration::from_millis(1));
+      }
+    }
+  });
+
+  let merfolk_caller = Mer::builder()
+    .backend(Embedded::<_, Postcard>::builder().port(port_caller).build().unwrap())
+    .frontend(merfolk_frontend_register::Register::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  let (a, b) = (rand::random::<i32>() / 2, rand::random::<i32>() / 2);
+  let result: i32 = merfolk_caller.frontend(|f| f.call("add", &(a, b)).unwrap()).unwrap();
+  assert_eq!(result, a + b);
+
+  merfolk_caller.frontend(|f| f.notify("store", &42).unwrap()).unwrap();
+
+  let err = merfolk_caller.frontend(|f| f.call::<_, i32>("missing", &()).unwrap_err()).unwrap();
+  assert_eq!(err.downcast_ref::<RemoteError>().unwrap().code, RemoteError::PROCEDURE_NOT_FOUND);
+
+  // the notification was answered before the following call
+  assert_eq!(stored.load(Ordering::Relaxed), 42);
+
+  stop.store(true, Ordering::Relaxed);
+  device.join().unwrap();
+}
+
+/// Connects a device using the [`Embedded`] backend to a host using the `SerialPort` backend, both call procedures of the other side.
+#[cfg(all(unix, not(target_arch = "arm")))]
+fn serialport_embedded(framing: Framing, addresses: Option<(u8, u8)>) {
+  use merfolk_backend_serialport::SerialPort;
+
+  let (port_host, port_device) = serialport::TTYPort::pair().unwrap();
+
+  let (results, result) = mpsc::channel();
+  let stop = Arc::new(AtomicBool::new(false));
+
+  let device = thread::spawn({
+    let stop = Arc::clone(&stop);
+    move || {
+      let register_device = merfolk_frontend_register::Register::builder().build().unwrap();
+      register_device.register("add", |(a, b)| add(a, b)).unwrap();
+
+      let mut backend = Embedded::<_, Postcard>::builder().port(Uart(port_device)).framing(framing);
+      if let Some((host, device)) = addresses {
+        backend = backend.address(device).target(host);
+      }
+
+      let merfolk_device = Mer::builder().backend(backend.build().unwrap()).frontend(register_device).build().unwrap();
+
+      results.send(merfolk_device.frontend(|f| f.call::<_, i32>("double", &21)).unwrap()).unwrap();
+
+      while !stop.load(Ordering::Relaxed) {
+        merfolk_device.backend(|b| b.poll()).unwrap().unwrap();
+        thread::sleep(Duration::from_millis(1));
+      }
+    }
+  });
+
+  let register_host = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_host.register("double", |a: i32| a * 2).unwrap();
+
+  let mut backend = SerialPort::<Postcard>::builder_with_codec().port(port_host).framing(framing);
+  if let Some((host, device)) = addresses {
+    backend = backend.address(host).target(device);
+  }
+
+  let merfolk_host = Mer::builder().backend(backend.build().unwrap()).frontend(register_host).build().unwrap();
+
+  assert_eq!(result.recv_timeout(Duration::from_secs(5)).unwrap().unwrap(), 42);
+
+  let (a, b) = (rand::random::<i32>() / 2, rand::random::<i32>() / 2);
+  let sum: i32 = merfolk_host.frontend(|f| f.call("add", &(a, b)).unwrap()).unwrap();
+  assert_eq!(sum, a + b);
+
+  // streaming calls are answered with the reply as single item
+  let items = merfolk_host
+    .frontend(|f| f.call_stream::<_, i32>("add", &(1, 2)).unwrap().collect::<Result<Vec<_>, _>>().unwrap())
+    .unwrap();
+  assert_eq!(items, vec![3]);
+
+  stop.store(true, Ordering::Relaxed);
+  device.join().unwrap();
+}
+
+#[test]
+#[cfg(all(unix, not(target_arch = "arm")))]
+fn serialport_lines_embedded() {
+  serialport_embedded(Framing::Lines, None);
+}
+
+#[test]
+#[cfg(all(unix, not(target_arch = "arm")))]
+fn serialport_cobs_embedded() {
+  serialport_embedded(Framing::Cobs, Some((1, 2)));
+}
+
+#[test]
+#[cfg(all(unix, not(target_arch = "arm")))]
+fn serialport_length_prefix_embedded() {
+  serialport_embedded(Framing::LengthPrefix, Some((1, 2)));
+}
+
+#[test]
+fn timeout_embedded() {
+  use merfolk::interfaces::{Codec, Encoded};
+
+  let (port_caller, port_peer) = pipe();
+
+  let register = merfolk_frontend_register::Register::builder().build().unwrap();
+  register.register("add", |(a, b)| add(a, b)).unwrap();
+
+  let start = std::time::Instant::now();
+  let merfolk_caller = Mer::builder()
+    .backend(Embedded::<_, Postcard>::builder().port(port_caller).clock(move || start.elapsed()).max_deferred(2).build().unwrap())
+    .frontend(register)
+    .timeout(Duration::from_millis(50))
+    .build()
+    .unwrap();
+
+  // the peer sends calls but never replies
+  for id in 1..=3_u64 {
+    let call = Postcard::encode(&(id, "add", Postcard::encode(&(id as i32, 1)).unwrap(), Metadata::new())).unwrap();
+    port_peer.tx.lock().unwrap().extend([b"c:", call.into_line().as_bytes(), b"\r\n"].concat());
+  }
+
+  let err = merfolk_caller.frontend(|f| f.call::<_, i32>("add", &(1, 2)).unwrap_err()).unwrap();
+  assert!(matches!(err.downcast_ref::<merfolk::Error>(), Some(merfolk::Error::Timeout { .. })));
+  assert!(start.elapsed() < Duration::from_millis(500));
+
+  // the deferred calls are answered by the next poll
+  merfolk_caller.backend(|b| b.poll()).unwrap().unwrap();
+
+  type ReplyFrame = (Option<u64>, Result<(Vec<u8>, Metadata), RemoteError>, bool);
+
+  let written: Vec<u8> = port_peer.rx.lock().unwrap().drain(..).collect();
+  let replies: Vec<ReplyFrame> = written
+    .split(|b| *b == b'\n')
+    .filter_map(|line| line.strip_prefix(b"r:"))
+    .map(|line| Postcard::decode(&Vec::<u8>::from_line(std::str::from_utf8(line).unwrap().trim_end()).unwrap()).unwrap())
+    .collect();
+
+  // the call which exceeded the deferred calls is rejected while waiting
+  assert_eq!(replies.len(), 3);
+  assert_eq!(replies[0].0, Some(3));
+  assert_eq!(replies[0].1.as_ref().unwrap_err().code, RemoteError::SERVER_ERROR);
+  for (reply, id) in replies[1..].iter().zip(1..) {
+    assert_eq!(reply.0, Some(id));
+    assert_eq!(Postcard::decode::<i32>(&reply.1.as_ref().unwrap().0).unwrap(), id as i32 + 1);
+  }
+}
@@ -1,21 +1,36 @@
-use std::{fmt::Debug, net::SocketAddr, sync::Arc};
+use std::{
+  fmt::Debug,
+  future::Future,
+  marker::PhantomData,
+  net::SocketAddr,
+  sync::{mpsc, Arc, Mutex},
+  time::Duration,
+};
 
 use anyhow::Result;
 use hyper::{
+  body::HttpBody,
   client::{connect::dns::GaiResolver, HttpConnector},
-  http::Uri,
+  header::{HeaderMap, HeaderName, HeaderValue},
+  http::{request, response, Uri},
   service::{make_service_fn, service_fn},
   Body, Client, Method, Request, Response, Server, StatusCode,
 };
-use log::{debug, info, trace};
-use merfolk::{interfaces::Backend, Call, Reply};
+use log::{debug, info, trace, warn};
+use merfolk::{
+  helpers::future::BoxFuture,
+  interfaces::{AsyncBackend, Backend, Codec, Encoded},
+  Call, CallStream, Metadata, RemoteError, Reply, ReplyStream,
+};
+use merfolk_codecs::Json;
 use thiserror::Error;
-use tokio::{runtime::Runtime, sync};
+use tokio::{
+  runtime::{Handle, Runtime},
+  sync,
+};
 
 #[derive(Debug, Error)]
 pub enum Error {
-  #[error("serializing failed: {0}")]
-  Serialize(#[source] serde_json::Error),
   #[error("deserializing failed {0}")]
   Deserialize(#[source] serde_json::Error),
   #[error("no speak provided in init()")]
@@ -40,17 +55,28 @@ pub enum Error {
   NoProcedureHeader(#[source] hyper::http::Error),
   #[error("could not create runtime: {0}")]
   RuntimeCreation(#[from] std::io::Error),
+  #[error("not running in a runtime: {0}")]
+  NoRuntime(#[from] tokio::runtime::TryCurrentError),
   #[error("already started")]
   AlreadyStarted,
   #[error("not started")]
   NotStarted,
   #[error("could not send stoping message")]
   Shutdown,
+  #[error("stream or upload receiver lock was poisoned")]
+  Lock,
+  #[error("stream was closed before its end")]
+  StreamClosed,
+  #[error("metadata {key:?} = {value:?} can not be sent as http header")]
+  InvalidMetadata { key: String, value: String },
 }
 
 #[derive(derive_builder::Builder)]
 #[builder(pattern = "owned")]
-pub struct Http {
+pub struct Http<C: Codec = Json> {
+  #[builder(private, default = "PhantomData")]
+  __phantom: PhantomData<C>,
+
   #[builder(setter(into, strip_option, name = "speak_setter"), private, default = "None")]
   speak: Option<(Uri, Client<HttpConnector<GaiResolver>, Body>)>,
 
@@ -59,28 +85,51 @@ pub struct Http {
 
   #[allow(clippy::type_complexity)]
   #[builder(private, default = "None")]
-  receiver: Option<Arc<dyn Fn(Call<String>) -> Result<Reply<String>> + Send + Sync>>,
+  receiver: Option<Arc<dyn Fn(Call<C::Intermediate>) -> BoxFuture<'static, Result<Reply<C::Intermediate>>> + Send + Sync>>,
 
-  #[builder(private, default = "Runtime::new().map_err(Error::RuntimeCreation).map_err(|e| e.to_string())?")]
-  runtime: Runtime,
+  #[allow(clippy::type_complexity)]
+  #[builder(private, default = "Arc::new(Mutex::new(None))")]
+  stream_receiver: Arc<Mutex<Option<Arc<dyn Fn(Call<C::Intermediate>) -> Result<ReplyStream<C::Intermediate>> + Send + Sync>>>>,
+
+  #[allow(clippy::type_complexity)]
+  #[builder(private, default = "Arc::new(Mutex::new(None))")]
+  upload_receiver: Arc<Mutex<Option<Arc<UploadReceiver<C::Intermediate>>>>>,
+
+  #[builder(private, default = "None")]
+  runtime: Option<Runtime>,
 
   #[builder(private, default = "None")]
   shutdown: Option<sync::oneshot::Sender<()>>,
 }
 
-impl HttpBuilder {
+impl<C: Codec> HttpBuilder<C> {
   pub fn speak(self, value: Uri) -> Self {
     self.speak_setter((value, Client::new()))
   }
 }
 
 impl Http {
-  pub fn builder() -> HttpBuilder {
+  /// Creates a builder for a [`Http`] backend using the [`Json`] [`Codec`].
+  pub fn builder() -> HttpBuilder<Json> {
+    HttpBuilder::default()
+  }
+}
+
+impl<C: Codec> Http<C> {
+  /// Creates a builder for a [`Http`] backend using the [`Codec`] `C`.
+  ///
+  /// The payloads are carried as binary request and response bodies.
+  ///
+  /// ```
+  /// # use merfolk_backend_http::Http;
+  /// let http = Http::<merfolk_codecs::MessagePack>::builder_with_codec().build().unwrap();
+  /// ```
+  pub fn builder_with_codec() -> HttpBuilder<C> {
     HttpBuilder::default()
   }
 }
 
-impl Debug for Http {
+impl<C: Codec> Debug for Http<C> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
     f.debug_struct("Http")
       .field("speak", &self.speak)
@@ -90,8 +139,226 @@ impl Debug for Http {
   }
 }
 
-impl Http {
+/// Server callback for uploads registered by [`Backend::register_upload`].
+type UploadReceiver<T> = dyn Fn(Call<T>, CallStream<T>) -> Result<Reply<T>> + Send + Sync;
+
+/// Prefix of the Http headers carrying the [`Metadata`] of [`Call`]s and [`Reply`]s.
+const METADATA_HEADER_PREFIX: &str = "metadata-";
+
+/// Collects the [`Metadata`] from the `Metadata-*` headers.
+fn metadata_from_headers(headers: &HeaderMap) -> Metadata {
+  headers
+    .iter()
+    .filter_map(|(name, value)| Some((name.as_str().strip_prefix(METADATA_HEADER_PREFIX)?, value.to_str().ok()?)))
+    .collect()
+}
+
+/// Checks that every entry of the [`Metadata`] can be sent as `Metadata-*` header.
+fn check_metadata(metadata: &Metadata) -> std::result::Result<(), Error> {
+  match metadata
+    .iter()
+    .find(|(key, value)| HeaderName::from_bytes((METADATA_HEADER_PREFIX.to_string() + key).as_bytes()).is_err() || HeaderValue::from_str(value).is_err())
+  {
+    Some((key, value)) => Err(Error::InvalidMetadata {
+      key: key.to_string(),
+      value: value.to_string(),
+    }),
+    None => Ok(()),
+  }
+}
+
+/// Adds the [`Metadata`] as `Metadata-*` headers to the [`Request`].
+fn request_with_metadata(builder: request::Builder, metadata: &Metadata) -> request::Builder {
+  metadata.iter().fold(builder, |builder, (key, value)| builder.header(METADATA_HEADER_PREFIX.to_string() + key, value))
+}
+
+/// Adds the [`Metadata`] as `Metadata-*` headers to the [`Response`].
+fn response_with_metadata(builder: response::Builder, metadata: &Metadata) -> response::Builder {
+  metadata.iter().fold(builder, |builder, (key, value)| builder.header(METADATA_HEADER_PREFIX.to_string() + key, value))
+}
+
+/// Builds the [`Response`] for a [`Reply`]. Fails with an error [`Response`] if the [`Metadata`] can not be sent as headers.
+fn reply_response<T: Encoded>(reply: Reply<T>) -> std::result::Result<Response<Body>, hyper::http::Error> {
+  if let Err(e) = check_metadata(&reply.metadata) {
+    return error_response(anyhow::Error::from(e).into());
+  }
+
+  response_with_metadata(Response::builder(), &reply.metadata)
+    .status(StatusCode::OK)
+    .body(Body::from(reply.payload.into_bytes()))
+}
+
+/// Builds the [`Response`] for a [`RemoteError`]. The status code is derived from the `code` of the [`RemoteError`].
+fn error_response(error: RemoteError) -> std::result::Result<Response<Body>, hyper::http::Error> {
+  let status = match error.code {
+    RemoteError::PARSE_ERROR | RemoteError::INVALID_PARAMS => StatusCode::BAD_REQUEST,
+    RemoteError::AUTHENTICATION_FAILED => StatusCode::UNAUTHORIZED,
+    RemoteError::PROCEDURE_NOT_FOUND => StatusCode::NOT_FOUND,
+    RemoteError::TIMEOUT => StatusCode::GATEWAY_TIMEOUT,
+    _ => StatusCode::INTERNAL_SERVER_ERROR,
+  };
+
+  match serde_json::to_string(&error) {
+    Ok(body) => Response::builder().status(status).header("Content-Type", "application/json").body(Body::from(body)),
+    Err(e) => Response::builder().status(StatusCode::INTERNAL_SERVER_ERROR).body(Body::from(e.to_string())),
+  }
+}
+
+/// Event of a server-sent event stream carrying an item of a [`ReplyStream`].
+const STREAM_ITEM: &str = "item";
+/// Event of a server-sent event stream carrying a failed item of a [`ReplyStream`] as [`RemoteError`].
+const STREAM_ERROR: &str = "error";
+/// Event of a server-sent event stream marking the end of a [`ReplyStream`].
+const STREAM_END: &str = "end";
+
+/// Formats a server-sent event.
+fn stream_event(event: &str, data: &str) -> String {
+  let mut formatted = format!("event: {}\n", event);
+  for line in data.split('\n') {
+    formatted += &format!("data: {}\n", line);
+  }
+  formatted + "\n"
+}
+
+/// Parses a server-sent event into its event and data.
+fn parse_stream_event(formatted: &str) -> (&str, String) {
+  let mut event = "";
+  let mut data = vec![];
+  for line in formatted.lines() {
+    if let Some(value) = line.strip_prefix("event:") {
+      event = value.trim_start();
+    } else if let Some(value) = line.strip_prefix("data:") {
+      data.push(value.strip_prefix(' ').unwrap_or(value));
+    }
+  }
+  (event, data.join("\n"))
+}
+
+/// Builds the [`Response`] sending the items of the [`ReplyStream`] as server-sent events as they are produced.
+fn stream_response<T: Encoded + Send + 'static>(stream: ReplyStream<T>) -> std::result::Result<Response<Body>, hyper::http::Error> {
+  let (mut sender, body) = Body::channel();
+  let handle = Handle::current();
+
+  // the items are produced by a blocking iterator
+  tokio::task::spawn_blocking(move || {
+    for item in stream {
+      let event = match item {
+        Ok(payload) => stream_event(STREAM_ITEM, &payload.into_line()),
+        Err(e) => stream_event(STREAM_ERROR, &serde_json::to_string(&RemoteError::<String>::from(e)).unwrap_or_default()),
+      };
+
+      if handle.block_on(sender.send_data(event.into())).is_err() {
+        debug!("client closed stream");
+        return;
+      }
+    }
+    handle.block_on(sender.send_data(stream_event(STREAM_END, "").into())).ok();
+  });
+
+  Response::builder().status(StatusCode::OK).header("Content-Type", "text/event-stream").body(body)
+}
+
+/// Reads the `body` until the next line break and returns the line without it. Returns the remaining bytes as last line when the `body` ends.
+async fn read_line(body: &mut Body, buffer: &mut Vec<u8>) -> Option<Result<String>> {
+  loop {
+    if let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+      let line: Vec<u8> = buffer.drain(..=end).take(end).collect();
+      return Some(String::from_utf8(line).map_err(|e| Error::ParseResponseBody(e).into()));
+    }
+
+    match body.data().await {
+      Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+      Some(Err(e)) => return Some(Err(Error::ParseResponseBodyBytes(e).into())),
+      None if buffer.is_empty() => return None,
+      None => return Some(String::from_utf8(std::mem::take(buffer)).map_err(|e| Error::ParseResponseBody(e).into())),
+    }
+  }
+}
+
+/// Receives an upload and builds the [`Response`] with its [`Reply`].
+///
+/// The first line of the `body` carries the payload of the [`Call`], every following line carries a chunk of the [`CallStream`].
+/// The lines are read from the `body` while the procedure consumes them, so the client side is slowed down by the flow control of Http.
+async fn upload_response<T>(procedure: String, metadata: Metadata, mut body: Body, upload_receiver: Option<Arc<UploadReceiver<T>>>) -> std::result::Result<Response<Body>, hyper::http::Error>
+where
+  T: Encoded + Debug + Send + 'static,
+{
+  let mut buffer: Vec<u8> = vec![];
+
+  let payload = match read_line(&mut body, &mut buffer).await {
+    Some(Ok(payload)) => match T::from_line(&payload) {
+      Ok(payload) => payload,
+      Err(e) => return error_response(RemoteError::new(RemoteError::PARSE_ERROR, e)),
+    },
+    Some(Err(e)) => return error_response(RemoteError::new(RemoteError::PARSE_ERROR, e)),
+    None => return error_response(RemoteError::new(RemoteError::PARSE_ERROR, "No payload provided")),
+  };
+
+  let upload_receiver = match upload_receiver {
+    Some(upload_receiver) => upload_receiver,
+    None => return error_response(anyhow::Error::from(merfolk::Error::Unsupported("uploads".into())).into()),
+  };
+
+  let (tx, mut rx) = sync::mpsc::channel::<Result<T>>(1);
+  tokio::spawn(async move {
+    while let Some(line) = read_line(&mut body, &mut buffer).await {
+      if tx.send(line.and_then(|line| T::from_line(&line))).await.is_err() {
+        debug!("upload was dropped");
+        return;
+      }
+    }
+  });
+
+  debug!("call Call {{ procedure: {:?}, payload: {:?} }}", &procedure, &payload);
+  let call_timeout = metadata.timeout();
+  let call = Call {
+    procedure: procedure.clone(),
+    payload,
+    metadata,
+  };
+
+  // the chunks are consumed by a blocking iterator
+  let reply = timeout(&procedure, call_timeout, async {
+    tokio::task::spawn_blocking(move || upload_receiver(call, CallStream::new(std::iter::from_fn(move || rx.blocking_recv())))).await?
+  })
+  .await;
+
+  match reply {
+    Err(e) => error_response(e.into()),
+    Ok(reply) => reply_response(reply),
+  }
+}
+
+/// Awaits the `future` and fails with [`merfolk::Error::Timeout`] if the `timeout` elapses first.
+async fn timeout<T>(procedure: &str, timeout: Option<Duration>, future: impl Future<Output = Result<T>>) -> Result<T> {
+  match timeout {
+    None => future.await,
+    Some(timeout) => tokio::time::timeout(timeout, future).await.map_err(|_| merfolk::Error::Timeout {
+      procedure: procedure.to_string(),
+      timeout,
+    })?,
+  }
+}
+
+/// Returns the private [`Runtime`] used by the synchronous [`Backend`] implementation. The [`Runtime`] is created on first use.
+fn runtime(runtime: &mut Option<Runtime>) -> Result<&Runtime> {
+  if runtime.is_none() {
+    *runtime = Some(Runtime::new().map_err(Error::RuntimeCreation)?);
+  }
+
+  Ok(runtime.as_ref().unwrap())
+}
+
+impl<C: Codec> Http<C> {
+  /// Starts the server on the private [`Runtime`].
   pub fn start(&mut self) -> Result<()> {
+    let handle = runtime(&mut self.runtime)?.handle().clone();
+
+    self.start_on(&handle)
+  }
+
+  /// Starts the server on the [`Runtime`] of the `handle`.
+  pub fn start_on(&mut self, handle: &Handle) -> Result<()> {
     trace!("start Http Backend");
 
     if self.shutdown.is_some() {
@@ -105,8 +372,10 @@ impl Http {
     let listen = self.listen.ok_or(Error::NoListen)?;
 
     let receiver = Arc::clone(self.receiver.as_ref().ok_or(Error::NoReceiver)?);
+    let stream_receiver = Arc::clone(&self.stream_receiver);
+    let upload_receiver = Arc::clone(&self.upload_receiver);
 
-    self.runtime.spawn(async move {
+    handle.spawn(async move {
       trace!("spawn listener");
 
       let receiver = receiver.clone();
@@ -116,6 +385,8 @@ impl Http {
           trace!("serve connection");
 
           let receiver = receiver.clone();
+          let stream_receiver = stream_receiver.clone();
+          let upload_receiver = upload_receiver.clone();
           async move {
             Ok::<_, hyper::Error>(service_fn(move |request: Request<Body>| {
               trace!("run service_fn");
@@ -125,35 +396,72 @@ impl Http {
               info!("received incomming call");
 
               let receiver = receiver.clone();
+              let stream_receiver = stream_receiver.lock().ok().and_then(|r| r.clone());
+              let upload_receiver = upload_receiver.lock().ok().and_then(|r| r.clone());
               async move {
                 let procedure = if let Some(procedure) = request.headers().get("Procedure") {
                   match procedure.to_str() {
                     Ok(procedure) => procedure.to_owned(),
-                    Err(e) => return Response::builder().status(StatusCode::BAD_REQUEST).body(Body::from(e.to_string())),
+                    Err(e) => return error_response(RemoteError::new(RemoteError::PARSE_ERROR, e)),
                   }
                 } else {
-                  return Response::builder().status(StatusCode::BAD_REQUEST).body(Body::from("No Procedure provided"));
+                  return error_response(RemoteError::new(RemoteError::PARSE_ERROR, "No Procedure provided"));
                 };
 
+                let metadata = metadata_from_headers(request.headers());
+
+                if metadata.is_upload() {
+                  return upload_response(procedure, metadata, request.into_body(), upload_receiver).await;
+                }
+
                 let body_bytes = match hyper::body::to_bytes(request.into_body()).await {
                   Ok(body_bytes) => body_bytes,
-                  Err(e) => return Response::builder().status(StatusCode::BAD_REQUEST).body(Body::from(e.to_string())),
+                  Err(e) => return error_response(RemoteError::new(RemoteError::PARSE_ERROR, e)),
                 };
 
-                let body = match String::from_utf8(body_bytes.to_vec()) {
+                let body = match C::Intermediate::from_bytes(body_bytes.to_vec()) {
                   Ok(body) => body,
-                  Err(e) => return Response::builder().status(StatusCode::BAD_REQUEST).body(Body::from(e.to_string())),
+                  Err(e) => return error_response(RemoteError::new(RemoteError::PARSE_ERROR, e)),
                 };
 
                 debug!("call Call {{ procedure: {:?}, payload: {:?} }}", &procedure, &body);
-                let reply = receiver(crate::Call { procedure, payload: body });
+                let call_timeout = metadata.timeout();
+                let call = crate::Call {
+                  procedure: procedure.clone(),
+                  payload: body,
+                  metadata,
+                };
+
+                if call.metadata.is_stream() {
+                  let stream = match stream_receiver {
+                    Some(stream_receiver) => timeout(&procedure, call_timeout, async { tokio::task::spawn_blocking(move || stream_receiver(call)).await? }).await,
+                    None => timeout(&procedure, call_timeout, receiver(call)).await.map(|reply| ReplyStream::once(Ok(reply.payload))),
+                  };
+
+                  return match stream {
+                    Err(e) => error_response(e.into()),
+                    Ok(stream) => stream_response(stream),
+                  };
+                }
+
+                // notifications are accepted before the procedure runs
+                if call.metadata.is_notification() {
+                  tokio::spawn(async move {
+                    if let Err(e) = timeout(&procedure, call_timeout, receiver(call)).await {
+                      warn!("notification {} failed: {}", procedure, e);
+                    }
+                  });
+                  return Response::builder().status(StatusCode::ACCEPTED).body(Body::empty());
+                }
+
+                let reply = timeout(&procedure, call_timeout, receiver(call)).await;
 
                 match reply {
-                  Err(e) => Response::builder().status(StatusCode::BAD_REQUEST).body(Body::from(format!("{:?}", e))),
+                  Err(e) => error_response(e.into()),
 
                   Ok(reply) => {
                     debug!("reply Reply {{ payload: {:?} }}", &reply.payload);
-                    Response::builder().status(StatusCode::OK).body(Body::from(reply.payload))
+                    reply_response(reply)
                   }
                 }
               }
@@ -173,10 +481,197 @@ impl Http {
     trace!("stop http backend");
     self.shutdown.take().ok_or(Error::NotStarted)?.send(()).map_err(|_| Error::Shutdown.into())
   }
+
+  fn ignore_start_error(started: Result<()>) -> Result<()> {
+    if let Some(err) = started.err().map(|e| e.downcast::<Error>()) {
+      match err {
+        Ok(err) => match err {
+          Error::AlreadyStarted | Error::NoListen | Error::NoReceiver => {}
+          err => return Err(err.into()),
+        },
+        Err(err) => return Err(err),
+      }
+    };
+
+    Ok(())
+  }
+
+  async fn request(speak: &(Uri, Client<HttpConnector<GaiResolver>, Body>), call: Call<C::Intermediate>) -> Result<Reply<C::Intermediate>> {
+    let procedure = call.procedure.clone();
+    let call_timeout = call.metadata.timeout();
+
+    timeout(&procedure, call_timeout, async {
+      let response = Self::send(speak, call).await?;
+
+      Self::reply(response, &procedure, call_timeout).await
+    })
+    .await
+  }
+
+  async fn request_upload(speak: &(Uri, Client<HttpConnector<GaiResolver>, Body>), call: Call<C::Intermediate>, chunks: CallStream<C::Intermediate>) -> Result<Reply<C::Intermediate>> {
+    let procedure = call.procedure.clone();
+    let call_timeout = call.metadata.timeout();
+
+    check_metadata(&call.metadata)?;
+
+    let (mut sender, body) = Body::channel();
+    let request = request_with_metadata(Request::builder(), &call.metadata)
+      .method(Method::POST)
+      .uri(&speak.0)
+      .header("Procedure", &call.procedure)
+      .header("Content-Type", "application/x-ndjson")
+      .body(body)
+      .map_err(Error::RequestBuilder)?;
+
+    debug!("request {:?}", &request);
+    let response = tokio::spawn(speak.1.request(request));
+
+    // sending the lines counts towards the timeout, as sending waits until the server side has read the lines sent before
+    timeout(&procedure, call_timeout, async {
+      if sender.send_data(format!("{}\n", call.payload.into_line()).into()).await.is_ok() {
+        for chunk in chunks {
+          match chunk {
+            Ok(chunk) => {
+              if sender.send_data(format!("{}\n", chunk.into_line()).into()).await.is_err() {
+                debug!("server closed upload");
+                break;
+              }
+            }
+            Err(e) => {
+              sender.abort();
+              return Err(e);
+            }
+          }
+        }
+      }
+      drop(sender);
+
+      let response = response.await?.map_err(Error::ClientRequest)?;
+      debug!("response {:?}", &response);
+
+      Self::reply(response, &procedure, call_timeout).await
+    })
+    .await
+  }
+
+  async fn request_stream(speak: &(Uri, Client<HttpConnector<GaiResolver>, Body>), call: Call<C::Intermediate>) -> Result<ReplyStream<C::Intermediate>> {
+    let procedure = call.procedure.clone();
+    let call_timeout = call.metadata.timeout();
+
+    let response = timeout(&procedure, call_timeout, Self::send(speak, call)).await?;
+
+    let status = response.status();
+    if status != StatusCode::OK {
+      let body = Self::body(response).await?;
+      return Err(Self::failed(status, &body, &procedure, call_timeout));
+    }
+
+    let (tx, rx) = mpsc::channel::<Result<C::Intermediate>>();
+    let mut body = response.into_body();
+
+    let stream_procedure = procedure.clone();
+    tokio::spawn(async move {
+      let mut buffer: Vec<u8> = vec![];
+
+      while let Some(chunk) = body.data().await {
+        match chunk {
+          Ok(chunk) => buffer.extend_from_slice(&chunk),
+          Err(e) => {
+            tx.send(Err(Error::ParseResponseBodyBytes(e).into())).ok();
+            return;
+          }
+        }
+
+        while let Some(end) = buffer.windows(2).position(|w| w == b"\n\n") {
+          let formatted: Vec<u8> = buffer.drain(..end + 2).collect();
+          let formatted = match String::from_utf8(formatted) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+              tx.send(Err(Error::ParseResponseBody(e).into())).ok();
+              continue;
+            }
+          };
+
+          let item = match parse_stream_event(&formatted) {
+            (STREAM_ITEM, data) => C::Intermediate::from_line(&data),
+            (STREAM_ERROR, data) => match serde_json::from_str::<RemoteError>(&data) {
+              Ok(remote) => Err(remote.into_error(&stream_procedure, call_timeout)),
+              Err(e) => Err(Error::Deserialize(e).into()),
+            },
+            (STREAM_END, _) => return,
+            (event, _) => {
+              warn!("dropping unknown stream event {}", event);
+              continue;
+            }
+          };
+
+          if tx.send(item).is_err() {
+            debug!("stream was dropped");
+            return;
+          }
+        }
+      }
+
+      tx.send(Err(Error::StreamClosed.into())).ok();
+    });
+
+    Ok(ReplyStream::receive(rx, procedure, call_timeout, |item| item))
+  }
+
+  /// Sends the [`Call`] as [`Request`].
+  async fn send(speak: &(Uri, Client<HttpConnector<GaiResolver>, Body>), call: Call<C::Intermediate>) -> Result<Response<Body>> {
+    check_metadata(&call.metadata)?;
+
+    let request = request_with_metadata(Request::builder(), &call.metadata)
+      .method(Method::POST)
+      .uri(&speak.0)
+      .header("Procedure", &call.procedure)
+      .body(Body::from(call.payload.into_bytes()))
+      .map_err(Error::RequestBuilder)?;
+
+    debug!("request {:?}", &request);
+    let response = speak.1.request(request).await.map_err(Error::ClientRequest)?;
+    debug!("response {:?}", &response);
+
+    Ok(response)
+  }
+
+  /// Reads the [`Reply`] from the [`Response`].
+  async fn reply(response: Response<Body>, procedure: &str, call_timeout: Option<Duration>) -> Result<Reply<C::Intermediate>> {
+    let status = response.status();
+    let metadata = metadata_from_headers(response.headers());
+    let body = Self::body(response).await?;
+
+    match status {
+      StatusCode::OK => Ok(Reply {
+        payload: C::Intermediate::from_bytes(body)?,
+        metadata,
+      }),
+      StatusCode::ACCEPTED => Ok(Reply {
+        payload: <Self as Backend>::serialize(&())?,
+        metadata,
+      }),
+      _ => Err(Self::failed(status, &body, procedure, call_timeout)),
+    }
+  }
+
+  /// Reads the whole body of the [`Response`].
+  async fn body(response: Response<Body>) -> Result<Vec<u8>> {
+    let body_bytes = hyper::body::to_bytes(response.into_body()).await.map_err(Error::ParseResponseBodyBytes)?;
+    Ok(body_bytes.to_vec())
+  }
+
+  /// Returns the error of a failed [`Response`] with the `status` and the `body`.
+  fn failed(status: StatusCode, body: &[u8], procedure: &str, call_timeout: Option<Duration>) -> anyhow::Error {
+    match serde_json::from_slice::<RemoteError>(body) {
+      Ok(remote) => remote.into_error(procedure, call_timeout),
+      Err(_) => Error::FailedRequest { status }.into(),
+    }
+  }
 }
 
-impl Backend for Http {
-  type Intermediate = String;
+impl<C: Codec> Backend for Http<C> {
+  type Intermediate = C::Intermediate;
 
   fn register<T>(&mut self, receiver: T) -> Result<()>
   where
@@ -184,24 +679,19 @@ impl Backend for Http {
   {
     trace!("register receiver");
 
-    self.receiver = Some(Arc::new(move |call: Call<String>| {
+    let receiver = Arc::new(receiver);
+    self.receiver = Some(Arc::new(move |call: Call<C::Intermediate>| {
       trace!("run receiver");
 
-      debug!("calling receiver");
-      receiver(call)
+      let receiver = Arc::clone(&receiver);
+      // the blocking receiver runs on its own thread so the timeout of the call can elapse, a late reply is dropped
+      Box::pin(async move {
+        debug!("calling receiver");
+        tokio::task::spawn_blocking(move || receiver(call)).await?
+      })
     }));
 
-    if let Some(err) = self.start().err().map(|e| e.downcast::<Error>()) {
-      match err {
-        Ok(err) => match err {
-          Error::AlreadyStarted | Error::NoListen | Error::NoReceiver => {}
-          err => return Err(err.into()),
-        },
-        Err(err) => return Err(err),
-      }
-    };
-
-    Ok(())
+    Self::ignore_start_error(self.start())
   }
 
   fn call(&mut self, call: Call<Self::Intermediate>) -> Result<Reply<Self::Intermediate>> {
@@ -214,34 +704,58 @@ impl Backend for Http {
     match &self.speak {
       None => Err(Error::NoSpeak.into()),
 
-      Some(speak) => self.runtime.block_on(async {
-        let request = Request::builder()
-          .method(Method::POST)
-          .uri(&speak.0)
-          .header("Procedure", &call.procedure)
-          .body(Body::from(call.payload.clone()))
-          .map_err(Error::RequestBuilder)?;
-
-        debug!("request {:?}", &request);
-        let response = speak.1.request(request).await.map_err(Error::ClientRequest)?;
-        debug!("response {:?}", &response);
-
-        let status = response.status();
-        let body_bytes = hyper::body::to_bytes(response.into_body()).await.map_err(Error::ParseResponseBodyBytes)?;
-        let body = String::from_utf8(body_bytes.to_vec()).map_err(Error::ParseResponseBody)?;
-
-        match status {
-          StatusCode::OK => Ok(Reply { payload: body }),
-          _ => Err(Error::FailedRequest { status }.into()),
-        }
-      }),
+      Some(speak) => runtime(&mut self.runtime)?.block_on(Self::request(speak, call)),
     }
   }
 
-  fn serialize<T: serde::Serialize>(from: &T) -> Result<String> {
+  fn register_stream<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<Self::Intermediate>) -> Result<ReplyStream<Self::Intermediate>> + Send + Sync + 'static,
+  {
+    trace!("register stream receiver");
+
+    *self.stream_receiver.lock().map_err(|_| Error::Lock)? = Some(Arc::new(receiver));
+    Ok(())
+  }
+
+  fn call_stream(&mut self, call: Call<Self::Intermediate>) -> Result<ReplyStream<Self::Intermediate>> {
+    trace!("call stream backend");
+
+    info!("received outgoing stream call");
+
+    match &self.speak {
+      None => Err(Error::NoSpeak.into()),
+
+      Some(speak) => runtime(&mut self.runtime)?.block_on(Self::request_stream(speak, call)),
+    }
+  }
+
+  fn register_upload<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<Self::Intermediate>, CallStream<Self::Intermediate>) -> Result<Reply<Self::Intermediate>> + Send + Sync + 'static,
+  {
+    trace!("register upload receiver");
+
+    *self.upload_receiver.lock().map_err(|_| Error::Lock)? = Some(Arc::new(receiver));
+    Ok(())
+  }
+
+  fn call_upload(&mut self, call: Call<Self::Intermediate>, chunks: CallStream<Self::Intermediate>) -> Result<Reply<Self::Intermediate>> {
+    trace!("call upload backend");
+
+    info!("received outgoing upload");
+
+    match &self.speak {
+      None => Err(Error::NoSpeak.into()),
+
+      Some(speak) => runtime(&mut self.runtime)?.block_on(Self::request_upload(speak, call, chunks)),
+    }
+  }
+
+  fn serialize<T: serde::Serialize>(from: &T) -> Result<Self::Intermediate> {
     trace!("serialize from");
 
-    serde_json::to_string(from).map_err(|e| Error::Serialize(e).into())
+    C::encode(from)
   }
 
   fn deserialize<'b, T>(from: &'b Self::Intermediate) -> Result<T>
@@ -250,11 +764,51 @@ impl Backend for Http {
   {
     trace!("deserialize from");
 
-    serde_json::from_str(from).map_err(|e| Error::Deserialize(e).into())
+    C::decode(from)
+  }
+}
+
+impl<C: Codec> AsyncBackend for Http<C> {
+  type Intermediate = C::Intermediate;
+
+  fn register<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<C::Intermediate>) -> BoxFuture<'static, Result<Reply<C::Intermediate>>> + Send + Sync + 'static,
+  {
+    trace!("register async receiver");
+
+    self.receiver = Some(Arc::new(receiver));
+
+    let handle = Handle::try_current().map_err(Error::NoRuntime)?;
+    Self::ignore_start_error(self.start_on(&handle))
+  }
+
+  fn call(&self, call: Call<C::Intermediate>) -> BoxFuture<'_, Result<Reply<C::Intermediate>>> {
+    trace!("call async backend");
+
+    info!("received outgoing call");
+
+    Box::pin(async move {
+      match &self.speak {
+        None => Err(Error::NoSpeak.into()),
+        Some(speak) => Self::request(speak, call).await,
+      }
+    })
+  }
+
+  fn serialize<T: serde::Serialize>(from: &T) -> Result<Self::Intermediate> {
+    <Self as Backend>::serialize(from)
+  }
+
+  fn deserialize<T>(from: &Self::Intermediate) -> Result<T>
+  where
+    T: for<'de> serde::Deserialize<'de>,
+  {
+    <Self as Backend>::deserialize(from)
   }
 }
 
-impl Drop for Http {
+impl<C: Codec> Drop for Http<C> {
   fn drop(&mut self) {
     if self.shutdown.is_some() {
       self.stop().unwrap()
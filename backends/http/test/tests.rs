@@ -74,3 +74,335 @@ fn register_http_duplex() {
   let result_second: i32 = merfolk_second.frontend(|f| f.call("add", &(x, y)).unwrap()).unwrap();
   assert_eq!(result_second, x + y);
 }
+
+#[test]
+fn async_register_http() {
+  use merfolk::adapters::Async;
+
+  tokio::runtime::Runtime::new().unwrap().block_on(async {
+    let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+    let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+    register_receiver.register("add", |(a, b)| add(a, b)).unwrap();
+
+    let merfolk_caller = AsyncMer::builder()
+      .backend(merfolk_backend_http::Http::builder().speak("http://localhost:8087".parse::<hyper::Uri>().unwrap()).build().unwrap())
+      .frontend(Async::new(register_caller))
+      .build()
+      .unwrap();
+
+    let _merfolk_receiver = AsyncMer::builder()
+      .backend(
+        merfolk_backend_http::Http::builder()
+          .listen(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8087))
+          .build()
+          .unwrap(),
+      )
+      .frontend(Async::new(register_receiver))
+      .build()
+      .unwrap();
+
+    let (a, b) = (rand::random::<i32>() / 2, rand::random::<i32>() / 2);
+    let result: i32 = tokio::task::block_in_place(|| merfolk_caller.frontend().inner(|f| f.call("add", &(a, b)).unwrap()).unwrap());
+    assert_eq!(result, a + b);
+  });
+}
+
+#[test]
+fn metadata_http() {
+  use merfolk::interfaces::Backend;
+
+  let mut receiver = merfolk_backend_http::Http::builder()
+    .listen(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8088))
+    .build()
+    .unwrap();
+
+  receiver
+    .register(|mut call: Call<String>| {
+      let user = call.metadata.remove("user").unwrap_or_default();
+      call.metadata.insert("greeting", format!("hello {}", user));
+
+      Ok(Reply {
+        payload: call.payload,
+        metadata: call.metadata,
+      })
+    })
+    .unwrap();
+
+  let mut caller = merfolk_backend_http::Http::builder().speak("http://localhost:8088".parse::<hyper::Uri>().unwrap()).build().unwrap();
+
+  let rnd = rand::random::<i32>().to_string();
+  let reply = caller
+    .call(Call {
+      procedure: "echo".to_string(),
+      payload: rnd.clone(),
+      metadata: vec![("User", "merfolk")].into_iter().collect(),
+    })
+    .unwrap();
+
+  assert_eq!(reply.payload, rnd);
+  assert_eq!(reply.metadata.get("greeting"), Some("hello merfolk"));
+  assert_eq!(reply.metadata.get("user"), None);
+
+  for metadata in [("user", "mer\nfolk"), ("us er", "merfolk")] {
+    let err = caller
+      .call(Call {
+        procedure: "echo".to_string(),
+        payload: rnd.clone(),
+        metadata: vec![metadata].into_iter().collect(),
+      })
+      .unwrap_err();
+
+    assert!(matches!(err.downcast_ref::<merfolk_backend_http::Error>(), Some(merfolk_backend_http::Error::InvalidMetadata { .. })));
+  }
+}
+
+#[test]
+fn timeout_http() {
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver.register("sleep", |millis: u64| std::thread::sleep(std::time::Duration::from_millis(millis))).unwrap();
+
+  let timeout = std::time::Duration::from_millis(50);
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_http::Http::builder().speak("http://localhost:8089".parse::<hyper::Uri>().unwrap()).build().unwrap())
+    .frontend(register_caller)
+    .timeout(timeout)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(
+      merfolk_backend_http::Http::builder()
+        .listen(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8089))
+        .build()
+        .unwrap(),
+    )
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  assert_eq!(merfolk_caller.timeout(), Some(timeout));
+
+  let result: () = merfolk_caller.frontend(|f| f.call_with_timeout("sleep", &10_u64, timeout * 10).unwrap()).unwrap();
+  assert_eq!(result, ());
+
+  let err = merfolk_caller.frontend(|f| f.call::<_, ()>("sleep", &500_u64).unwrap_err()).unwrap();
+  assert!(matches!(err.downcast_ref::<merfolk::Error>(), Some(merfolk::Error::Timeout { .. })));
+
+  // the server answers with a timeout without waiting for the procedure which is still sleeping
+  let start = std::time::Instant::now();
+  let status = tokio::runtime::Runtime::new().unwrap().block_on(async {
+    let request = hyper::Request::post("http://localhost:8089")
+      .header("Procedure", "sleep")
+      .header("Metadata-Timeout", timeout.as_millis().to_string())
+      .body(hyper::Body::from("500"))
+      .unwrap();
+    hyper::Client::new().request(request).await.unwrap().status()
+  });
+  assert_eq!(status, hyper::StatusCode::GATEWAY_TIMEOUT);
+  assert!(start.elapsed() < std::time::Duration::from_millis(400));
+}
+
+#[test]
+fn remote_error_http() {
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver.register("add", |(a, b)| add(a, b)).unwrap();
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_http::Http::builder().speak("http://localhost:8090".parse::<hyper::Uri>().unwrap()).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(
+      merfolk_backend_http::Http::builder()
+        .listen(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8090))
+        .build()
+        .unwrap(),
+    )
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let err = merfolk_caller.frontend(|f| f.call::<_, i32>("subtract", &(1, 2)).unwrap_err()).unwrap();
+  let remote = err.downcast_ref::<RemoteError>().unwrap();
+  assert_eq!(remote.code, RemoteError::PROCEDURE_NOT_FOUND);
+  assert!(remote.message.contains("subtract"));
+
+  let err = merfolk_caller.frontend(|f| f.call::<_, i32>("add", &"1 + 2").unwrap_err()).unwrap();
+  assert_eq!(err.downcast_ref::<RemoteError>().unwrap().code, RemoteError::INVALID_PARAMS);
+}
+
+#[test]
+fn notification_http() {
+  let (notified, notifications) = std::sync::mpsc::channel::<i32>();
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver
+    .register("record", move |value: i32| {
+      std::thread::sleep(std::time::Duration::from_millis(200));
+      notified.send(value).unwrap();
+    })
+    .unwrap();
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_http::Http::builder().speak("http://localhost:8092".parse::<hyper::Uri>().unwrap()).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(
+      merfolk_backend_http::Http::builder()
+        .listen(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8092))
+        .build()
+        .unwrap(),
+    )
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let start = std::time::Instant::now();
+  merfolk_caller.frontend(|f| f.notify("record", &42).unwrap()).unwrap();
+  assert!(start.elapsed() < std::time::Duration::from_millis(200));
+  assert_eq!(notifications.recv_timeout(std::time::Duration::from_secs(2)).unwrap(), 42);
+
+  merfolk_caller.frontend(|f| f.notify("subtract", &(1, 2)).unwrap()).unwrap();
+}
+
+#[test]
+fn batch_http() {
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver.register("add", |(a, b)| add(a, b)).unwrap();
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_http::Http::builder().speak("http://localhost:8093".parse::<hyper::Uri>().unwrap()).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(
+      merfolk_backend_http::Http::builder()
+        .listen(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8093))
+        .build()
+        .unwrap(),
+    )
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let replies = merfolk_caller
+    .frontend(|f| f.batch().call("add", &(1, 2)).unwrap().call("subtract", &(1, 2)).unwrap().call("add", &(3, 4)).unwrap().send().unwrap())
+    .unwrap();
+
+  assert_eq!(replies.len(), 3);
+  assert_eq!(replies.get::<i32>(0).unwrap(), 3);
+  assert_eq!(replies.get::<i32>(1).unwrap_err().downcast_ref::<RemoteError>().unwrap().code, RemoteError::PROCEDURE_NOT_FOUND);
+  assert_eq!(replies.get::<i32>(2).unwrap(), 7);
+}
+
+#[test]
+fn stream_http() {
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver
+    .register_stream("count", |to: u32| (0..to).inspect(|_| std::thread::sleep(std::time::Duration::from_millis(100))))
+    .unwrap();
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_http::Http::builder().speak("http://localhost:8094".parse::<hyper::Uri>().unwrap()).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(
+      merfolk_backend_http::Http::builder()
+        .listen(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8094))
+        .build()
+        .unwrap(),
+    )
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let start = std::time::Instant::now();
+  let mut stream = merfolk_caller.frontend(|f| f.call_stream::<_, u32>("count", &5).unwrap()).unwrap();
+
+  // the first item is received before the last one is produced
+  assert_eq!(stream.next().unwrap().unwrap(), 0);
+  assert!(start.elapsed() < std::time::Duration::from_millis(400));
+  assert_eq!(stream.collect::<Result<Vec<_>, _>>().unwrap(), vec![1, 2, 3, 4]);
+
+  let err = merfolk_caller
+    .frontend(|f| f.call_stream::<_, u32>("missing", &()).and_then(|s| s.collect::<Result<Vec<_>, _>>()).unwrap_err())
+    .unwrap();
+  assert_eq!(err.downcast_ref::<RemoteError>().unwrap().code, RemoteError::PROCEDURE_NOT_FOUND);
+}
+
+#[test]
+fn upload_http() {
+  use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+  };
+
+  let produced = Arc::new(AtomicBool::new(false));
+  let produced_receiver = Arc::clone(&produced);
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver
+    .register_upload("join", move |separator: String, mut chunks: merfolk::CallStream<String>| {
+      // the first chunk is received before the last one is produced
+      let first = chunks.next().unwrap().unwrap();
+      let early = !produced_receiver.load(Ordering::SeqCst);
+
+      (chunks.fold(first, |acc, chunk| acc + &separator + &chunk.unwrap()), early)
+    })
+    .unwrap();
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_http::Http::builder().speak("http://localhost:8095".parse::<hyper::Uri>().unwrap()).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(
+      merfolk_backend_http::Http::builder()
+        .listen(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8095))
+        .build()
+        .unwrap(),
+    )
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let (joined, early): (String, bool) = merfolk_caller
+    .frontend(|f| {
+      let produced = Arc::clone(&produced);
+      let chunks = (0..5).map(move |i| {
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        if i == 4 {
+          produced.store(true, Ordering::SeqCst);
+        }
+        format!("line {}\nof {}", i, 5)
+      });
+
+      f.upload("join", &", ".to_string(), chunks).unwrap()
+    })
+    .unwrap();
+
+  assert_eq!(joined, (0..5).map(|i| format!("line {}\nof 5", i)).collect::<Vec<_>>().join(", "));
+  assert!(early);
+
+  let err = merfolk_caller.frontend(|f| f.upload::<_, _, String, _>("missing", &(), vec![1, 2]).unwrap_err()).unwrap();
+  assert_eq!(err.downcast_ref::<RemoteError>().unwrap().code, RemoteError::PROCEDURE_NOT_FOUND);
+}
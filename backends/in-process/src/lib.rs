@@ -1,22 +1,36 @@
-use std::sync::Arc;
+use std::{future::Future, marker::PhantomData, sync::Arc, time::Duration};
 
 use anyhow::Result;
-use log::trace;
-use merfolk::{interfaces::Backend, Call, Reply};
+use log::{trace, warn};
+use merfolk::{
+  helpers::future::BoxFuture,
+  interfaces::{AsyncBackend, Backend, Codec},
+  Call, CallStream, Metadata, RemoteError, Reply, ReplyStream,
+};
+use merfolk_codecs::Json;
 use thiserror::Error;
 use tokio::{
-  runtime::Runtime,
+  runtime::{Handle, Runtime},
   sync::{mpsc, oneshot},
 };
 
-pub type InProcessChannel = (Call<String>, oneshot::Sender<Result<Reply<String>>>);
+/// Message sent over the channels. Notifications do not carry an [`InProcessReply`].
+///
+/// `T` is the [`Intermediate`](Codec::Intermediate) type of the [`Codec`] of the [`InProcess`] backends.
+pub type InProcessChannel<T = String> = (Call<T>, Option<InProcessReply<T>>);
+
+/// Channel the reply of a [`Call`] is sent back on.
+pub enum InProcessReply<T = String> {
+  /// Carries the single [`Reply`] of a [`Call`].
+  Reply(oneshot::Sender<Result<Reply<T>, RemoteError>>),
+  /// Carries the items of a streaming [`Call`]. The stream ends when the sender is dropped.
+  Stream(std::sync::mpsc::Sender<Result<T, RemoteError>>),
+  /// Carries the chunks of an upload and the single [`Reply`] sent back after they were consumed. The chunks end when their sender is dropped.
+  Upload(mpsc::Receiver<Result<T, RemoteError>>, oneshot::Sender<Result<Reply<T>, RemoteError>>),
+}
 
 #[derive(Debug, Error)]
 pub enum Error {
-  #[error("derializing failed: {0}")]
-  Serialize(#[source] serde_json::Error),
-  #[error("deserializing failed: {0}")]
-  Deserialize(#[source] serde_json::Error),
   #[error("no receiver was registered by the init() function")]
   NoReceiver,
   #[error("no `to` channel was provided in init()")]
@@ -25,50 +39,111 @@ pub enum Error {
   NoReceiverChannel,
   #[error("recv() from `rx` channel failed")]
   CallerRecv,
-  #[error("send() to `to` channel failed: {0}")]
-  CallerSend(#[from] tokio::sync::mpsc::error::SendError<InProcessChannel>),
+  #[error("send() to `to` channel failed")]
+  CallerSend,
   #[error("could not create runtime: {0}")]
   RuntimeCreation(#[from] std::io::Error),
+  #[error("not running in a runtime: {0}")]
+  NoRuntime(#[from] tokio::runtime::TryCurrentError),
   #[error("already started")]
   AlreadyStarted,
   #[error("not yet started")]
   NotStarted,
+  #[error("stream or upload receiver lock was poisoned")]
+  Lock,
 }
 
 #[derive(derive_builder::Builder)]
 #[builder(pattern = "owned")]
-pub struct InProcess {
+pub struct InProcess<C: Codec = Json> {
+  #[builder(private, default = "PhantomData")]
+  __phantom: PhantomData<C>,
+
   #[allow(clippy::type_complexity)]
   #[builder(private, default = "None")]
-  receiver: Option<Arc<dyn Fn(Call<String>) -> Result<Reply<String>> + Send + Sync>>,
+  receiver: Option<Arc<dyn Fn(Call<C::Intermediate>) -> BoxFuture<'static, Result<Reply<C::Intermediate>>> + Send + Sync>>,
+
+  #[allow(clippy::type_complexity)]
+  #[builder(private, default = "Arc::new(std::sync::Mutex::new(None))")]
+  stream_receiver: Arc<std::sync::Mutex<Option<Arc<dyn Fn(Call<C::Intermediate>) -> Result<ReplyStream<C::Intermediate>> + Send + Sync>>>>,
+
+  #[allow(clippy::type_complexity)]
+  #[builder(private, default = "Arc::new(std::sync::Mutex::new(None))")]
+  upload_receiver: Arc<std::sync::Mutex<Option<Arc<dyn Fn(Call<C::Intermediate>, CallStream<C::Intermediate>) -> Result<Reply<C::Intermediate>> + Send + Sync>>>>,
+
+  /// Number of chunks of an upload which are sent before the receiving procedure consumes them. Must be greater than zero.
+  #[builder(default = "8")]
+  upload_window: usize,
 
-  #[builder(private, default = "Runtime::new().map_err(Error::RuntimeCreation).map_err(|e| e.to_string())?")]
-  runtime: Runtime,
+  #[builder(private, default = "None")]
+  runtime: Option<Runtime>,
 
   #[builder(private, default = "None")]
   handle: Option<tokio::task::JoinHandle<std::convert::Infallible>>,
 
   #[builder(setter(into, strip_option), default = "None")]
-  to: Option<mpsc::Sender<InProcessChannel>>,
+  to: Option<mpsc::Sender<InProcessChannel<C::Intermediate>>>,
 
+  #[allow(clippy::type_complexity)]
   #[builder(setter(into, strip_option, name = "from_setter"), private, default = "None")]
-  from: Option<Arc<tokio::sync::Mutex<mpsc::Receiver<InProcessChannel>>>>,
+  from: Option<Arc<tokio::sync::Mutex<mpsc::Receiver<InProcessChannel<C::Intermediate>>>>>,
 }
 
-impl InProcessBuilder {
-  pub fn from(self, value: mpsc::Receiver<InProcessChannel>) -> Self {
+impl<C: Codec> InProcessBuilder<C> {
+  pub fn from(self, value: mpsc::Receiver<InProcessChannel<C::Intermediate>>) -> Self {
     self.from_setter(Arc::new(tokio::sync::Mutex::new(value)))
   }
 }
 
 impl InProcess {
-  pub fn builder() -> InProcessBuilder {
+  /// Creates a builder for an [`InProcess`] backend using the [`Json`] [`Codec`].
+  pub fn builder() -> InProcessBuilder<Json> {
     InProcessBuilder::default()
   }
 }
 
-impl InProcess {
+impl<C: Codec> InProcess<C> {
+  /// Creates a builder for an [`InProcess`] backend using the [`Codec`] `C`.
+  ///
+  /// ```
+  /// # use merfolk_backend_in_process::InProcess;
+  /// let in_process = InProcess::<merfolk_codecs::Postcard>::builder_with_codec().build().unwrap();
+  /// ```
+  pub fn builder_with_codec() -> InProcessBuilder<C> {
+    InProcessBuilder::default()
+  }
+}
+
+/// Awaits the `future` and fails with [`merfolk::Error::Timeout`] if the `timeout` elapses first.
+async fn timeout<T>(procedure: &str, timeout: Option<Duration>, future: impl Future<Output = Result<T>>) -> Result<T> {
+  match timeout {
+    None => future.await,
+    Some(timeout) => tokio::time::timeout(timeout, future).await.map_err(|_| merfolk::Error::Timeout {
+      procedure: procedure.to_string(),
+      timeout,
+    })?,
+  }
+}
+
+/// Returns the private [`Runtime`] used by the synchronous [`Backend`] implementation. The [`Runtime`] is created on first use.
+fn runtime(runtime: &mut Option<Runtime>) -> Result<&Runtime> {
+  if runtime.is_none() {
+    *runtime = Some(Runtime::new().map_err(Error::RuntimeCreation)?);
+  }
+
+  Ok(runtime.as_ref().unwrap())
+}
+
+impl<C: Codec> InProcess<C> {
+  /// Starts listening on the `from` channel on the private [`Runtime`].
   pub fn start(&mut self) -> Result<()> {
+    let handle = runtime(&mut self.runtime)?.handle().clone();
+
+    self.start_on(&handle)
+  }
+
+  /// Starts listening on the `from` channel on the [`Runtime`] of the `handle`.
+  pub fn start_on(&mut self, handle: &Handle) -> Result<()> {
     trace!("start InProcess");
 
     if self.handle.is_some() {
@@ -77,17 +152,81 @@ impl InProcess {
 
     let from = self.from.as_ref().ok_or(Error::NoReceiverChannel)?.clone();
     let receiver = self.receiver.as_ref().ok_or(Error::NoReceiver)?.clone();
+    let stream_receiver = Arc::clone(&self.stream_receiver);
+    let upload_receiver = Arc::clone(&self.upload_receiver);
 
-    self.handle = Some(self.runtime.spawn(async move {
+    self.handle = Some(handle.spawn(async move {
       loop {
         let (call, tx) = from.lock().await.recv().await.unwrap();
 
-        let reply = receiver(Call {
-          procedure: call.procedure,
-          payload: call.payload,
+        let tx = match tx {
+          Some(InProcessReply::Stream(tx)) => {
+            let receiver = receiver.clone();
+            let stream_receiver = stream_receiver.lock().ok().and_then(|r| r.clone());
+
+            // the items are produced by a blocking iterator
+            tokio::task::spawn_blocking(move || {
+              let stream = match stream_receiver {
+                Some(stream_receiver) => stream_receiver(call),
+                None => Handle::current().block_on(receiver(call)).map(|reply| ReplyStream::once(Ok(reply.payload))),
+              };
+
+              match stream {
+                Ok(stream) => {
+                  for item in stream {
+                    if tx.send(item.map_err(RemoteError::from)).is_err() {
+                      break;
+                    }
+                  }
+                }
+                Err(e) => {
+                  tx.send(Err(e.into())).ok();
+                }
+              }
+            });
+            continue;
+          }
+          Some(InProcessReply::Upload(chunks, tx)) => {
+            let upload_receiver = upload_receiver.lock().ok().and_then(|r| r.clone());
+
+            // the chunks are consumed by a blocking iterator
+            tokio::task::spawn_blocking(move || {
+              let mut chunks = chunks;
+              let reply = match upload_receiver {
+                Some(upload_receiver) => upload_receiver(
+                  call,
+                  CallStream::new(std::iter::from_fn(move || chunks.blocking_recv()).map(|chunk| chunk.map_err(anyhow::Error::from))),
+                ),
+                None => Err(merfolk::Error::Unsupported("uploads".into()).into()),
+              };
+
+              tx.send(reply.map_err(RemoteError::from)).ok();
+            });
+            continue;
+          }
+          Some(InProcessReply::Reply(tx)) => Some(tx),
+          None => None,
+        };
+
+        let procedure = call.procedure.clone();
+        let call_timeout = call.metadata.timeout();
+        let receiver = receiver.clone();
+
+        // the calls are answered concurrently, the listener only dispatches them
+        tokio::spawn(async move {
+          let reply = timeout(&procedure, call_timeout, receiver(call)).await.map_err(RemoteError::from);
+
+          match tx {
+            Some(tx) => {
+              tx.send(reply).ok();
+            }
+            None => {
+              if let Err(e) = reply {
+                warn!("notification {} failed: {}", procedure, e);
+              }
+            }
+          }
         });
-
-        tx.send(reply).unwrap();
       }
     }));
 
@@ -105,10 +244,55 @@ impl InProcess {
       }
     }
   }
+
+  async fn request(to: Option<&mpsc::Sender<InProcessChannel<C::Intermediate>>>, call: Call<C::Intermediate>) -> Result<Reply<C::Intermediate>> {
+    let procedure = call.procedure.clone();
+    let call_timeout = call.metadata.timeout();
+
+    if call.metadata.is_notification() {
+      return timeout(&procedure, call_timeout, async {
+        to.ok_or(Error::NoCallerChannel)?.send((call, None)).await.map_err(|_| Error::CallerSend)?;
+
+        Ok(Reply {
+          payload: <Self as Backend>::serialize(&())?,
+          metadata: Metadata::new(),
+        })
+      })
+      .await;
+    }
+
+    timeout(&procedure, call_timeout, async {
+      #[allow(clippy::type_complexity)]
+      let (tx, rx): (
+        oneshot::Sender<Result<Reply<C::Intermediate>, RemoteError>>,
+        oneshot::Receiver<Result<Reply<C::Intermediate>, RemoteError>>,
+      ) = oneshot::channel();
+      to.ok_or(Error::NoCallerChannel)?.send((call, Some(InProcessReply::Reply(tx)))).await.map_err(|_| Error::CallerSend)?;
+
+      rx.await?.map_err(|e| e.into_error(&procedure, call_timeout))
+    })
+    .await
+  }
+
+  async fn request_stream(to: Option<&mpsc::Sender<InProcessChannel<C::Intermediate>>>, call: Call<C::Intermediate>) -> Result<ReplyStream<C::Intermediate>> {
+    let procedure = call.procedure.clone();
+    let call_timeout = call.metadata.timeout();
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    timeout(&procedure, call_timeout, async {
+      to.ok_or(Error::NoCallerChannel)?.send((call, Some(InProcessReply::Stream(tx)))).await.map_err(|_| Error::CallerSend)?;
+      Ok(())
+    })
+    .await?;
+
+    Ok(ReplyStream::receive(rx, procedure.clone(), call_timeout, move |item| {
+      item.map_err(|e| e.into_error(&procedure, call_timeout))
+    }))
+  }
 }
 
-impl Backend for InProcess {
-  type Intermediate = String;
+impl<C: Codec> Backend for InProcess<C> {
+  type Intermediate = C::Intermediate;
 
   fn register<T>(&mut self, receiver: T) -> Result<()>
   where
@@ -116,7 +300,12 @@ impl Backend for InProcess {
   {
     trace!("register receiver");
 
-    self.receiver = Some(Arc::new(receiver));
+    let receiver = Arc::new(receiver);
+    self.receiver = Some(Arc::new(move |call: Call<C::Intermediate>| {
+      let receiver = Arc::clone(&receiver);
+      // the blocking receiver runs on its own thread so the timeout of the call can elapse, a late reply is dropped
+      Box::pin(async move { tokio::task::spawn_blocking(move || receiver(call)).await? })
+    }));
 
     self.start().ok();
 
@@ -126,31 +315,82 @@ impl Backend for InProcess {
   fn call(&mut self, call: Call<Self::Intermediate>) -> Result<Reply<Self::Intermediate>> {
     trace!("receive call");
 
-    self.runtime.block_on(async {
-      #[allow(clippy::type_complexity)]
-      let (tx, rx): (oneshot::Sender<Result<Reply<String>>>, oneshot::Receiver<Result<Reply<String>>>) = oneshot::channel();
+    runtime(&mut self.runtime)?.block_on(Self::request(self.to.as_ref(), call))
+  }
+
+  fn register_stream<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<Self::Intermediate>) -> Result<ReplyStream<Self::Intermediate>> + Send + Sync + 'static,
+  {
+    trace!("register stream receiver");
+
+    *self.stream_receiver.lock().map_err(|_| Error::Lock)? = Some(Arc::new(receiver));
+
+    Ok(())
+  }
+
+  fn call_stream(&mut self, call: Call<Self::Intermediate>) -> Result<ReplyStream<Self::Intermediate>> {
+    trace!("receive stream call");
+
+    runtime(&mut self.runtime)?.block_on(Self::request_stream(self.to.as_ref(), call))
+  }
+
+  fn register_upload<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<Self::Intermediate>, CallStream<Self::Intermediate>) -> Result<Reply<Self::Intermediate>> + Send + Sync + 'static,
+  {
+    trace!("register upload receiver");
+
+    *self.upload_receiver.lock().map_err(|_| Error::Lock)? = Some(Arc::new(receiver));
+
+    Ok(())
+  }
+
+  fn call_upload(&mut self, call: Call<Self::Intermediate>, chunks: CallStream<Self::Intermediate>) -> Result<Reply<Self::Intermediate>> {
+    trace!("receive upload call");
+
+    let procedure = call.procedure.clone();
+    let call_timeout = call.metadata.timeout();
+
+    let runtime = runtime(&mut self.runtime)?;
+
+    // the bounded channel blocks the sender until the receiving procedure consumed the chunks sent before
+    let (chunks_tx, chunks_rx) = mpsc::channel(self.upload_window);
+    let (tx, rx) = oneshot::channel();
+
+    // sending the chunks counts towards the timeout, a receiving procedure which stops consuming them fails the call
+    runtime.block_on(timeout(&procedure, call_timeout, async {
       self
         .to
         .as_ref()
         .ok_or(Error::NoCallerChannel)?
-        .send((
-          Call {
-            procedure: call.procedure.clone(),
-            payload: call.payload.clone(),
-          },
-          tx,
-        ))
+        .send((call, Some(InProcessReply::Upload(chunks_rx, tx))))
         .await
-        .map_err(Error::CallerSend)?;
+        .map_err(|_| Error::CallerSend)?;
+
+      for chunk in chunks {
+        match chunk {
+          Ok(chunk) => {
+            if chunks_tx.send(Ok(chunk)).await.is_err() {
+              break;
+            }
+          }
+          Err(e) => {
+            chunks_tx.send(Err(RemoteError::new(RemoteError::INTERNAL_ERROR, &e))).await.ok();
+            return Err(e);
+          }
+        }
+      }
+      drop(chunks_tx);
 
-      rx.await?
-    })
+      rx.await?.map_err(|e| e.into_error(&procedure, call_timeout))
+    }))
   }
 
-  fn serialize<T: serde::Serialize>(from: &T) -> Result<String> {
+  fn serialize<T: serde::Serialize>(from: &T) -> Result<Self::Intermediate> {
     trace!("serialize from");
 
-    serde_json::to_string(from).map_err(|e| Error::Serialize(e).into())
+    C::encode(from)
   }
 
   fn deserialize<'b, T>(from: &'b Self::Intermediate) -> Result<T>
@@ -159,12 +399,51 @@ impl Backend for InProcess {
   {
     trace!("deserialize from");
 
-    serde_json::from_str(from).map_err(|e| Error::Deserialize(e).into())
+    C::decode(from)
+  }
+}
+
+impl<C: Codec> AsyncBackend for InProcess<C> {
+  type Intermediate = C::Intermediate;
+
+  fn register<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<C::Intermediate>) -> BoxFuture<'static, Result<Reply<C::Intermediate>>> + Send + Sync + 'static,
+  {
+    trace!("register async receiver");
+
+    self.receiver = Some(Arc::new(receiver));
+
+    if self.from.is_some() {
+      let handle = Handle::try_current().map_err(Error::NoRuntime)?;
+      self.start_on(&handle).ok();
+    }
+
+    Ok(())
+  }
+
+  fn call(&self, call: Call<C::Intermediate>) -> BoxFuture<'_, Result<Reply<C::Intermediate>>> {
+    trace!("receive async call");
+
+    Box::pin(Self::request(self.to.as_ref(), call))
+  }
+
+  fn serialize<T: serde::Serialize>(from: &T) -> Result<Self::Intermediate> {
+    <Self as Backend>::serialize(from)
+  }
+
+  fn deserialize<T>(from: &Self::Intermediate) -> Result<T>
+  where
+    T: for<'de> serde::Deserialize<'de>,
+  {
+    <Self as Backend>::deserialize(from)
   }
 }
 
-impl Drop for InProcess {
+impl<C: Codec> Drop for InProcess<C> {
   fn drop(&mut self) {
-    self.stop().unwrap()
+    if self.handle.is_some() {
+      self.stop().unwrap()
+    }
   }
 }
@@ -64,3 +64,439 @@ fn register_in_process_duplex() {
   let result_second: i32 = merfolk_second.frontend(|f| f.call("add", &(x, y)).unwrap()).unwrap();
   assert_eq!(result_second, x + y);
 }
+
+#[test]
+fn async_register_in_process() {
+  use merfolk::adapters::Async;
+  use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+  tokio::runtime::Runtime::new().unwrap().block_on(async {
+    let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+    let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+    register_receiver.register("add", |(a, b)| add(a, b)).unwrap();
+
+    let (to, from): (Sender<merfolk_backend_in_process::InProcessChannel>, Receiver<merfolk_backend_in_process::InProcessChannel>) = channel(1);
+
+    let merfolk_caller = AsyncMer::builder()
+      .backend(merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap())
+      .frontend(Async::new(register_caller))
+      .build()
+      .unwrap();
+
+    let _merfolk_receiver = AsyncMer::builder()
+      .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+      .frontend(Async::new(register_receiver))
+      .build()
+      .unwrap();
+
+    let (a, b) = (rand::random::<i32>() / 2, rand::random::<i32>() / 2);
+    let result: i32 = tokio::task::block_in_place(|| merfolk_caller.frontend().inner(|f| f.call("add", &(a, b)).unwrap()).unwrap());
+    assert_eq!(result, a + b);
+  });
+}
+
+#[test]
+fn metadata_in_process() {
+  use merfolk::interfaces::Backend;
+  use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+  let (to, from): (Sender<merfolk_backend_in_process::InProcessChannel>, Receiver<merfolk_backend_in_process::InProcessChannel>) = channel(1);
+
+  let mut receiver = merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap();
+
+  receiver
+    .register(|mut call: Call<String>| {
+      let user = call.metadata.remove("user").unwrap_or_default();
+      call.metadata.insert("greeting", format!("hello {}", user));
+
+      Ok(Reply {
+        payload: call.payload,
+        metadata: call.metadata,
+      })
+    })
+    .unwrap();
+
+  let mut caller = merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap();
+
+  let rnd = rand::random::<i32>().to_string();
+  let reply = caller
+    .call(Call {
+      procedure: "echo".to_string(),
+      payload: rnd.clone(),
+      metadata: vec![("User", "merfolk")].into_iter().collect(),
+    })
+    .unwrap();
+
+  assert_eq!(reply.payload, rnd);
+  assert_eq!(reply.metadata.get("greeting"), Some("hello merfolk"));
+  assert_eq!(reply.metadata.get("user"), None);
+}
+
+#[test]
+fn timeout_in_process() {
+  use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver.register("sleep", |millis: u64| std::thread::sleep(std::time::Duration::from_millis(millis))).unwrap();
+
+  let (to, from): (Sender<merfolk_backend_in_process::InProcessChannel>, Receiver<merfolk_backend_in_process::InProcessChannel>) = channel(1);
+
+  let raw = to.clone();
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let timeout = std::time::Duration::from_millis(50);
+
+  let result: () = merfolk_caller.frontend(|f| f.call_with_timeout("sleep", &10_u64, timeout * 10).unwrap()).unwrap();
+  assert_eq!(result, ());
+
+  let err = merfolk_caller.frontend(|f| f.call_with_timeout::<_, ()>("sleep", &500_u64, timeout).unwrap_err()).unwrap();
+  assert!(matches!(err.downcast_ref::<merfolk::Error>(), Some(merfolk::Error::Timeout { .. })));
+
+  // the server answers with a timeout without waiting for the procedure which is still sleeping
+  let mut metadata = Metadata::new();
+  metadata.set_timeout(timeout);
+  let (tx, rx) = tokio::sync::oneshot::channel();
+
+  let start = std::time::Instant::now();
+  raw
+    .blocking_send((
+      Call {
+        procedure: "sleep".to_string(),
+        payload: "500".to_string(),
+        metadata,
+      },
+      Some(merfolk_backend_in_process::InProcessReply::Reply(tx)),
+    ))
+    .unwrap();
+  assert_eq!(rx.blocking_recv().unwrap().unwrap_err().code, RemoteError::TIMEOUT);
+  assert!(start.elapsed() < std::time::Duration::from_millis(400));
+}
+
+#[test]
+fn remote_error_in_process() {
+  use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver.register("add", |(a, b)| add(a, b)).unwrap();
+
+  let (to, from): (Sender<merfolk_backend_in_process::InProcessChannel>, Receiver<merfolk_backend_in_process::InProcessChannel>) = channel(1);
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let err = merfolk_caller.frontend(|f| f.call::<_, i32>("subtract", &(1, 2)).unwrap_err()).unwrap();
+  assert_eq!(err.downcast_ref::<RemoteError>().unwrap().code, RemoteError::PROCEDURE_NOT_FOUND);
+
+  let err = merfolk_caller.frontend(|f| f.call::<_, i32>("add", &"1 + 2").unwrap_err()).unwrap();
+  assert_eq!(err.downcast_ref::<RemoteError>().unwrap().code, RemoteError::INVALID_PARAMS);
+}
+
+#[test]
+fn notification_in_process() {
+  use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+  let (notified, notifications) = std::sync::mpsc::channel::<i32>();
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver
+    .register("record", move |value: i32| {
+      std::thread::sleep(std::time::Duration::from_millis(200));
+      notified.send(value).unwrap();
+    })
+    .unwrap();
+
+  let (to, from): (Sender<merfolk_backend_in_process::InProcessChannel>, Receiver<merfolk_backend_in_process::InProcessChannel>) = channel(1);
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let start = std::time::Instant::now();
+  merfolk_caller.frontend(|f| f.notify("record", &42).unwrap()).unwrap();
+  assert!(start.elapsed() < std::time::Duration::from_millis(200));
+  assert_eq!(notifications.recv_timeout(std::time::Duration::from_secs(2)).unwrap(), 42);
+
+  merfolk_caller.frontend(|f| f.notify("subtract", &(1, 2)).unwrap()).unwrap();
+}
+
+#[test]
+fn batch_in_process() {
+  use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver.register("add", |(a, b)| add(a, b)).unwrap();
+
+  let (to, from): (Sender<merfolk_backend_in_process::InProcessChannel>, Receiver<merfolk_backend_in_process::InProcessChannel>) = channel(1);
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let replies = merfolk_caller
+    .frontend(|f| {
+      f.batch()
+        .call("add", &(1, 2))
+        .unwrap()
+        .call("subtract", &(1, 2))
+        .unwrap()
+        .call("add", &"1 + 2")
+        .unwrap()
+        .call("add", &(3, 4))
+        .unwrap()
+        .send()
+        .unwrap()
+    })
+    .unwrap();
+
+  assert_eq!(replies.len(), 4);
+  assert_eq!(replies.get::<i32>(0).unwrap(), 3);
+  assert_eq!(replies.get::<i32>(1).unwrap_err().downcast_ref::<RemoteError>().unwrap().code, RemoteError::PROCEDURE_NOT_FOUND);
+  assert_eq!(replies.get::<i32>(2).unwrap_err().downcast_ref::<RemoteError>().unwrap().code, RemoteError::INVALID_PARAMS);
+  assert_eq!(replies.get::<i32>(3).unwrap(), 7);
+  assert!(replies.get::<i32>(4).is_err());
+}
+
+#[test]
+fn async_batch_in_process() {
+  use merfolk::adapters::Async;
+  use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+  tokio::runtime::Runtime::new().unwrap().block_on(async {
+    let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+    let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+    register_receiver.register("add", |(a, b)| add(a, b)).unwrap();
+
+    let (to, from): (Sender<merfolk_backend_in_process::InProcessChannel>, Receiver<merfolk_backend_in_process::InProcessChannel>) = channel(1);
+
+    let merfolk_caller = AsyncMer::builder()
+      .backend(merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap())
+      .frontend(Async::new(register_caller))
+      .build()
+      .unwrap();
+
+    let _merfolk_receiver = AsyncMer::builder()
+      .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+      .frontend(Async::new(register_receiver))
+      .build()
+      .unwrap();
+
+    let replies = tokio::task::block_in_place(|| {
+      merfolk_caller
+        .frontend()
+        .inner(|f| f.batch().call("subtract", &(1, 2)).unwrap().call("add", &(1, 2)).unwrap().send().unwrap())
+        .unwrap()
+    });
+
+    assert_eq!(replies.get::<i32>(0).unwrap_err().downcast_ref::<RemoteError>().unwrap().code, RemoteError::PROCEDURE_NOT_FOUND);
+    assert_eq!(replies.get::<i32>(1).unwrap(), 3);
+  });
+}
+
+#[test]
+fn stream_in_process() {
+  use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver
+    .register_stream("count", |to: u32| (0..to).inspect(|_| std::thread::sleep(std::time::Duration::from_millis(100))))
+    .unwrap();
+
+  let (to, from): (Sender<merfolk_backend_in_process::InProcessChannel>, Receiver<merfolk_backend_in_process::InProcessChannel>) = channel(1);
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let start = std::time::Instant::now();
+  let mut stream = merfolk_caller.frontend(|f| f.call_stream::<_, u32>("count", &5).unwrap()).unwrap();
+
+  // the first item is received before the last one is produced
+  assert_eq!(stream.next().unwrap().unwrap(), 0);
+  assert!(start.elapsed() < std::time::Duration::from_millis(400));
+  assert_eq!(stream.collect::<Result<Vec<_>, _>>().unwrap(), vec![1, 2, 3, 4]);
+
+  let err = merfolk_caller
+    .frontend(|f| f.call_stream::<_, u32>("missing", &()).and_then(|s| s.collect::<Result<Vec<_>, _>>()).unwrap_err())
+    .unwrap();
+  assert_eq!(err.downcast_ref::<RemoteError>().unwrap().code, RemoteError::PROCEDURE_NOT_FOUND);
+}
+
+#[test]
+fn stream_timeout_in_process() {
+  use std::time::{Duration, Instant};
+
+  use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver
+    .register_stream("stall", |to: u32| {
+      (0..to).inspect(|i| {
+        if *i == 1 {
+          std::thread::sleep(Duration::from_millis(1000));
+        }
+      })
+    })
+    .unwrap();
+
+  let (to, from): (Sender<merfolk_backend_in_process::InProcessChannel>, Receiver<merfolk_backend_in_process::InProcessChannel>) = channel(1);
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap())
+    .frontend(register_caller)
+    .timeout(Duration::from_millis(200))
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let start = Instant::now();
+  let mut stream = merfolk_caller.frontend(|f| f.call_stream::<_, u32>("stall", &3).unwrap()).unwrap();
+
+  // the stream ends with a timeout when the next item is not received in time
+  assert_eq!(stream.next().unwrap().unwrap(), 0);
+  let err = stream.next().unwrap().unwrap_err();
+  assert!(matches!(err.downcast_ref::<merfolk::Error>(), Some(merfolk::Error::Timeout { .. })));
+  assert!(stream.next().is_none());
+  assert!(start.elapsed() < Duration::from_millis(800));
+}
+
+#[test]
+fn upload_in_process() {
+  use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+  };
+  use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+  let consumed = Arc::new(AtomicUsize::new(0));
+  let consumed_receiver = Arc::clone(&consumed);
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver
+    .register_upload("sum", move |offset: u64, chunks: merfolk::CallStream<u64>| {
+      chunks.fold(offset, |acc, chunk| {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        consumed_receiver.fetch_add(1, Ordering::SeqCst);
+        acc + chunk.unwrap()
+      })
+    })
+    .unwrap();
+
+  let (to, from): (Sender<merfolk_backend_in_process::InProcessChannel>, Receiver<merfolk_backend_in_process::InProcessChannel>) = channel(1);
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().to(to).upload_window(4).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  // the chunks are not produced faster than they are consumed
+  let sum: u64 = merfolk_caller
+    .frontend(|f| {
+      let consumed = Arc::clone(&consumed);
+      f.upload("sum", &100_u64, (0..50_u64).inspect(move |i| assert!(*i as usize <= consumed.load(Ordering::SeqCst) + 6)))
+        .unwrap()
+    })
+    .unwrap();
+  assert_eq!(sum, 100 + (0..50).sum::<u64>());
+
+  let err = merfolk_caller.frontend(|f| f.upload::<_, _, u64, _>("missing", &(), 0..3_u64).unwrap_err()).unwrap();
+  assert_eq!(err.downcast_ref::<RemoteError>().unwrap().code, RemoteError::PROCEDURE_NOT_FOUND);
+}
+
+#[test]
+fn upload_timeout_in_process() {
+  use std::time::{Duration, Instant};
+
+  use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver
+    .register_upload("sum", |offset: u64, chunks: merfolk::CallStream<u64>| {
+      std::thread::sleep(Duration::from_millis(1000));
+      chunks.fold(offset, |acc, chunk| acc + chunk.unwrap())
+    })
+    .unwrap();
+
+  let (to, from): (Sender<merfolk_backend_in_process::InProcessChannel>, Receiver<merfolk_backend_in_process::InProcessChannel>) = channel(1);
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().to(to).upload_window(1).build().unwrap())
+    .frontend(register_caller)
+    .timeout(Duration::from_millis(100))
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  // the call times out while the chunks wait for the procedure which does not consume them yet
+  let start = Instant::now();
+  let err = merfolk_caller.frontend(|f| f.upload::<_, _, u64, _>("sum", &0_u64, 0..20_u64).unwrap_err()).unwrap();
+  assert!(matches!(err.downcast_ref::<merfolk::Error>(), Some(merfolk::Error::Timeout { .. })));
+  assert!(start.elapsed() < Duration::from_millis(800));
+}
@@ -118,7 +118,10 @@ impl serialport::SerialPort for MockTty {
   }
 
   fn try_clone(&self) -> serialport::Result<Box<dyn serialport::SerialPort>> {
-    Err(serialport::Error::new(serialport::ErrorKind::Unknown, "cannot clone"))
+    Ok(Box::new(MockTty {
+      m: self.m.try_clone()?,
+      s: self.s.try_clone()?,
+    }))
   }
 
   fn set_break(&self) -> serialport::Result<()> {
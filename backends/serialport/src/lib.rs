@@ -1,81 +1,366 @@
-use std::{fmt::Debug, sync::Arc};
+pub use merfolk_framing::Framing;
+use merfolk_framing::{self as framing, Route, SelfAckFrame, SelfCall, SelfChunkFrame, SelfReply, SelfReplyFrame};
+
+use std::{
+  collections::HashMap,
+  fmt::Debug,
+  future::Future,
+  marker::PhantomData,
+  sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc,
+  },
+  time::Duration,
+};
 
 use anyhow::Result;
-use log::{debug, error, info, trace};
-use merfolk::{interfaces::Backend, Call, Reply};
-use serde::{Deserialize, Serialize};
+use log::{debug, error, info, trace, warn};
+use merfolk::{
+  helpers::future::BoxFuture,
+  interfaces::{AsyncBackend, Backend, Codec, Encoded},
+  Call, CallStream, Metadata, RemoteError, Reply, ReplyStream,
+};
+use merfolk_codecs::Ron;
 use thiserror::Error;
-use tokio::{runtime::Runtime, sync::Mutex};
+use tokio::{
+  runtime::{Handle, Runtime},
+  sync::{mpsc, oneshot, Mutex},
+};
 
 #[derive(Debug, Error)]
 pub enum Error {
-  #[error("serializing failed: {0}")]
-  Serialize(#[source] ron::Error),
-  #[error("deserializing failed: {0}")]
-  Deserialize(#[source] ron::de::SpannedError),
   #[error("no receiver was degistered by init()")]
   NoReceiver,
   #[error("could not create runtime: {0}")]
   RuntimeCreation(#[from] std::io::Error),
+  #[error("not running in a runtime: {0}")]
+  NoRuntime(#[from] tokio::runtime::TryCurrentError),
   #[error("already started")]
   AlreadyStarted,
   #[error("not started")]
   NotStarted,
   #[error("error while sending: {0}")]
   SendError(#[source] std::io::Error),
-  #[error("no sender channel still alive")]
-  NoSenderChannel,
-  #[error("from frontend: {0}")]
-  FromFrontend(#[source] anyhow::Error),
+  #[error("listener stopped before the reply for call {0} was received")]
+  NoReply(u64),
   #[error("{0} must be initialized")]
   Init(String),
+  #[error("pending calls, stream or upload receiver lock was poisoned")]
+  Lock,
+  #[error("malformed frame: {0}")]
+  MalformedFrame(String),
+  #[error("no target node was selected for the call")]
+  NoTarget,
+  #[error("invalid target address {0}")]
+  InvalidTarget(String),
+  #[error("only notifications can be broadcast")]
+  Broadcast,
+  #[error("serial port is disconnected")]
+  Disconnected,
+  #[error("could not clone the serial port for reading: {0}")]
+  Clone(#[source] serialport::Error),
+}
+
+/// State of the connection to the serial port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+  /// The serial port is open.
+  Connected,
+  /// The serial port was disconnected or could not be opened. It is reopened with backoff if the [`SerialPort`] was built with a way to open it.
+  Disconnected,
 }
 
+/// Key of the [`Metadata`] selecting the address of the node a [`Call`] is sent to, overriding the `target` of the [`SerialPort`].
+///
+/// ```
+/// # use merfolk::Metadata;
+/// let mut metadata = Metadata::new();
+/// metadata.insert(merfolk_backend_serialport::TARGET, 7);
+/// ```
+pub const TARGET: &str = "target";
+
+/// Address of all nodes on the bus. Only notifications can be sent to it as the replies would collide on the bus.
+pub const BROADCAST: u8 = 0xff;
+
 #[derive(derive_builder::Builder)]
-#[builder(pattern = "owned")]
-pub struct SerialPort {
-  #[builder(setter(name = "port_setter"), private)]
-  port: Arc<Mutex<Box<dyn serialport::SerialPort>>>,
+#[builder(pattern = "owned", build_fn(validate = "Self::validate"))]
+pub struct SerialPort<C: Codec = Ron> {
+  #[builder(private, default = "PhantomData")]
+  __phantom: PhantomData<C>,
+
+  #[builder(setter(name = "port_setter"), private, default = "Arc::new(Mutex::new(None))")]
+  port: Arc<Mutex<Option<Box<dyn serialport::SerialPort>>>>,
 
   #[allow(clippy::type_complexity)]
-  #[builder(private, default = "None")]
-  receiver: Option<Arc<dyn Fn(Call<String>) -> Result<Reply<String>> + Send + Sync>>,
+  #[builder(setter(name = "reopen_setter"), private, default = "None")]
+  open: Option<Arc<dyn Fn() -> serialport::Result<Box<dyn serialport::SerialPort>> + Send + Sync>>,
+
+  /// Delay before reopening a disconnected serial port, it is doubled after every failed attempt up to `max_backoff`.
+  #[builder(default = "Duration::from_millis(100)")]
+  backoff: Duration,
+
+  /// Maximum delay between the attempts to reopen a disconnected serial port.
+  #[builder(default = "Duration::from_secs(5)")]
+  max_backoff: Duration,
 
+  #[builder(private, default = "Arc::new(AtomicBool::new(false))")]
+  connected: Arc<AtomicBool>,
+
+  #[allow(clippy::type_complexity)]
+  #[builder(setter(name = "on_state_setter"), private, default = "None")]
+  on_state: Option<Arc<dyn Fn(State) + Send + Sync>>,
+
+  #[allow(clippy::type_complexity)]
   #[builder(private, default = "None")]
-  reply_queue: Option<Arc<Mutex<tokio::sync::mpsc::Receiver<String>>>>,
+  receiver: Option<Arc<dyn Fn(Call<C::Intermediate>) -> BoxFuture<'static, Result<Reply<C::Intermediate>>> + Send + Sync>>,
+
+  #[allow(clippy::type_complexity)]
+  #[builder(private, default = "Arc::new(std::sync::Mutex::new(None))")]
+  stream_receiver: Arc<std::sync::Mutex<Option<Arc<dyn Fn(Call<C::Intermediate>) -> Result<ReplyStream<C::Intermediate>> + Send + Sync>>>>,
+
+  #[allow(clippy::type_complexity)]
+  #[builder(private, default = "Arc::new(std::sync::Mutex::new(None))")]
+  upload_receiver: Arc<std::sync::Mutex<Option<Arc<dyn Fn(Call<C::Intermediate>, CallStream<C::Intermediate>) -> Result<Reply<C::Intermediate>> + Send + Sync>>>>,
+
+  /// Number of chunks of an upload which are sent before the receiving procedure acknowledged consuming them. Must be greater than zero.
+  #[builder(default = "8")]
+  upload_window: usize,
+
+  /// [`Framing`] of the packages on the serial port. Defaults to [`Framing::Lines`].
+  #[builder(default)]
+  framing: Framing,
+
+  #[builder(private, default = "Arc::new(AtomicU64::new(0))")]
+  malformed: Arc<AtomicU64>,
+
+  /// Address of this node on a multi-drop bus (e.g. RS-485). Frames carry their source and destination address if it is set and frames for other nodes are ignored.
+  ///
+  /// All nodes on the bus have to set an address, [`BROADCAST`] must not be used.
+  #[builder(setter(strip_option), default = "None")]
+  address: Option<u8>,
 
-  #[builder(private, default = "Runtime::new().map_err(Error::RuntimeCreation).map_err(|e| e.to_string())?")]
-  runtime: Runtime,
+  /// Address of the node [`Call`]s are sent to if their [`Metadata`] does not select one with the [`TARGET`] key.
+  #[builder(setter(strip_option), default = "None")]
+  target: Option<u8>,
+
+  #[builder(private, default = "Arc::new(AtomicU64::new(0))")]
+  next_id: Arc<AtomicU64>,
+
+  #[allow(clippy::type_complexity)]
+  #[builder(private, default = "Arc::new(std::sync::Mutex::new(HashMap::new()))")]
+  pending: Arc<std::sync::Mutex<HashMap<u64, Waiting<C::Intermediate>>>>,
+
+  #[builder(private, default = "None")]
+  runtime: Option<Runtime>,
 
   #[builder(private, default = "None")]
-  handle: Option<tokio::task::JoinHandle<std::convert::Infallible>>,
+  handle: Option<tokio::task::JoinHandle<()>>,
 }
 
-impl SerialPortBuilder {
+impl<C: Codec> SerialPortBuilder<C> {
+  /// Uses the opened serial port. It is not reopened after it was disconnected unless [`reopen`](Self::reopen) is set as well.
+  ///
+  /// The serial port has to support [`try_clone`](serialport::SerialPort::try_clone) as a dedicated thread reads from a clone while calls and replies are written.
   pub fn port<S: 'static + serialport::SerialPort>(self, value: S) -> Self {
-    self.port_setter(Arc::new(Mutex::new(Box::new(value))))
+    self.port_setter(Arc::new(Mutex::new(Some(Box::new(value)))))
+  }
+
+  /// Opens the serial port at the `path` with the `baud_rate` and reopens it after it was disconnected.
+  pub fn path<P: Into<String>>(self, path: P, baud_rate: u32) -> Self {
+    self.settings(serialport::new(path.into(), baud_rate).timeout(Duration::from_millis(10)))
+  }
+
+  /// Opens the serial port with the `settings` and reopens it after it was disconnected.
+  pub fn settings(self, settings: serialport::SerialPortBuilder) -> Self {
+    self.reopen(move || settings.clone().open())
+  }
+
+  /// Opens the serial port with `open` and calls it again to reopen the serial port after it was disconnected.
+  pub fn reopen<F: Fn() -> serialport::Result<Box<dyn serialport::SerialPort>> + Send + Sync + 'static>(self, open: F) -> Self {
+    self.reopen_setter(Some(Arc::new(open)))
+  }
+
+  /// Calls `on_state` with the new [`State`] whenever the connection to the serial port changes.
+  pub fn on_state<F: Fn(State) + Send + Sync + 'static>(self, on_state: F) -> Self {
+    self.on_state_setter(Some(Arc::new(on_state)))
+  }
+
+  fn validate(&self) -> std::result::Result<(), String> {
+    match (&self.port, &self.open) {
+      (None, None | Some(None)) => Err("either `port`, `path`, `settings` or `reopen` must be set".into()),
+      _ => Ok(()),
+    }
   }
 }
 
 impl SerialPort {
-  pub fn builder() -> SerialPortBuilder {
+  /// Creates a builder for a [`SerialPort`] backend using the [`Ron`] [`Codec`].
+  pub fn builder() -> SerialPortBuilder<Ron> {
     SerialPortBuilder::default()
   }
 }
 
-#[derive(Serialize, Deserialize)]
-struct SelfCall {
-  procedure: String,
-  payload: String,
+impl<C: Codec> SerialPort<C> {
+  /// Creates a builder for a [`SerialPort`] backend using the [`Codec`] `C`.
+  ///
+  /// The frames are encoded with the [`Codec`], binary [`Codec`]s are sent as hexadecimal text.
+  pub fn builder_with_codec() -> SerialPortBuilder<C> {
+    SerialPortBuilder::default()
+  }
 }
 
-#[derive(Serialize, Deserialize)]
-struct SelfReply {
-  payload: String,
+/// Call waiting for its reply frames.
+enum Waiting<T> {
+  Reply(oneshot::Sender<Result<SelfReply<T>, RemoteError>>),
+  Stream(std::sync::mpsc::Sender<Result<T, RemoteError>>),
+  /// Upload waiting for its reply and for the `a:` frames acknowledging its chunks.
+  Upload(oneshot::Sender<Result<SelfReply<T>, RemoteError>>, mpsc::UnboundedSender<()>),
 }
 
-impl SerialPort {
+/// Serializes a [`SelfReplyFrame`] to a `r:` frame.
+fn reply_frame<C: Codec>(framing: Framing, route: Option<Route>, id: Option<u64>, reply: Result<SelfReply<C::Intermediate>, RemoteError>, end: bool) -> Result<Vec<u8>> {
+  match serialize::<C, _>(framing, framing::REPLY, route, &SelfReplyFrame { id, reply, end }) {
+    Ok(ser) => Ok(ser),
+    Err(e) => serialize::<C, _>(
+      framing,
+      framing::REPLY,
+      route,
+      &SelfReplyFrame::<C::Intermediate> {
+        id,
+        reply: Err(RemoteError::new(RemoteError::INTERNAL_ERROR, e)),
+        end,
+      },
+    ),
+  }
+}
+
+/// Serializes a [`SelfChunkFrame`] to a `u:` frame.
+fn chunk_frame<C: Codec>(framing: Framing, route: Option<Route>, id: u64, chunk: Option<Result<C::Intermediate, RemoteError>>) -> Result<Vec<u8>> {
+  serialize::<C, _>(framing, framing::CHUNK, route, &SelfChunkFrame { id, chunk })
+}
+
+/// Serializes a [`SelfAckFrame`] to a `a:` frame.
+fn ack_frame<C: Codec>(framing: Framing, route: Option<Route>, id: u64) -> Result<Vec<u8>> {
+  serialize::<C, _>(framing, framing::ACK, route, &SelfAckFrame { id })
+}
+
+/// Writes a `r:` or `a:` frame to the `port`. The frame is dropped if it could not be serialized or the `port` is disconnected.
+fn write_frame(port: &mut Option<Box<dyn serialport::SerialPort>>, frame: Result<Vec<u8>>) {
+  let frame = match frame {
+    Ok(frame) => frame,
+    Err(e) => {
+      error!("dropping frame as it could not be serialized: {:?}", e);
+      return;
+    }
+  };
+
+  let port = match port {
+    Some(port) => port,
+    None => {
+      debug!("dropping frame as the serial port is disconnected");
+      return;
+    }
+  };
+
+  // like `write_all`, but the write is retried if it timed out so the rest of a partly written frame is not lost
+  let (mut written, mut timeouts) = (0, 0);
+  while written < frame.len() {
+    match port.write(&frame[written..]) {
+      Ok(0) => {
+        error!("{} wrote zero bytes of frame", port.name().unwrap_or_default());
+        return;
+      }
+      Ok(n) => written += n,
+      Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => (),
+      Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut && timeouts < 2 => timeouts += 1,
+      Err(e) => {
+        error!("{:?}", e);
+        return;
+      }
+    }
+  }
+
+  debug!("{} sent frame of {} bytes", port.name().unwrap_or_default(), written);
+}
+
+/// Writes a `c:` or `u:` frame to the `port`.
+async fn send_frame(port: &Mutex<Option<Box<dyn serialport::SerialPort>>>, frame: &[u8]) -> Result<()> {
+  match port.lock().await.as_mut() {
+    Some(port) => port.write_all(frame).map_err(|e| Error::SendError(e).into()),
+    None => Err(Error::Disconnected.into()),
+  }
+}
+
+/// Returns the error of a call whose reply sender was dropped, pending calls are dropped when the serial port is disconnected.
+fn no_reply(connected: &AtomicBool, id: u64) -> anyhow::Error {
+  if connected.load(Ordering::Relaxed) {
+    Error::NoReply(id).into()
+  } else {
+    Error::Disconnected.into()
+  }
+}
+
+/// Stores the [`State`] and calls `on_state` if it changed.
+fn set_state(connected: &AtomicBool, on_state: &Option<Arc<dyn Fn(State) + Send + Sync>>, state: State) {
+  if connected.swap(state == State::Connected, Ordering::Relaxed) != (state == State::Connected) {
+    info!("serial port {:?}", state);
+
+    if let Some(on_state) = on_state {
+      on_state(state);
+    }
+  }
+}
+
+/// Timeout of the reads of the reader thread. It only delays noticing that the listener was stopped, as reads return as soon as bytes arrive.
+const READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Events sent from the reader thread to the listener.
+enum Event {
+  /// The serial port with the name was opened.
+  Connected(String),
+  /// Bytes were read from the serial port.
+  Read(Vec<u8>),
+  /// The serial port was disconnected.
+  Disconnected,
+}
+
+/// Clones the `port` for the reader thread so reading never holds the lock writers wait on.
+fn reader(port: &dyn serialport::SerialPort) -> Result<Box<dyn serialport::SerialPort>> {
+  let mut reader = port.try_clone().map_err(Error::Clone)?;
+  reader.set_timeout(READ_TIMEOUT).map_err(Error::Clone)?;
+  Ok(reader)
+}
+
+/// Awaits the `future` and fails with [`merfolk::Error::Timeout`] if the `timeout` elapses first.
+async fn timeout<T>(procedure: &str, timeout: Option<Duration>, future: impl Future<Output = Result<T>>) -> Result<T> {
+  match timeout {
+    None => future.await,
+    Some(timeout) => tokio::time::timeout(timeout, future).await.map_err(|_| merfolk::Error::Timeout {
+      procedure: procedure.to_string(),
+      timeout,
+    })?,
+  }
+}
+
+/// Returns the private [`Runtime`] used by the synchronous [`Backend`] implementation. The [`Runtime`] is created on first use.
+fn runtime(runtime: &mut Option<Runtime>) -> Result<&Runtime> {
+  if runtime.is_none() {
+    *runtime = Some(Runtime::new().map_err(Error::RuntimeCreation)?);
+  }
+
+  Ok(runtime.as_ref().unwrap())
+}
+
+impl<C: Codec> SerialPort<C> {
+  /// Starts listening on the serial port on the private [`Runtime`].
   pub fn start(&mut self) -> Result<()> {
+    let handle = runtime(&mut self.runtime)?.handle().clone();
+
+    self.start_on(&handle)
+  }
+
+  /// Starts listening on the serial port on the [`Runtime`] of the `handle`.
+  pub fn start_on(&mut self, handle: &Handle) -> Result<()> {
     trace!("start SerialPort Backend");
 
     if self.handle.is_some() {
@@ -83,133 +368,689 @@ impl SerialPort {
     }
 
     let receiver = Arc::clone(self.receiver.as_ref().ok_or(Error::NoReceiver)?);
+    let stream_receiver = Arc::clone(&self.stream_receiver);
+    let upload_receiver = Arc::clone(&self.upload_receiver);
 
-    let (tx, rx) = tokio::sync::mpsc::channel::<String>(2);
+    let pending = Arc::clone(&self.pending);
 
-    self.reply_queue = Some(Arc::new(Mutex::new(rx)));
+    // the port is opened before listening so calls can be sent right away
+    let serial = match self.port.try_lock() {
+      Ok(mut port_gate) => {
+        if let (None, Some(open)) = (port_gate.as_ref(), &self.open) {
+          match open() {
+            Ok(opened) => *port_gate = Some(opened),
+            Err(e) => warn!("opening serial port failed: {}", e),
+          }
+        }
+
+        port_gate.as_deref().map(reader).transpose()?
+      }
+      // the reader thread clones the port once the writer released it
+      Err(_) => None,
+    };
+    set_state(&self.connected, &self.on_state, if serial.is_some() { State::Connected } else { State::Disconnected });
 
     let port = Arc::clone(&self.port);
+    let framing = self.framing;
+    let malformed = Arc::clone(&self.malformed);
+    let address = self.address;
+    let open = self.open.clone();
+    let connected = Arc::clone(&self.connected);
+    let on_state = self.on_state.clone();
+    let (min_backoff, max_backoff) = (self.backoff, self.max_backoff);
 
-    self.handle = Some(self.runtime.spawn(async move {
-      trace!("spawn listener");
+    let (events, mut listener) = mpsc::unbounded_channel();
 
-      loop {
-        trace!("reading serialport");
+    // the reader thread blocks on reads of its own clone of the port, so writers only wait for other writers
+    std::thread::spawn({
+      let port = Arc::clone(&port);
+      move || {
+        trace!("spawn reader");
 
-        let mut read: Vec<u8> = vec![];
+        let mut serial = serial;
+        if let Some(serial) = &serial {
+          events.send(Event::Connected(serial.name().unwrap_or_default())).ok();
+        }
+        let mut backoff = min_backoff;
 
-        let mut port_gate = port.lock().await;
+        // the reader stops when the listener was stopped
+        while !events.is_closed() {
+          let reading = match serial.as_mut() {
+            Some(reading) => reading,
+            None => {
+              let mut port_gate = port.blocking_lock();
 
-        loop {
-          let mut buf: Vec<u8> = vec![0; 1024];
+              if let (None, Some(open)) = (port_gate.as_ref(), open.as_ref()) {
+                match open() {
+                  Ok(opened) => *port_gate = Some(opened),
+                  Err(e) => debug!("reopening serial port failed: {}", e),
+                }
+              }
 
-          match port_gate.read(buf.as_mut_slice()) {
-            Ok(n) => {
-              debug!("{} read {} bytes", port_gate.name().unwrap_or_else(|| "".to_string()), n);
-              read.append(&mut buf[0..n].to_vec());
+              match port_gate.as_deref().map(reader) {
+                Some(Ok(reading)) => {
+                  drop(port_gate);
 
-              if n != buf.len() {
-                break;
+                  backoff = min_backoff;
+                  set_state(&connected, &on_state, State::Connected);
+                  events.send(Event::Connected(reading.name().unwrap_or_default())).ok();
+                  serial = Some(reading);
+                  continue;
+                }
+                Some(Err(e)) => {
+                  error!("{}", e);
+                  *port_gate = None;
+                }
+                None => (),
               }
+
+              drop(port_gate);
+              std::thread::sleep(backoff);
+              backoff = (backoff * 2).min(max_backoff);
+              continue;
             }
-            Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => {
-              debug!("{} read timeout", port_gate.name().unwrap_or_else(|| "".to_string()));
-              break;
+          };
+
+          let mut buf: Vec<u8> = vec![0; 1024];
+
+          match reading.read(buf.as_mut_slice()) {
+            Ok(n) => {
+              trace!("read {} bytes", n);
+              buf.truncate(n);
+              events.send(Event::Read(buf)).ok();
             }
+            Err(ref e) if matches!(e.kind(), std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock | std::io::ErrorKind::Interrupted) => (),
             Err(e) => {
-              error!("{:?}", e);
-              break;
+              error!("{} read failed: {}", reading.name().unwrap_or_default(), e);
+
+              serial = None;
+              *port.blocking_lock() = None;
+              set_state(&connected, &on_state, State::Disconnected);
+              events.send(Event::Disconnected).ok();
             }
           }
         }
 
-        if !read.is_empty() {
-          if let Ok(read_string) = String::from_utf8(read) {
-            let read_parts = read_string.split("\r\n");
+        trace!("stop reader");
+      }
+    });
+
+    self.handle = Some(handle.spawn(async move {
+      trace!("spawn listener");
+
+      // frames are kept until they are complete, they can span several reads
+      let mut read: Vec<u8> = vec![];
+
+      // uploads of which the receiving procedure still consumes chunks
+      let mut uploads: HashMap<u64, std::sync::mpsc::Sender<Result<C::Intermediate, RemoteError>>> = HashMap::new();
+
+      let mut name = String::new();
+
+      while let Some(event) = listener.recv().await {
+        match event {
+          Event::Connected(port_name) => {
+            name = port_name;
+            continue;
+          }
+          Event::Read(mut bytes) => {
+            debug!("{} read {} bytes", name, bytes.len());
+            read.append(&mut bytes);
+          }
+          Event::Disconnected => {
+            read.clear();
+
+            // uploads fail as their remaining chunks are lost, ending them would pass on a truncated upload
+            for (_, tx) in uploads.drain() {
+              tx.send(Err(RemoteError::from(anyhow::Error::from(Error::Disconnected)))).ok();
+            }
 
-            for part in read_parts {
-              if part.is_empty() {
-                break;
+            // pending calls fail as their replies are lost
+            if let Ok(mut pending) = pending.lock() {
+              for (_, waiting) in pending.drain() {
+                if let Waiting::Stream(tx) = waiting {
+                  tx.send(Err(RemoteError::from(anyhow::Error::from(Error::Disconnected)))).ok();
+                }
               }
+            }
+            continue;
+          }
+        }
 
-              match &part[0..2] {
-                "r:" => {
-                  debug!("{} read reply", port_gate.name().unwrap_or_else(|| "".to_string()));
-
-                  match tx.send(part[2..].to_string()).await {
-                    Ok(_) => {}
-                    Err(e) => {
-                      for _ in 0..2 {
-                        match port_gate.write(("r:".to_string() + &Self::serialize(&Err::<SelfReply, _>(e.to_string())).unwrap() + "\r\n").as_bytes()) {
-                          Ok(n) => {
-                            debug!("{} sent r: {} bytes", port_gate.name().unwrap_or_else(|| "".to_string()), n);
-                            break;
-                          }
-                          Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => (),
-                          Err(e) => log::error!("{:?}", e),
-                        }
-                      }
-                    }
+        while let Some(frame) = framing.next(&mut read) {
+          let (kind, body) = match frame {
+            Ok(frame) => frame,
+            Err(e) => {
+              warn!("dropping malformed frame: {}", e);
+              malformed.fetch_add(1, Ordering::Relaxed);
+              continue;
+            }
+          };
+
+          // frames of addressed nodes start with their route, frames for other nodes are ignored
+          let (route, body) = match address {
+            None => (None, body),
+            Some(address) => match framing.route(body) {
+              Ok((route, _)) if route.destination != address && route.destination != BROADCAST => {
+                trace!("ignoring frame for node {}", route.destination);
+                continue;
+              }
+              Ok((route, _)) if route.destination == BROADCAST && kind != framing::CALL => {
+                warn!("dropping broadcast frame of kind {:?}", kind as char);
+                malformed.fetch_add(1, Ordering::Relaxed);
+                continue;
+              }
+              Ok((route, body)) => (Some(route), body),
+              Err(e) => {
+                warn!("dropping frame without route: {}", e);
+                malformed.fetch_add(1, Ordering::Relaxed);
+                continue;
+              }
+            },
+          };
+          let broadcast = route.is_some_and(|route| route.destination == BROADCAST);
+          // replies and acknowledgements are sent back to the source of the frame
+          let reply_route = address.zip(route).map(|(source, route)| Route { source, destination: route.source });
+
+          match kind {
+            framing::REPLY => {
+              debug!("{} read reply", name);
+
+              let frame = match deserialize::<C, SelfReplyFrame<C::Intermediate>>(framing, &body) {
+                Ok(frame) => frame,
+                Err(e) => {
+                  warn!("dropping malformed reply: {}", e);
+                  malformed.fetch_add(1, Ordering::Relaxed);
+                  continue;
+                }
+              };
+
+              // streams stay pending until their end frame
+              let waiting = frame.id.and_then(|id| {
+                let mut pending = pending.lock().ok()?;
+                match (pending.get(&id)?, frame.end) {
+                  (Waiting::Stream(tx), false) => Some(Waiting::Stream(tx.clone())),
+                  _ => pending.remove(&id),
+                }
+              });
+              match waiting {
+                Some(Waiting::Reply(tx)) => {
+                  tx.send(frame.reply).ok();
+                }
+                Some(Waiting::Stream(tx)) => {
+                  if !frame.end {
+                    tx.send(frame.reply.map(|r| r.payload)).ok();
                   }
                 }
-                "c:" => {
-                  debug!("{} read call", port_gate.name().unwrap_or_else(|| "".to_string()));
-
-                  let read_unpacked = part[2..].to_string();
-
-                  let self_reply_string = match Self::deserialize::<SelfCall>(&read_unpacked) {
-                    Ok(self_call) => {
-                      let reply = receiver(Call {
-                        procedure: self_call.procedure,
-                        payload: self_call.payload,
-                      });
-
-                      let self_reply = match reply.map(|r| SelfReply { payload: r.payload }) {
-                        Ok(ok) => std::result::Result::Ok(ok),
-                        Err(err) => std::result::Result::Err(err.to_string()),
-                      };
-                      match &Self::serialize(&self_reply) {
-                        Ok(ser) => "r:".to_string() + ser + "\r\n",
-                        Err(e) => "r:".to_string() + &Self::serialize(&Err::<SelfReply, _>(e.to_string())).unwrap() + "\r\n",
-                      }
+                Some(Waiting::Upload(tx, _)) => {
+                  tx.send(frame.reply).ok();
+                }
+                None => warn!("dropping reply for unknown call {:?}", frame.id),
+              }
+            }
+            framing::CALL => {
+              debug!("{} read call", name);
+
+              let self_reply_frame = match deserialize::<C, SelfCall<C::Intermediate>>(framing, &body) {
+                // broadcasts are never answered as the replies would collide on the bus
+                Ok(self_call) if broadcast && !self_call.metadata.is_notification() => {
+                  warn!("dropping broadcast call {} which is not a notification", self_call.procedure);
+                  malformed.fetch_add(1, Ordering::Relaxed);
+                  continue;
+                }
+                Err(e) if broadcast => {
+                  warn!("dropping malformed broadcast call: {}", e);
+                  malformed.fetch_add(1, Ordering::Relaxed);
+                  continue;
+                }
+                Ok(self_call) if self_call.metadata.is_notification() => {
+                  let call_timeout = self_call.metadata.timeout();
+                  let reply = receiver(Call {
+                    procedure: self_call.procedure.clone(),
+                    payload: self_call.payload,
+                    metadata: self_call.metadata,
+                  });
+
+                  // notifications are not answered with a `r:` frame
+                  tokio::spawn(async move {
+                    if let Err(e) = timeout(&self_call.procedure, call_timeout, reply).await {
+                      warn!("notification {} failed: {}", self_call.procedure, e);
+                    }
+                  });
+                  continue;
+                }
+                Ok(self_call) if self_call.metadata.is_upload() => {
+                  let id = self_call.id;
+                  let call = Call {
+                    procedure: self_call.procedure,
+                    payload: self_call.payload,
+                    metadata: self_call.metadata,
+                  };
+
+                  let upload_receiver = match upload_receiver.lock().ok().and_then(|r| r.clone()) {
+                    Some(upload_receiver) => upload_receiver,
+                    None => {
+                      let unsupported = RemoteError::from(anyhow::Error::from(merfolk::Error::Unsupported("uploads".into())));
+                      write_frame(&mut *port.lock().await, reply_frame::<C>(framing, reply_route, Some(id), Err(unsupported), false));
+                      continue;
                     }
-                    Err(e) => "r:".to_string() + &Self::serialize(&Err::<SelfReply, _>(e.to_string())).unwrap() + "\r\n",
                   };
 
-                  for _ in 0..2 {
-                    match port_gate.write(self_reply_string.as_bytes()) {
-                      Ok(n) => {
-                        debug!("{} sent r: {} bytes", port_gate.name().unwrap_or_else(|| "".to_string()), n);
-                        break;
-                      }
-                      Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => (),
-                      Err(e) => log::error!("{:?}", e),
+                  let (tx, rx) = std::sync::mpsc::channel();
+                  uploads.insert(id, tx);
+
+                  let port = Arc::clone(&port);
+                  let handle = Handle::current();
+
+                  // the chunks are consumed by a blocking iterator which acknowledges every consumed chunk
+                  tokio::task::spawn_blocking(move || {
+                    let chunks = {
+                      let port = Arc::clone(&port);
+                      let handle = handle.clone();
+                      rx.into_iter().map(move |chunk: Result<C::Intermediate, RemoteError>| {
+                        write_frame(&mut handle.block_on(port.lock()), ack_frame::<C>(framing, reply_route, id));
+                        chunk.map_err(anyhow::Error::from)
+                      })
+                    };
+
+                    let self_reply = upload_receiver(call, CallStream::new(chunks))
+                      .map(|r| SelfReply {
+                        payload: r.payload,
+                        metadata: r.metadata,
+                      })
+                      .map_err(RemoteError::from);
+
+                    write_frame(&mut handle.block_on(port.lock()), reply_frame::<C>(framing, reply_route, Some(id), self_reply, false));
+                  });
+                  continue;
+                }
+                Ok(self_call) if self_call.metadata.is_stream() => {
+                  let call = Call {
+                    procedure: self_call.procedure.clone(),
+                    payload: self_call.payload,
+                    metadata: self_call.metadata,
+                  };
+
+                  let stream_receiver = stream_receiver.lock().ok().and_then(|r| r.clone());
+                  let receiver = Arc::clone(&receiver);
+                  let port = Arc::clone(&port);
+                  let handle = Handle::current();
+
+                  // the items are produced by a blocking iterator, each one is written as soon as it was produced
+                  tokio::task::spawn_blocking(move || {
+                    let stream = match stream_receiver {
+                      Some(stream_receiver) => stream_receiver(call),
+                      None => handle.block_on(receiver(call)).map(|reply| ReplyStream::once(Ok(reply.payload))),
+                    };
+
+                    let items: Box<dyn Iterator<Item = Result<C::Intermediate>>> = match stream {
+                      Ok(stream) => Box::new(stream),
+                      Err(e) => Box::new(std::iter::once(Err(e))),
+                    };
+
+                    for item in items {
+                      let self_reply = item.map(|payload| SelfReply { payload, metadata: Metadata::new() }).map_err(RemoteError::from);
+                      write_frame(&mut handle.block_on(port.lock()), reply_frame::<C>(framing, reply_route, Some(self_call.id), self_reply, false));
                     }
+
+                    let end = C::encode(&())
+                      .map(|payload| SelfReply { payload, metadata: Metadata::new() })
+                      .map_err(|e| RemoteError::new(RemoteError::INTERNAL_ERROR, e));
+                    write_frame(&mut handle.block_on(port.lock()), reply_frame::<C>(framing, reply_route, Some(self_call.id), end, true));
+                  });
+                  continue;
+                }
+                Ok(self_call) => {
+                  let call_timeout = self_call.metadata.timeout();
+                  let reply = receiver(Call {
+                    procedure: self_call.procedure.clone(),
+                    payload: self_call.payload,
+                    metadata: self_call.metadata,
+                  });
+                  let port = Arc::clone(&port);
+
+                  // the call is answered on its own task so the replies of this node's calls are still read while the procedure runs
+                  tokio::spawn(async move {
+                    let self_reply = timeout(&self_call.procedure, call_timeout, reply)
+                      .await
+                      .map(|r| SelfReply {
+                        payload: r.payload,
+                        metadata: r.metadata,
+                      })
+                      .map_err(RemoteError::from);
+
+                    write_frame(&mut *port.lock().await, reply_frame::<C>(framing, reply_route, Some(self_call.id), self_reply, false));
+                  });
+                  continue;
+                }
+                Err(e) => {
+                  malformed.fetch_add(1, Ordering::Relaxed);
+                  reply_frame::<C>(framing, reply_route, None, Err(RemoteError::new(RemoteError::PARSE_ERROR, e)), false)
+                }
+              };
+
+              write_frame(&mut *port.lock().await, self_reply_frame);
+            }
+            framing::CHUNK => {
+              debug!("{} read chunk", name);
+
+              let frame = match deserialize::<C, SelfChunkFrame<C::Intermediate>>(framing, &body) {
+                Ok(frame) => frame,
+                Err(e) => {
+                  warn!("dropping malformed chunk: {}", e);
+                  malformed.fetch_add(1, Ordering::Relaxed);
+                  continue;
+                }
+              };
+
+              match (frame.chunk, uploads.get(&frame.id)) {
+                (Some(chunk), Some(tx)) => {
+                  if tx.send(chunk).is_err() {
+                    debug!("upload {} was dropped", frame.id);
+                    uploads.remove(&frame.id);
                   }
                 }
-                _ => {}
+                (None, _) => {
+                  uploads.remove(&frame.id);
+                }
+                (Some(_), None) => debug!("dropping chunk for unknown upload {}", frame.id),
               }
             }
-          };
+            framing::ACK => {
+              debug!("{} read acknowledgement", name);
+
+              let frame = match deserialize::<C, SelfAckFrame>(framing, &body) {
+                Ok(frame) => frame,
+                Err(e) => {
+                  warn!("dropping malformed acknowledgement: {}", e);
+                  malformed.fetch_add(1, Ordering::Relaxed);
+                  continue;
+                }
+              };
+
+              if let Some(Waiting::Upload(_, acks)) = pending.lock().ok().as_ref().and_then(|pending| pending.get(&frame.id)) {
+                acks.send(()).ok();
+              }
+            }
+            kind => {
+              warn!("dropping frame of unknown kind {:?}", kind as char);
+              malformed.fetch_add(1, Ordering::Relaxed);
+            }
+          }
         }
       }
     }));
     Ok(())
   }
 
+  /// Returns the [`State`] of the connection to the serial port.
+  pub fn state(&self) -> State {
+    if self.connected.load(Ordering::Relaxed) {
+      State::Connected
+    } else {
+      State::Disconnected
+    }
+  }
+
+  /// Returns the number of malformed frames which were dropped since the [`SerialPort`] was built.
+  pub fn malformed_frames(&self) -> u64 {
+    self.malformed.load(Ordering::Relaxed)
+  }
+
   pub fn stop(&mut self) -> Result<()> {
     trace!("stop serialport backend");
-    match &self.handle {
-      None => Err(Error::NotStarted.into()),
-      Some(handle) => {
-        handle.abort();
-        Ok(())
+    self.handle.take().ok_or(Error::NotStarted)?.abort();
+    self.pending.lock().map_err(|_| Error::Lock)?.clear();
+    Ok(())
+  }
+
+  /// Returns the [`Route`] to the node selected by the [`TARGET`] key of the [`Metadata`] or the `target` if this node is addressed. The [`TARGET`] key is removed from the `call`.
+  fn route(&self, call: &mut Call<C::Intermediate>) -> Result<Option<Route>> {
+    let target = call.metadata.remove(TARGET);
+
+    let source = match self.address {
+      None => return Ok(None),
+      Some(source) => source,
+    };
+
+    let destination = match target {
+      Some(target) => target.parse().map_err(|_| Error::InvalidTarget(target))?,
+      None => self.target.ok_or(Error::NoTarget)?,
+    };
+    if destination == BROADCAST && !call.metadata.is_notification() {
+      return Err(Error::Broadcast.into());
+    }
+
+    Ok(Some(Route { source, destination }))
+  }
+
+  fn request(&self, mut call: Call<C::Intermediate>) -> Result<impl std::future::Future<Output = Result<Reply<C::Intermediate>>>> {
+    if self.handle.is_none() {
+      return Err(Error::NotStarted.into());
+    }
+
+    let port = Arc::clone(&self.port);
+    let pending = Arc::clone(&self.pending);
+    let connected = Arc::clone(&self.connected);
+    let framing = self.framing;
+    let route = self.route(&mut call)?;
+
+    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+    Ok(async move {
+      let procedure = call.procedure.clone();
+      let call_timeout = call.metadata.timeout();
+      let notification = call.metadata.is_notification();
+
+      let self_call = SelfCall {
+        id,
+        procedure: call.procedure,
+        payload: call.payload,
+        metadata: call.metadata,
+      };
+      let self_call_frame = serialize::<C, _>(framing, framing::CALL, route, &self_call)?;
+
+      let (tx, rx) = oneshot::channel();
+      if !notification {
+        pending.lock().map_err(|_| Error::Lock)?.insert(id, Waiting::Reply(tx));
       }
+
+      match send_frame(&port, &self_call_frame).await {
+        Ok(()) if notification => {
+          debug!("sent notification c: {}", id);
+
+          Ok(Reply {
+            payload: C::encode(&())?,
+            metadata: Metadata::new(),
+          })
+        }
+        Ok(()) => {
+          debug!("sent c: {}", id);
+
+          let self_reply = match timeout(&procedure, call_timeout, async { rx.await.map_err(|_| no_reply(&connected, id)) }).await {
+            Ok(self_reply) => self_reply,
+            Err(err) => {
+              pending.lock().map_err(|_| Error::Lock)?.remove(&id);
+              return Err(err);
+            }
+          }
+          .map_err(|e| e.into_error(&procedure, call_timeout))?;
+
+          Ok(Reply {
+            payload: self_reply.payload,
+            metadata: self_reply.metadata,
+          })
+        }
+        Err(e) => {
+          pending.lock().map_err(|_| Error::Lock)?.remove(&id);
+          Err(e)
+        }
+      }
+    })
+  }
+}
+
+impl<C: Codec> SerialPort<C> {
+  fn request_stream(&self, mut call: Call<C::Intermediate>) -> Result<impl std::future::Future<Output = Result<ReplyStream<C::Intermediate>>>> {
+    if self.handle.is_none() {
+      return Err(Error::NotStarted.into());
     }
+
+    let port = Arc::clone(&self.port);
+    let pending = Arc::clone(&self.pending);
+    let framing = self.framing;
+    let route = self.route(&mut call)?;
+
+    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+    Ok(async move {
+      let procedure = call.procedure.clone();
+      let call_timeout = call.metadata.timeout();
+
+      let self_call = SelfCall {
+        id,
+        procedure: call.procedure,
+        payload: call.payload,
+        metadata: call.metadata,
+      };
+      let self_call_frame = serialize::<C, _>(framing, framing::CALL, route, &self_call)?;
+
+      let (tx, rx) = std::sync::mpsc::channel();
+      pending.lock().map_err(|_| Error::Lock)?.insert(id, Waiting::Stream(tx));
+
+      match send_frame(&port, &self_call_frame).await {
+        Ok(()) => {
+          debug!("sent stream c: {}", id);
+
+          Ok(ReplyStream::receive(rx, procedure.clone(), call_timeout, move |item| {
+            item.map_err(|e| e.into_error(&procedure, call_timeout))
+          }))
+        }
+        Err(e) => {
+          pending.lock().map_err(|_| Error::Lock)?.remove(&id);
+          Err(e)
+        }
+      }
+    })
   }
+
+  fn request_upload(&self, mut call: Call<C::Intermediate>, chunks: CallStream<C::Intermediate>) -> Result<impl std::future::Future<Output = Result<Reply<C::Intermediate>>>> {
+    if self.handle.is_none() {
+      return Err(Error::NotStarted.into());
+    }
+
+    let port = Arc::clone(&self.port);
+    let pending = Arc::clone(&self.pending);
+    let connected = Arc::clone(&self.connected);
+    let framing = self.framing;
+    let route = self.route(&mut call)?;
+    let window = self.upload_window;
+
+    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+    Ok(async move {
+      let procedure = call.procedure.clone();
+      let call_timeout = call.metadata.timeout();
+
+      let self_call = SelfCall {
+        id,
+        procedure: call.procedure,
+        payload: call.payload,
+        metadata: call.metadata,
+      };
+      let self_call_frame = serialize::<C, _>(framing, framing::CALL, route, &self_call)?;
+
+      let (tx, rx) = oneshot::channel();
+      let (acks_tx, mut acks) = mpsc::unbounded_channel();
+      pending.lock().map_err(|_| Error::Lock)?.insert(id, Waiting::Upload(tx, acks_tx));
+
+      let sent = async {
+        send_frame(&port, &self_call_frame).await?;
+        debug!("sent upload c: {}", id);
+
+        let mut credits = window;
+        for chunk in chunks {
+          // the acknowledgements are dropped when the reply was received before all chunks were sent
+          loop {
+            match acks.try_recv() {
+              Ok(()) => credits += 1,
+              Err(mpsc::error::TryRecvError::Empty) => break,
+              Err(mpsc::error::TryRecvError::Disconnected) => return Ok(()),
+            }
+          }
+
+          // waits until the receiving procedure consumed a chunk sent before
+          if credits == 0 {
+            match acks.recv().await {
+              Some(()) => credits += 1,
+              None => return Ok(()),
+            }
+          }
+          credits -= 1;
+
+          match chunk {
+            Ok(chunk) => send_frame(&port, &chunk_frame::<C>(framing, route, id, Some(Ok(chunk)))?).await?,
+            Err(e) => {
+              send_frame(&port, &chunk_frame::<C>(framing, route, id, Some(Err(RemoteError::new(RemoteError::INTERNAL_ERROR, &e))))?).await?;
+              send_frame(&port, &chunk_frame::<C>(framing, route, id, None)?).await?;
+              return Err(e);
+            }
+          }
+        }
+
+        send_frame(&port, &chunk_frame::<C>(framing, route, id, None)?).await?;
+        Ok(())
+      };
+
+      // sending the chunks counts towards the timeout, a receiving procedure which stops acknowledging them fails the call
+      let replied = timeout(&procedure, call_timeout, async {
+        sent.await?;
+        rx.await.map_err(|_| no_reply(&connected, id))
+      });
+
+      let self_reply = match replied.await {
+        Ok(self_reply) => self_reply,
+        Err(err) => {
+          pending.lock().map_err(|_| Error::Lock)?.remove(&id);
+          return Err(err);
+        }
+      }
+      .map_err(|e| e.into_error(&procedure, call_timeout))?;
+
+      Ok(Reply {
+        payload: self_reply.payload,
+        metadata: self_reply.metadata,
+      })
+    })
+  }
+}
+
+/// Encodes a frame of the `kind` with the [`Codec`] `C` and wraps it with the [`Framing`] and the [`Route`].
+fn serialize<C: Codec, T: serde::Serialize>(framing: Framing, kind: u8, route: Option<Route>, from: &T) -> Result<Vec<u8>> {
+  trace!("serialize from");
+
+  let encoded = C::encode(from)?;
+  match framing {
+    Framing::Lines => framing.wrap(kind, route, encoded.into_line().as_bytes()),
+    _ => framing.wrap(kind, route, &encoded.into_bytes()),
+  }
+}
+
+/// Decodes the body of a frame unwrapped by the [`Framing`] with the [`Codec`] `C`.
+fn deserialize<C: Codec, T>(framing: Framing, from: &[u8]) -> Result<T>
+where
+  T: for<'de> serde::Deserialize<'de>,
+{
+  trace!("deserialize from");
+
+  let encoded = match framing {
+    Framing::Lines => C::Intermediate::from_line(std::str::from_utf8(from).map_err(|e| Error::MalformedFrame(e.to_string()))?)?,
+    _ => C::Intermediate::from_bytes(from.to_vec())?,
+  };
+  C::decode(&encoded)
 }
-impl Backend for SerialPort {
-  type Intermediate = String;
+
+impl<C: Codec> Backend for SerialPort<C> {
+  type Intermediate = C::Intermediate;
 
   fn register<T>(&mut self, receiver: T) -> Result<()>
   where
@@ -217,11 +1058,16 @@ impl Backend for SerialPort {
   {
     trace!("register receiver");
 
-    self.receiver = Some(Arc::new(move |call: Call<String>| {
+    let receiver = Arc::new(receiver);
+    self.receiver = Some(Arc::new(move |call: Call<C::Intermediate>| {
       trace!("run receiver");
 
-      debug!("calling receiver");
-      receiver(call)
+      let receiver = Arc::clone(&receiver);
+      // the blocking receiver runs on its own thread so the timeout of the call can elapse, a late reply is dropped
+      Box::pin(async move {
+        debug!("calling receiver");
+        tokio::task::spawn_blocking(move || receiver(call)).await?
+      })
     }));
 
     self.start().ok();
@@ -234,66 +1080,101 @@ impl Backend for SerialPort {
 
     info!("received outgoing call");
 
-    if self.reply_queue.is_none() {
-      return Err(Error::NotStarted.into());
-    }
+    let request = self.request(call)?;
+    runtime(&mut self.runtime)?.block_on(request)
+  }
 
-    let port = Arc::clone(&self.port);
-    let reply_queue = Arc::clone(self.reply_queue.as_ref().unwrap());
+  fn register_stream<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<Self::Intermediate>) -> Result<ReplyStream<Self::Intermediate>> + Send + Sync + 'static,
+  {
+    trace!("register stream receiver");
 
-    self.runtime.block_on(async move {
-      let self_call = SelfCall {
-        procedure: call.procedure,
-        payload: call.payload,
-      };
-      let self_call_string = "c:".to_string() + &Self::serialize(&self_call).unwrap() + "\r\n";
+    *self.stream_receiver.lock().map_err(|_| Error::Lock)? = Some(Arc::new(receiver));
+    Ok(())
+  }
 
-      let port_name;
-      let written;
-      {
-        let mut port_gate = port.lock().await;
+  fn call_stream(&mut self, call: Call<Self::Intermediate>) -> Result<ReplyStream<Self::Intermediate>> {
+    trace!("call stream backend");
 
-        port_name = port_gate.name().unwrap_or_else(|| "".to_string());
+    info!("received outgoing stream call");
 
-        written = port_gate.write(self_call_string.as_bytes());
-      }
+    let request = self.request_stream(call)?;
+    runtime(&mut self.runtime)?.block_on(request)
+  }
 
-      match written {
-        Ok(n) => {
-          debug!("{} sent c: {} bytes", port_name, n);
-          let mut queue_lock = reply_queue.lock().await;
-
-          match queue_lock.recv().await {
-            Some(self_reply_string) => Ok(Reply {
-              payload: Self::deserialize::<Result<SelfReply, String>>(&self_reply_string)?
-                .map_err(|e| Error::FromFrontend(anyhow::anyhow!(e)))?
-                .payload,
-            }),
-            None => Err(Error::NoSenderChannel.into()),
-          }
-        }
-        Err(e) => Err(Error::SendError(e).into()),
-      }
-    })
+  fn register_upload<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<Self::Intermediate>, CallStream<Self::Intermediate>) -> Result<Reply<Self::Intermediate>> + Send + Sync + 'static,
+  {
+    trace!("register upload receiver");
+
+    *self.upload_receiver.lock().map_err(|_| Error::Lock)? = Some(Arc::new(receiver));
+    Ok(())
   }
 
-  fn serialize<T: serde::Serialize>(from: &T) -> Result<String> {
-    trace!("serialize from");
+  fn call_upload(&mut self, call: Call<Self::Intermediate>, chunks: CallStream<Self::Intermediate>) -> Result<Reply<Self::Intermediate>> {
+    trace!("call upload backend");
+
+    info!("received outgoing upload");
 
-    ron::ser::to_string(from).map_err(|e| Error::Serialize(e).into())
+    let request = self.request_upload(call, chunks)?;
+    runtime(&mut self.runtime)?.block_on(request)
+  }
+
+  fn serialize<T: serde::Serialize>(from: &T) -> Result<Self::Intermediate> {
+    C::encode(from)
   }
 
   fn deserialize<'b, T>(from: &'b Self::Intermediate) -> Result<T>
   where
     T: for<'de> serde::Deserialize<'de>,
   {
-    trace!("deserialize from");
+    C::decode(from)
+  }
+}
+
+impl<C: Codec> AsyncBackend for SerialPort<C> {
+  type Intermediate = C::Intermediate;
+
+  fn register<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<C::Intermediate>) -> BoxFuture<'static, Result<Reply<C::Intermediate>>> + Send + Sync + 'static,
+  {
+    trace!("register async receiver");
+
+    self.receiver = Some(Arc::new(receiver));
+
+    let handle = Handle::try_current().map_err(Error::NoRuntime)?;
+    self.start_on(&handle).ok();
+
+    Ok(())
+  }
+
+  fn call(&self, call: Call<C::Intermediate>) -> BoxFuture<'_, Result<Reply<C::Intermediate>>> {
+    trace!("call async backend");
 
-    ron::de::from_str(from).map_err(|e| Error::Deserialize(e).into())
+    info!("received outgoing call");
+
+    match self.request(call) {
+      Ok(request) => Box::pin(request),
+      Err(err) => Box::pin(std::future::ready(Err(err))),
+    }
+  }
+
+  fn serialize<T: serde::Serialize>(from: &T) -> Result<Self::Intermediate> {
+    C::encode(from)
+  }
+
+  fn deserialize<T>(from: &Self::Intermediate) -> Result<T>
+  where
+    T: for<'de> serde::Deserialize<'de>,
+  {
+    C::decode(from)
   }
 }
 
-impl Drop for SerialPort {
+impl<C: Codec> Drop for SerialPort<C> {
   fn drop(&mut self) {
     if self.handle.is_some() {
       self.stop().unwrap()
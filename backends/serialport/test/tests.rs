@@ -119,7 +119,10 @@ impl serialport::SerialPort for MockTty {
   }
 
   fn try_clone(&self) -> serialport::Result<Box<dyn serialport::SerialPort>> {
-    Err(serialport::Error::new(serialport::ErrorKind::Unknown, "cannot clone"))
+    Ok(Box::new(MockTty {
+      m: self.m.try_clone()?,
+      s: self.s.try_clone()?,
+    }))
   }
 
   fn set_break(&self) -> serialport::Result<()> {
@@ -182,3 +185,811 @@ fn register_serialport() {
   let result: i32 = merfolk_caller.frontend(|f| f.call("add", &(a, b)).unwrap()).unwrap();
   assert_eq!(result, a + b);
 }
+
+#[test]
+#[cfg(all(unix, not(target_arch = "armv7")))]
+fn register_serialport_postcard() {
+  use merfolk_backend_serialport::SerialPort;
+  use merfolk_codecs::Postcard;
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver.register("concat", |(a, b): (String, String)| a + &b).unwrap();
+
+  let pairs = (serialport::TTYPort::pair().unwrap(), serialport::TTYPort::pair().unwrap());
+
+  let port_caller = MockTty {
+    m: Box::new(pairs.0 .0),
+    s: Box::new(pairs.1 .1),
+  };
+
+  let port_receiver = MockTty {
+    m: Box::new(pairs.1 .0),
+    s: Box::new(pairs.0 .1),
+  };
+
+  let merfolk_caller = Mer::builder()
+    .backend(SerialPort::<Postcard>::builder_with_codec().port(port_caller).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(SerialPort::<Postcard>::builder_with_codec().port(port_receiver).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let result: String = merfolk_caller.frontend(|f| f.call("concat", &("line\r\n", "break")).unwrap()).unwrap();
+  assert_eq!(result, "line\r\nbreak");
+}
+
+/// Calls over a [`Framing`](merfolk_backend_serialport::Framing) after `noise` was sent to the receiver and returns the number of malformed frames the receiver dropped.
+#[cfg(all(unix, not(target_arch = "armv7")))]
+fn framing_serialport(framing: merfolk_backend_serialport::Framing, noise: &[u8]) -> u64 {
+  use std::io::Write;
+
+  use merfolk_backend_serialport::SerialPort;
+  use merfolk_codecs::Postcard;
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver.register("concat", |(a, b): (String, String)| a + &b).unwrap();
+
+  let mut pairs = (serialport::TTYPort::pair().unwrap(), serialport::TTYPort::pair().unwrap());
+
+  pairs.0 .0.write_all(noise).unwrap();
+
+  let port_caller = MockTty {
+    m: Box::new(pairs.0 .0),
+    s: Box::new(pairs.1 .1),
+  };
+
+  let port_receiver = MockTty {
+    m: Box::new(pairs.1 .0),
+    s: Box::new(pairs.0 .1),
+  };
+
+  let merfolk_caller = Mer::builder()
+    .backend(SerialPort::<Postcard>::builder_with_codec().port(port_caller).framing(framing).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let merfolk_receiver = Mer::builder()
+    .backend(SerialPort::<Postcard>::builder_with_codec().port(port_receiver).framing(framing).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let result: String = merfolk_caller.frontend(|f| f.call("concat", &("line\r\n\0", "break")).unwrap()).unwrap();
+  assert_eq!(result, "line\r\n\0break");
+
+  merfolk_receiver.backend(|b| b.malformed_frames()).unwrap()
+}
+
+#[test]
+#[cfg(all(unix, not(target_arch = "armv7")))]
+fn framing_lines_serialport() {
+  assert_eq!(framing_serialport(merfolk_backend_serialport::Framing::Lines, b"r\r\n"), 1);
+}
+
+#[test]
+#[cfg(all(unix, not(target_arch = "armv7")))]
+fn framing_cobs_serialport() {
+  assert_eq!(framing_serialport(merfolk_backend_serialport::Framing::Cobs, b"noise\0\x03\x11\x22\0"), 2);
+}
+
+#[test]
+#[cfg(all(unix, not(target_arch = "armv7")))]
+fn framing_length_prefix_serialport() {
+  assert_eq!(framing_serialport(merfolk_backend_serialport::Framing::LengthPrefix, b"noise\xa5\x5a\x05\x00\x63"), 2);
+}
+
+#[test]
+#[cfg(all(unix, not(target_arch = "armv7")))]
+fn addressing_serialport() {
+  use std::io::Write;
+
+  use merfolk_backend_serialport::{SerialPort, BROADCAST, TARGET};
+
+  let (notified, notifications) = std::sync::mpsc::channel::<i32>();
+
+  let register_master = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_device = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_device.register("add", |(a, b)| add(a, b)).unwrap();
+  register_device.register("record", move |value: i32| notified.send(value).unwrap()).unwrap();
+
+  let mut pairs = (serialport::TTYPort::pair().unwrap(), serialport::TTYPort::pair().unwrap());
+
+  // a call from node 1 to node 3 on the same line
+  pairs
+    .0
+     .0
+    .write_all(b"c:0301(id:0,procedure:\"record\",payload:\"1\",metadata:{\"notification\":\"true\"})\r\n")
+    .unwrap();
+
+  let port_master = MockTty {
+    m: Box::new(pairs.0 .0),
+    s: Box::new(pairs.1 .1),
+  };
+
+  let port_device = MockTty {
+    m: Box::new(pairs.1 .0),
+    s: Box::new(pairs.0 .1),
+  };
+
+  let merfolk_master = Mer::builder()
+    .backend(SerialPort::builder().port(port_master).address(1).target(2).build().unwrap())
+    .frontend(register_master)
+    .build()
+    .unwrap();
+
+  let merfolk_device = Mer::builder()
+    .backend(SerialPort::builder().port(port_device).address(2).build().unwrap())
+    .frontend(register_device)
+    .build()
+    .unwrap();
+
+  let result: i32 = merfolk_master.frontend(|f| f.call("add", &(1, 2)).unwrap()).unwrap();
+  assert_eq!(result, 3);
+
+  let broadcast = Metadata::from_iter([(TARGET, BROADCAST)]);
+  merfolk_master.frontend(|f| f.notify_with_metadata("record", &42, broadcast.clone()).unwrap()).unwrap();
+  assert_eq!(notifications.recv_timeout(std::time::Duration::from_secs(2)).unwrap(), 42);
+
+  assert!(merfolk_master.frontend(|f| f.call_with_metadata::<_, i32>("add", &(1, 2), broadcast.clone()).is_err()).unwrap());
+
+  assert!(notifications.try_recv().is_err());
+  assert_eq!(merfolk_device.backend(|b| b.malformed_frames()).unwrap(), 0);
+}
+
+#[test]
+#[cfg(all(unix, not(target_arch = "armv7")))]
+fn reconnect_serialport() {
+  use std::{
+    io::{Read, Write},
+    sync::{
+      atomic::{AtomicBool, Ordering},
+      Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+  };
+
+  use merfolk_backend_serialport::{Error, SerialPort, State};
+
+  type Shared = Arc<Mutex<serialport::TTYPort>>;
+
+  fn shared(mut port: serialport::TTYPort) -> Shared {
+    serialport::SerialPort::set_timeout(&mut port, Duration::from_millis(100)).unwrap();
+    Arc::new(Mutex::new(port))
+  }
+
+  // copies the bytes read from `from` to `to` until the adapter is unplugged
+  fn relay(from: Shared, to: Shared, unplugged: Arc<AtomicBool>) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+      let mut buf = [0; 1024];
+      while !unplugged.load(Ordering::Relaxed) {
+        let n = from.lock().unwrap().read(&mut buf).unwrap_or(0);
+        to.lock().unwrap().write_all(&buf[..n]).unwrap();
+      }
+    })
+  }
+
+  // the device stays powered, only the adapter between it and the caller is unplugged
+  let device = (serialport::TTYPort::pair().unwrap(), serialport::TTYPort::pair().unwrap());
+  let (device_tx, device_rx) = (shared(device.0 .1), shared(device.1 .0));
+
+  let register_device = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_device.register("add", |(a, b)| add(a, b)).unwrap();
+  register_device.register("sleep", |millis: u64| thread::sleep(Duration::from_millis(millis))).unwrap();
+
+  let port_device = MockTty {
+    m: Box::new(device.0 .0),
+    s: Box::new(device.1 .1),
+  };
+  let _merfolk_device = Mer::builder()
+    .backend(SerialPort::builder().port(port_device).build().unwrap())
+    .frontend(register_device)
+    .build()
+    .unwrap();
+
+  let plugged: Arc<Mutex<Option<MockTty>>> = Arc::new(Mutex::new(None));
+
+  let plug = || {
+    let pairs = (serialport::TTYPort::pair().unwrap(), serialport::TTYPort::pair().unwrap());
+    let unplugged = Arc::new(AtomicBool::new(false));
+
+    let relays = [
+      relay(shared(pairs.0 .1), Arc::clone(&device_rx), Arc::clone(&unplugged)),
+      relay(Arc::clone(&device_tx), shared(pairs.1 .0), Arc::clone(&unplugged)),
+    ];
+
+    *plugged.lock().unwrap() = Some(MockTty {
+      m: Box::new(pairs.0 .0),
+      s: Box::new(pairs.1 .1),
+    });
+
+    move || {
+      unplugged.store(true, Ordering::Relaxed);
+      relays.into_iter().for_each(|relay| relay.join().unwrap());
+    }
+  };
+
+  let (states, state_changes) = std::sync::mpsc::channel();
+
+  let unplug = plug();
+
+  let merfolk_caller = Mer::builder()
+    .backend(
+      SerialPort::builder()
+        .reopen({
+          let plugged = Arc::clone(&plugged);
+          move || match plugged.lock().unwrap().take() {
+            Some(port) => Ok(Box::new(port) as Box<dyn serialport::SerialPort>),
+            None => Err(serialport::Error::new(serialport::ErrorKind::NoDevice, "unplugged")),
+          }
+        })
+        .backoff(Duration::from_millis(10))
+        .max_backoff(Duration::from_millis(50))
+        .on_state(move |state| states.send(state).unwrap())
+        .build()
+        .unwrap(),
+    )
+    .frontend(merfolk_frontend_register::Register::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  assert_eq!(state_changes.recv_timeout(Duration::from_secs(2)).unwrap(), State::Connected);
+  let result: i32 = merfolk_caller.frontend(|f| f.call("add", &(1, 2)).unwrap()).unwrap();
+  assert_eq!(result, 3);
+
+  // the pending call fails when the adapter is unplugged before the reply was received
+  let unplugging = thread::spawn(move || {
+    thread::sleep(Duration::from_millis(100));
+    unplug();
+  });
+  let err = merfolk_caller.frontend(|f| f.call::<_, ()>("sleep", &300_u64).unwrap_err()).unwrap();
+  assert!(matches!(err.downcast_ref::<Error>(), Some(Error::Disconnected)));
+  unplugging.join().unwrap();
+
+  assert_eq!(state_changes.recv_timeout(Duration::from_secs(2)).unwrap(), State::Disconnected);
+  assert_eq!(merfolk_caller.backend(|b| b.state()).unwrap(), State::Disconnected);
+
+  let err = merfolk_caller.frontend(|f| f.call::<_, i32>("add", &(1, 2)).unwrap_err()).unwrap();
+  assert!(matches!(err.downcast_ref::<Error>(), Some(Error::Disconnected)));
+
+  // the port is reopened when the adapter is plugged in again
+  let _unplug = plug();
+
+  assert_eq!(state_changes.recv_timeout(Duration::from_secs(2)).unwrap(), State::Connected);
+  let result: i32 = merfolk_caller.frontend(|f| f.call("add", &(3, 4)).unwrap()).unwrap();
+  assert_eq!(result, 7);
+}
+
+#[test]
+#[cfg(all(unix, not(target_arch = "armv7")))]
+fn latency_serialport() {
+  use std::time::{Duration, Instant};
+
+  use serialport::SerialPort as _;
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver.register("add", |(a, b)| add(a, b)).unwrap();
+
+  let pairs = (serialport::TTYPort::pair().unwrap(), serialport::TTYPort::pair().unwrap());
+
+  let mut port_caller = MockTty {
+    m: Box::new(pairs.0 .0),
+    s: Box::new(pairs.1 .1),
+  };
+
+  let mut port_receiver = MockTty {
+    m: Box::new(pairs.1 .0),
+    s: Box::new(pairs.0 .1),
+  };
+
+  // neither the calls nor the replies may wait for the reads to time out
+  port_caller.set_timeout(Duration::from_secs(5)).unwrap();
+  port_receiver.set_timeout(Duration::from_secs(5)).unwrap();
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_serialport::SerialPort::builder().port(port_caller).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(merfolk_backend_serialport::SerialPort::builder().port(port_receiver).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let start = Instant::now();
+  for i in 0..10 {
+    let result: i32 = merfolk_caller.frontend(|f| f.call("add", &(i, 1)).unwrap()).unwrap();
+    assert_eq!(result, i + 1);
+  }
+  assert!(start.elapsed() < Duration::from_secs(2), "calls took {:?}", start.elapsed());
+}
+
+#[test]
+#[cfg(all(unix, not(target_arch = "armv7")))]
+fn concurrent_calls_serialport() {
+  use merfolk::interfaces::AsyncBackend;
+
+  let pairs = (serialport::TTYPort::pair().unwrap(), serialport::TTYPort::pair().unwrap());
+
+  let port_caller = MockTty {
+    m: Box::new(pairs.0 .0),
+    s: Box::new(pairs.1 .1),
+  };
+
+  let port_receiver = MockTty {
+    m: Box::new(pairs.1 .0),
+    s: Box::new(pairs.0 .1),
+  };
+
+  let runtime = tokio::runtime::Runtime::new().unwrap();
+
+  runtime.block_on(async {
+    let mut caller = merfolk_backend_serialport::SerialPort::builder().port(port_caller).build().unwrap();
+    let mut receiver = merfolk_backend_serialport::SerialPort::builder().port(port_receiver).build().unwrap();
+
+    AsyncBackend::register(&mut caller, |_call: Call<String>| -> merfolk::helpers::future::BoxFuture<'static, anyhow::Result<Reply<String>>> {
+      Box::pin(async { Err(anyhow::anyhow!("caller does not receive")) })
+    })
+    .unwrap();
+    AsyncBackend::register(&mut receiver, |call: Call<String>| -> merfolk::helpers::future::BoxFuture<'static, anyhow::Result<Reply<String>>> {
+      Box::pin(async move {
+        Ok(Reply {
+          payload: call.payload,
+          metadata: call.metadata,
+        })
+      })
+    })
+    .unwrap();
+
+    let caller = std::sync::Arc::new(caller);
+
+    let calls: Vec<_> = (0..8)
+      .map(|i| {
+        let caller = std::sync::Arc::clone(&caller);
+        tokio::spawn(async move {
+          let reply = AsyncBackend::call(
+            &*caller,
+            Call {
+              procedure: "echo".to_string(),
+              payload: i.to_string(),
+              metadata: Default::default(),
+            },
+          )
+          .await
+          .unwrap();
+          (i, reply.payload)
+        })
+      })
+      .collect();
+
+    for call in calls {
+      let (i, payload) = call.await.unwrap();
+      assert_eq!(payload, i.to_string());
+    }
+  });
+}
+
+#[test]
+#[cfg(all(unix, not(target_arch = "armv7")))]
+fn notification_serialport() {
+  let (notified, notifications) = std::sync::mpsc::channel::<i32>();
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver.register("add", |(a, b)| add(a, b)).unwrap();
+  register_receiver
+    .register("record", move |value: i32| {
+      std::thread::sleep(std::time::Duration::from_millis(200));
+      notified.send(value).unwrap();
+    })
+    .unwrap();
+
+  let pairs = (serialport::TTYPort::pair().unwrap(), serialport::TTYPort::pair().unwrap());
+
+  let port_caller = MockTty {
+    m: Box::new(pairs.0 .0),
+    s: Box::new(pairs.1 .1),
+  };
+
+  let port_receiver = MockTty {
+    m: Box::new(pairs.1 .0),
+    s: Box::new(pairs.0 .1),
+  };
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_serialport::SerialPort::builder().port(port_caller).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(merfolk_backend_serialport::SerialPort::builder().port(port_receiver).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let start = std::time::Instant::now();
+  merfolk_caller.frontend(|f| f.notify("record", &42).unwrap()).unwrap();
+  assert!(start.elapsed() < std::time::Duration::from_millis(200));
+  assert_eq!(notifications.recv_timeout(std::time::Duration::from_secs(2)).unwrap(), 42);
+
+  // no `r:` frame of the notification is mistaken for the reply of the next call
+  let result: i32 = merfolk_caller.frontend(|f| f.call("add", &(1, 2)).unwrap()).unwrap();
+  assert_eq!(result, 3);
+}
+
+#[test]
+#[cfg(all(unix, not(target_arch = "armv7")))]
+fn late_reply_serialport() {
+  use std::io::Write;
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver.register("add", |(a, b)| add(a, b)).unwrap();
+  register_receiver.register("sleep", |millis: u64| std::thread::sleep(std::time::Duration::from_millis(millis))).unwrap();
+
+  let pairs = (serialport::TTYPort::pair().unwrap(), serialport::TTYPort::pair().unwrap());
+  let mut to_caller = pairs.1 .0.try_clone_native().unwrap();
+
+  let port_caller = MockTty {
+    m: Box::new(pairs.0 .0),
+    s: Box::new(pairs.1 .1),
+  };
+
+  let port_receiver = MockTty {
+    m: Box::new(pairs.1 .0),
+    s: Box::new(pairs.0 .1),
+  };
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_serialport::SerialPort::builder().port(port_caller).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(merfolk_backend_serialport::SerialPort::builder().port(port_receiver).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let err = merfolk_caller
+    .frontend(|f| f.call_with_timeout::<_, ()>("sleep", &200_u64, std::time::Duration::from_millis(50)).unwrap_err())
+    .unwrap();
+  assert!(matches!(err.downcast_ref::<merfolk::Error>(), Some(merfolk::Error::Timeout { .. })));
+
+  // a reply for a call which was never sent is dropped
+  to_caller.write_all(b"r:(id:Some(4096),reply:Ok((payload:\"5\")))\r\n").unwrap();
+
+  // neither the unknown reply nor the late reply of the call which timed out is mistaken for the reply of the next call
+  let result: i32 = merfolk_caller.frontend(|f| f.call("add", &(1, 2)).unwrap()).unwrap();
+  assert_eq!(result, 3);
+  assert_eq!(merfolk_caller.backend(|b| b.malformed_frames()).unwrap(), 0);
+}
+
+#[test]
+#[cfg(all(unix, not(target_arch = "armv7")))]
+fn disconnected_upload_serialport() {
+  use std::{
+    io::{Read, Write},
+    sync::{
+      atomic::{AtomicBool, Ordering},
+      mpsc, Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+  };
+
+  use merfolk_backend_serialport::{Error, SerialPort};
+
+  type Shared = Arc<Mutex<serialport::TTYPort>>;
+
+  fn shared(mut port: serialport::TTYPort) -> Shared {
+    serialport::SerialPort::set_timeout(&mut port, Duration::from_millis(100)).unwrap();
+    Arc::new(Mutex::new(port))
+  }
+
+  // copies the bytes read from `from` to `to` until the cable is cut, both ports are closed afterwards
+  fn relay(from: Shared, to: Shared, cut: Arc<AtomicBool>) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+      let mut buf = [0; 1024];
+      while !cut.load(Ordering::Relaxed) {
+        let n = from.lock().unwrap().read(&mut buf).unwrap_or(0);
+        to.lock().unwrap().write_all(&buf[..n]).unwrap();
+      }
+    })
+  }
+
+  let (results, consumed) = mpsc::channel();
+
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver
+    .register_upload("collect", move |_: (), chunks: merfolk::CallStream<String>| {
+      for chunk in chunks {
+        thread::sleep(Duration::from_millis(50));
+        results.send(chunk.is_ok()).unwrap();
+      }
+    })
+    .unwrap();
+
+  let pairs = [
+    serialport::TTYPort::pair().unwrap(),
+    serialport::TTYPort::pair().unwrap(),
+    serialport::TTYPort::pair().unwrap(),
+    serialport::TTYPort::pair().unwrap(),
+  ];
+  let [(to_caller_m, to_caller_s), (from_caller_m, from_caller_s), (to_receiver_m, to_receiver_s), (from_receiver_m, from_receiver_s)] = pairs;
+
+  let cut = Arc::new(AtomicBool::new(false));
+  let relays = [
+    relay(shared(from_caller_s), shared(to_receiver_m), Arc::clone(&cut)),
+    relay(shared(from_receiver_s), shared(to_caller_m), Arc::clone(&cut)),
+  ];
+
+  let merfolk_caller = Mer::builder()
+    .backend(
+      SerialPort::builder()
+        .port(MockTty {
+          m: Box::new(from_caller_m),
+          s: Box::new(to_caller_s),
+        })
+        .build()
+        .unwrap(),
+    )
+    .frontend(merfolk_frontend_register::Register::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(
+      SerialPort::builder()
+        .port(MockTty {
+          m: Box::new(from_receiver_m),
+          s: Box::new(to_receiver_s),
+        })
+        .build()
+        .unwrap(),
+    )
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  thread::scope(|scope| {
+    let uploading = scope.spawn(|| {
+      merfolk_caller
+        .frontend(|f| f.upload::<_, _, (), _>("collect", &(), (0..20).map(|_| "x".repeat(100))).unwrap_err())
+        .unwrap()
+    });
+
+    // the cable is cut while the procedure still consumes the chunks
+    assert!(consumed.recv_timeout(Duration::from_secs(2)).unwrap());
+    cut.store(true, Ordering::Relaxed);
+    relays.into_iter().for_each(|relay| relay.join().unwrap());
+
+    // the procedure sees the upload fail instead of a truncated upload which looks complete
+    assert!(std::iter::from_fn(|| consumed.recv_timeout(Duration::from_secs(2)).ok()).any(|ok| !ok));
+
+    let err = uploading.join().unwrap();
+    assert!(matches!(err.downcast_ref::<Error>(), Some(Error::Disconnected)));
+  });
+}
+
+#[test]
+#[cfg(all(unix, not(target_arch = "armv7")))]
+fn restart_serialport() {
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver.register("add", |(a, b)| add(a, b)).unwrap();
+
+  let pairs = (serialport::TTYPort::pair().unwrap(), serialport::TTYPort::pair().unwrap());
+
+  let port_caller = MockTty {
+    m: Box::new(pairs.0 .0),
+    s: Box::new(pairs.1 .1),
+  };
+
+  let port_receiver = MockTty {
+    m: Box::new(pairs.1 .0),
+    s: Box::new(pairs.0 .1),
+  };
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_serialport::SerialPort::builder().port(port_caller).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let merfolk_receiver = Mer::builder()
+    .backend(merfolk_backend_serialport::SerialPort::builder().port(port_receiver).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  merfolk_receiver.backend(|b| b.stop()).unwrap().unwrap();
+  let err = merfolk_receiver.backend(|b| b.stop()).unwrap().unwrap_err();
+  assert!(matches!(err.downcast_ref::<merfolk_backend_serialport::Error>(), Some(merfolk_backend_serialport::Error::NotStarted)));
+
+  // the reader thread of the stopped listener notices the stop after its read timed out
+  std::thread::sleep(std::time::Duration::from_millis(200));
+
+  merfolk_receiver.backend(|b| b.start()).unwrap().unwrap();
+  let err = merfolk_receiver.backend(|b| b.start()).unwrap().unwrap_err();
+  assert!(matches!(
+    err.downcast_ref::<merfolk_backend_serialport::Error>(),
+    Some(merfolk_backend_serialport::Error::AlreadyStarted)
+  ));
+
+  let result: i32 = merfolk_caller.frontend(|f| f.call("add", &(1, 2)).unwrap()).unwrap();
+  assert_eq!(result, 3);
+}
+
+#[test]
+#[cfg(all(unix, not(target_arch = "armv7")))]
+fn batch_serialport() {
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver.register("add", |(a, b)| add(a, b)).unwrap();
+
+  let pairs = (serialport::TTYPort::pair().unwrap(), serialport::TTYPort::pair().unwrap());
+
+  let port_caller = MockTty {
+    m: Box::new(pairs.0 .0),
+    s: Box::new(pairs.1 .1),
+  };
+
+  let port_receiver = MockTty {
+    m: Box::new(pairs.1 .0),
+    s: Box::new(pairs.0 .1),
+  };
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_serialport::SerialPort::builder().port(port_caller).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(merfolk_backend_serialport::SerialPort::builder().port(port_receiver).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let replies = merfolk_caller
+    .frontend(|f| f.batch().call("add", &(1, 2)).unwrap().call("subtract", &(1, 2)).unwrap().call("add", &(3, 4)).unwrap().send().unwrap())
+    .unwrap();
+
+  assert_eq!(replies.len(), 3);
+  assert_eq!(replies.get::<i32>(0).unwrap(), 3);
+  assert_eq!(replies.get::<i32>(1).unwrap_err().downcast_ref::<RemoteError>().unwrap().code, RemoteError::PROCEDURE_NOT_FOUND);
+  assert_eq!(replies.get::<i32>(2).unwrap(), 7);
+}
+
+#[test]
+#[cfg(all(unix, not(target_arch = "armv7")))]
+fn stream_serialport() {
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver.register("add", |(a, b)| add(a, b)).unwrap();
+  register_receiver
+    .register_stream("count", |to: u32| (0..to).inspect(|_| std::thread::sleep(std::time::Duration::from_millis(100))))
+    .unwrap();
+
+  let pairs = (serialport::TTYPort::pair().unwrap(), serialport::TTYPort::pair().unwrap());
+
+  let port_caller = MockTty {
+    m: Box::new(pairs.0 .0),
+    s: Box::new(pairs.1 .1),
+  };
+
+  let port_receiver = MockTty {
+    m: Box::new(pairs.1 .0),
+    s: Box::new(pairs.0 .1),
+  };
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_serialport::SerialPort::builder().port(port_caller).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(merfolk_backend_serialport::SerialPort::builder().port(port_receiver).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let start = std::time::Instant::now();
+  let mut stream = merfolk_caller.frontend(|f| f.call_stream::<_, u32>("count", &5).unwrap()).unwrap();
+
+  // the first item is received before the last one is produced
+  assert_eq!(stream.next().unwrap().unwrap(), 0);
+  assert!(start.elapsed() < std::time::Duration::from_millis(400));
+  assert_eq!(stream.collect::<Result<Vec<_>, _>>().unwrap(), vec![1, 2, 3, 4]);
+
+  let err = merfolk_caller
+    .frontend(|f| f.call_stream::<_, u32>("missing", &()).and_then(|s| s.collect::<Result<Vec<_>, _>>()).unwrap_err())
+    .unwrap();
+  assert_eq!(err.downcast_ref::<RemoteError>().unwrap().code, RemoteError::PROCEDURE_NOT_FOUND);
+
+  // the end frame of the stream is not mistaken for the reply of the next call
+  let result: i32 = merfolk_caller.frontend(|f| f.call("add", &(1, 2)).unwrap()).unwrap();
+  assert_eq!(result, 3);
+}
+
+#[test]
+fn upload_serialport() {
+  use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+  };
+
+  let consumed = Arc::new(AtomicUsize::new(0));
+  let consumed_receiver = Arc::clone(&consumed);
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver.register("add", |(a, b)| add(a, b)).unwrap();
+  register_receiver
+    .register_upload("length", move |offset: usize, chunks: merfolk::CallStream<String>| {
+      chunks.fold(offset, |acc, chunk| {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        consumed_receiver.fetch_add(1, Ordering::SeqCst);
+        acc + chunk.unwrap().len()
+      })
+    })
+    .unwrap();
+
+  let pairs = (serialport::TTYPort::pair().unwrap(), serialport::TTYPort::pair().unwrap());
+
+  let port_caller = MockTty {
+    m: Box::new(pairs.0 .0),
+    s: Box::new(pairs.1 .1),
+  };
+
+  let port_receiver = MockTty {
+    m: Box::new(pairs.1 .0),
+    s: Box::new(pairs.0 .1),
+  };
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_serialport::SerialPort::builder().port(port_caller).upload_window(4).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(merfolk_backend_serialport::SerialPort::builder().port(port_receiver).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  // the chunks span several reads and are not produced faster than they are consumed
+  let length: usize = merfolk_caller
+    .frontend(|f| {
+      let consumed = Arc::clone(&consumed);
+      let chunks = (0..20_usize).map(move |i| {
+        assert!(i <= consumed.load(Ordering::SeqCst) + 6);
+        "x".repeat(3000)
+      });
+      f.upload("length", &7_usize, chunks).unwrap()
+    })
+    .unwrap();
+  assert_eq!(length, 7 + 20 * 3000);
+
+  let err = merfolk_caller.frontend(|f| f.upload::<_, _, usize, _>("missing", &(), 0..3_u32).unwrap_err()).unwrap();
+  assert_eq!(err.downcast_ref::<RemoteError>().unwrap().code, RemoteError::PROCEDURE_NOT_FOUND);
+
+  let result: i32 = merfolk_caller.frontend(|f| f.call("add", &(1, 2)).unwrap()).unwrap();
+  assert_eq!(result, 3);
+}
@@ -0,0 +1,869 @@
+//! A [`Backend`] for merfolk communicating over persistent TCP connections.
+//!
+//! The client opens a single connection to the server on the first [`Call`] and multiplexes concurrent [`Call`]s on it, the replies are correlated by the id of their [`Call`].
+//! Closed connections are reopened by the next [`Call`] with backoff. The frames are encoded with any [`Codec`], [`Json`] by default.
+//!
+//! ```no_run
+//! # use std::net::SocketAddr;
+//! # use merfolk_backend_tcp::Tcp;
+//! let register = merfolk_frontend_register::Register::builder().build().unwrap();
+//! register.register("add", |(a, b): (i32, i32)| a + b).unwrap();
+//!
+//! let _server = merfolk::Mer::builder()
+//!   .backend(Tcp::builder().listen("127.0.0.1:8080".parse::<SocketAddr>().unwrap()).build().unwrap())
+//!   .frontend(register)
+//!   .build()
+//!   .unwrap();
+//!
+//! let client = merfolk::Mer::builder()
+//!   .backend(Tcp::builder().speak("127.0.0.1:8080".parse::<SocketAddr>().unwrap()).build().unwrap())
+//!   .frontend(merfolk_frontend_register::Register::builder().build().unwrap())
+//!   .build()
+//!   .unwrap();
+//!
+//! let sum: i32 = client.frontend(|f| f.call("add", &(1, 2)).unwrap()).unwrap();
+//! ```
+
+use std::{
+  collections::HashMap,
+  fmt::Debug,
+  future::Future,
+  marker::PhantomData,
+  net::SocketAddr,
+  sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+  },
+  time::Duration,
+};
+
+use anyhow::Result;
+use log::{debug, error, info, trace, warn};
+use merfolk::{
+  helpers::future::BoxFuture,
+  interfaces::{AsyncBackend, Backend, Codec, Encoded},
+  Call, Metadata, RemoteError, Reply, ReplyStream,
+};
+use merfolk_codecs::Json;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::{
+  io::{AsyncReadExt, AsyncWriteExt},
+  net::{
+    tcp::{OwnedReadHalf, OwnedWriteHalf},
+    TcpListener, TcpStream,
+  },
+  runtime::{Handle, Runtime},
+  sync::{oneshot, Mutex},
+  task::{JoinHandle, JoinSet},
+};
+
+#[derive(Debug, Error)]
+pub enum Error {
+  #[error("no speak provided in init()")]
+  NoSpeak,
+  #[error("no listen provided in init()")]
+  NoListen,
+  #[error("no receiver was registered by init()")]
+  NoReceiver,
+  #[error("could not create runtime: {0}")]
+  RuntimeCreation(#[source] std::io::Error),
+  #[error("not running in a runtime: {0}")]
+  NoRuntime(#[from] tokio::runtime::TryCurrentError),
+  #[error("already started")]
+  AlreadyStarted,
+  #[error("not started")]
+  NotStarted,
+  #[error("error binding server to {0}: {1}")]
+  Bind(SocketAddr, #[source] std::io::Error),
+  #[error("could not connect to {0}: {1}")]
+  Connect(SocketAddr, #[source] std::io::Error),
+  #[error("error while sending: {0}")]
+  SendError(#[source] std::io::Error),
+  #[error("error while receiving: {0}")]
+  ReceiveError(#[source] std::io::Error),
+  #[error("connection was closed before the reply was received")]
+  Disconnected,
+  #[error("pending calls or stream receiver lock was poisoned")]
+  Lock,
+  #[error("malformed frame: {0}")]
+  MalformedFrame(String),
+  #[error("frame of {0} bytes exceeds the maximum frame size")]
+  FrameTooLarge(usize),
+}
+
+/// Kind of a frame carrying a call.
+const CALL: u8 = b'c';
+/// Kind of a frame carrying a reply or an item of a reply stream.
+const REPLY: u8 = b'r';
+
+/// Server callback registered by [`Backend::register`] or [`AsyncBackend::register`].
+type Receiver<T> = Arc<dyn Fn(Call<T>) -> BoxFuture<'static, Result<Reply<T>>> + Send + Sync>;
+
+/// Server callback for streaming calls registered by [`Backend::register_stream`].
+type StreamReceiver<T> = Arc<dyn Fn(Call<T>) -> Result<ReplyStream<T>> + Send + Sync>;
+
+/// Calls waiting for their reply frames on a connection. It is `None` once the connection was closed so no call waits for a reply which never arrives.
+type Pending<T> = Arc<std::sync::Mutex<Option<HashMap<u64, Waiting<T>>>>>;
+
+/// [`Backend`] sending length prefixed frames over persistent TCP connections.
+///
+/// Every frame starts with the length of its kind and body as little endian `u32`, followed by the kind (`c` for calls, `r` for replies) and the body encoded with the [`Codec`].
+/// Calls carry an id which correlates their replies, so concurrent calls share a single connection and are answered in any order.
+#[derive(derive_builder::Builder)]
+#[builder(pattern = "owned")]
+pub struct Tcp<C: Codec = Json> {
+  #[builder(private, default = "PhantomData")]
+  __phantom: PhantomData<C>,
+
+  /// Address of the server [`Call`]s are sent to. The connection is opened by the first [`Call`] and reopened by the next [`Call`] after it was closed.
+  #[builder(setter(into, strip_option), default = "None")]
+  speak: Option<SocketAddr>,
+
+  /// Address the server listens on for connections.
+  #[builder(setter(into, strip_option), default = "None")]
+  listen: Option<SocketAddr>,
+
+  /// Number of attempts to open the connection before a [`Call`] fails.
+  #[builder(default = "4")]
+  connect_attempts: u32,
+
+  /// Delay before the next attempt to open the connection, it is doubled after every failed attempt up to `max_backoff`.
+  #[builder(default = "Duration::from_millis(100)")]
+  backoff: Duration,
+
+  /// Maximum delay between the attempts to open the connection.
+  #[builder(default = "Duration::from_secs(5)")]
+  max_backoff: Duration,
+
+  /// Maximum size of a received frame in bytes. Connections sending larger frames are closed.
+  #[builder(default = "16 * 1024 * 1024")]
+  max_frame_size: usize,
+
+  #[builder(private, default = "None")]
+  receiver: Option<Receiver<C::Intermediate>>,
+
+  #[allow(clippy::type_complexity)]
+  #[builder(private, default = "Arc::new(std::sync::Mutex::new(None))")]
+  stream_receiver: Arc<std::sync::Mutex<Option<StreamReceiver<C::Intermediate>>>>,
+
+  #[builder(private, default = "Arc::new(AtomicU64::new(0))")]
+  next_id: Arc<AtomicU64>,
+
+  #[builder(private, default = "Arc::new(Mutex::new(None))")]
+  connection: Arc<Mutex<Option<Connection<C::Intermediate>>>>,
+
+  #[builder(private, default = "None")]
+  runtime: Option<Runtime>,
+
+  #[builder(private, default = "None")]
+  server: Option<JoinHandle<()>>,
+}
+
+impl Tcp {
+  /// Creates a builder for a [`Tcp`] backend using the [`Json`] [`Codec`].
+  pub fn builder() -> TcpBuilder<Json> {
+    TcpBuilder::default()
+  }
+}
+
+impl<C: Codec> Tcp<C> {
+  /// Creates a builder for a [`Tcp`] backend using the [`Codec`] `C`.
+  ///
+  /// ```
+  /// # use merfolk_backend_tcp::Tcp;
+  /// let tcp = Tcp::<merfolk_codecs::Postcard>::builder_with_codec().build().unwrap();
+  /// ```
+  pub fn builder_with_codec() -> TcpBuilder<C> {
+    TcpBuilder::default()
+  }
+}
+
+impl<C: Codec> Debug for Tcp<C> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
+    f.debug_struct("Tcp").field("speak", &self.speak).field("listen", &self.listen).field("runtime", &self.runtime).finish()
+  }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SelfCall<T> {
+  id: u64,
+  procedure: String,
+  payload: T,
+  #[serde(default)]
+  metadata: Metadata,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SelfReply<T> {
+  payload: T,
+  #[serde(default)]
+  metadata: Metadata,
+}
+
+/// Frame of a reply. The `id` correlates the reply to the [`SelfCall`] and is `None` if the call could not be deserialized.
+///
+/// Streaming calls are answered with a frame per item followed by a frame with `end` set.
+#[derive(Serialize, Deserialize)]
+struct SelfReplyFrame<T> {
+  id: Option<u64>,
+  reply: Result<SelfReply<T>, RemoteError>,
+  #[serde(default)]
+  end: bool,
+}
+
+/// Call waiting for its reply frames.
+enum Waiting<T> {
+  Reply(oneshot::Sender<Result<SelfReply<T>, RemoteError>>),
+  Stream(std::sync::mpsc::Sender<Result<T, RemoteError>>),
+}
+
+/// Persistent connection to the server at the `speak` address.
+struct Connection<T> {
+  writer: Arc<Mutex<OwnedWriteHalf>>,
+  pending: Pending<T>,
+  reader: JoinHandle<()>,
+}
+
+impl<T> Connection<T> {
+  fn is_closed(&self) -> bool {
+    self.pending.lock().map_or(true, |pending| pending.is_none())
+  }
+}
+
+impl<T> Drop for Connection<T> {
+  fn drop(&mut self) {
+    self.reader.abort();
+  }
+}
+
+/// Settings for opening the connection to the server at the `speak` address.
+#[derive(Clone, Copy)]
+struct Dial {
+  speak: SocketAddr,
+  attempts: u32,
+  backoff: Duration,
+  max_backoff: Duration,
+  max_frame_size: usize,
+}
+
+/// Adds the `waiting` call to the `pending` calls of a connection. Fails if the connection was closed meanwhile.
+fn wait<T>(pending: &Pending<T>, id: u64, waiting: Waiting<T>) -> Result<()> {
+  match pending.lock().map_err(|_| Error::Lock)?.as_mut() {
+    Some(pending) => {
+      pending.insert(id, waiting);
+      Ok(())
+    }
+    None => Err(Error::Disconnected.into()),
+  }
+}
+
+/// Removes the call with the id from the pending calls when it stops waiting for its reply, e.g. because it timed out or its future was dropped.
+struct Forget<'a, T>(&'a Pending<T>, u64);
+
+impl<T> Drop for Forget<'_, T> {
+  fn drop(&mut self) {
+    if let Some(pending) = self.0.lock().ok().as_mut().and_then(|pending| pending.as_mut()) {
+      pending.remove(&self.1);
+    }
+  }
+}
+
+/// Encodes a frame of the `kind` with the [`Codec`] `C`, prefixed by the length of the kind and the body as little endian `u32`.
+fn frame<C: Codec, T: Serialize>(kind: u8, from: &T) -> Result<Vec<u8>> {
+  trace!("serialize from");
+
+  let body = C::encode(from)?.into_bytes();
+  let len = u32::try_from(body.len() + 1).map_err(|_| Error::FrameTooLarge(body.len() + 1))?;
+
+  Ok([&len.to_le_bytes()[..], &[kind], &body].concat())
+}
+
+/// Encodes a [`SelfReplyFrame`] to a `r` frame.
+fn reply_frame<C: Codec>(id: Option<u64>, reply: Result<SelfReply<C::Intermediate>, RemoteError>, end: bool) -> Result<Vec<u8>> {
+  match frame::<C, _>(REPLY, &SelfReplyFrame { id, reply, end }) {
+    Ok(frame) => Ok(frame),
+    Err(e) => frame::<C, _>(
+      REPLY,
+      &SelfReplyFrame::<C::Intermediate> {
+        id,
+        reply: Err(RemoteError::new(RemoteError::INTERNAL_ERROR, e)),
+        end,
+      },
+    ),
+  }
+}
+
+/// Decodes the body of a frame with the [`Codec`] `C`.
+fn decode<C: Codec, T>(body: Vec<u8>) -> Result<T>
+where
+  T: for<'de> serde::Deserialize<'de>,
+{
+  trace!("deserialize from");
+
+  C::decode(&C::Intermediate::from_bytes(body)?)
+}
+
+/// Writes the `frame` to the connection. Frames of concurrent calls are never interleaved as the `writer` is locked for the whole frame.
+async fn write_frame(writer: &Mutex<OwnedWriteHalf>, frame: &[u8]) -> Result<()> {
+  writer.lock().await.write_all(frame).await.map_err(|e| Error::SendError(e).into())
+}
+
+/// Writes a `r` frame. The reply is dropped if it could not be encoded or if the connection was closed, as the caller already failed.
+///
+/// Returns `false` if the connection was closed, so no further replies are written.
+async fn write_reply(writer: &Mutex<OwnedWriteHalf>, frame: Result<Vec<u8>>) -> bool {
+  let frame = match frame {
+    Ok(frame) => frame,
+    Err(e) => {
+      error!("dropping reply as it could not be encoded: {:?}", e);
+      return true;
+    }
+  };
+
+  match write_frame(writer, &frame).await {
+    Ok(()) => true,
+    Err(e) => {
+      debug!("dropping reply: {}", e);
+      false
+    }
+  }
+}
+
+/// Reads the next frame from the connection and returns its kind and body. Returns `None` if the connection was closed.
+async fn read_frame(reader: &mut OwnedReadHalf, max_frame_size: usize) -> Result<Option<(u8, Vec<u8>)>> {
+  let mut len = [0; 4];
+  match reader.read_exact(&mut len).await {
+    Ok(_) => (),
+    Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+    Err(e) => return Err(Error::ReceiveError(e).into()),
+  }
+
+  let len = u32::from_le_bytes(len) as usize;
+  if len == 0 {
+    return Err(Error::MalformedFrame("empty frame".into()).into());
+  }
+  if len > max_frame_size {
+    return Err(Error::FrameTooLarge(len).into());
+  }
+
+  let mut frame = vec![0; len];
+  reader.read_exact(&mut frame).await.map_err(Error::ReceiveError)?;
+
+  let body = frame.split_off(1);
+  Ok(Some((frame[0], body)))
+}
+
+/// Awaits the `future` and fails with [`merfolk::Error::Timeout`] if the `timeout` elapses first.
+async fn timeout<T>(procedure: &str, timeout: Option<Duration>, future: impl Future<Output = Result<T>>) -> Result<T> {
+  match timeout {
+    None => future.await,
+    Some(timeout) => tokio::time::timeout(timeout, future).await.map_err(|_| merfolk::Error::Timeout {
+      procedure: procedure.to_string(),
+      timeout,
+    })?,
+  }
+}
+
+/// Returns the private [`Runtime`] used by the synchronous [`Backend`] implementation. The [`Runtime`] is created on first use.
+fn runtime(runtime: &mut Option<Runtime>) -> Result<&Runtime> {
+  if runtime.is_none() {
+    *runtime = Some(Runtime::new().map_err(Error::RuntimeCreation)?);
+  }
+
+  Ok(runtime.as_ref().unwrap())
+}
+
+/// Answers the calls read from a connection accepted by the server until it is closed.
+async fn serve<C: Codec>(stream: TcpStream, receiver: Receiver<C::Intermediate>, stream_receiver: Arc<std::sync::Mutex<Option<StreamReceiver<C::Intermediate>>>>, max_frame_size: usize) {
+  let (mut reader, writer) = stream.into_split();
+  let writer = Arc::new(Mutex::new(writer));
+
+  // the calls are answered concurrently, their replies are correlated by the id of the call
+  let mut answers = JoinSet::new();
+
+  loop {
+    let body = match read_frame(&mut reader, max_frame_size).await {
+      Ok(Some((CALL, body))) => body,
+      Ok(Some((kind, _))) => {
+        warn!("dropping frame of unknown kind {:?}", kind as char);
+        continue;
+      }
+      Ok(None) => break,
+      Err(e) => {
+        warn!("closing connection: {}", e);
+        break;
+      }
+    };
+
+    debug!("read call");
+
+    let stream_receiver = stream_receiver.lock().ok().and_then(|r| r.clone());
+    answers.spawn(answer::<C>(body, Arc::clone(&writer), Arc::clone(&receiver), stream_receiver));
+
+    while answers.try_join_next().is_some() {}
+  }
+
+  // the calls received before the connection was closed are still answered
+  while answers.join_next().await.is_some() {}
+}
+
+/// Runs the call of a `c` frame and writes its `r` frames.
+async fn answer<C: Codec>(body: Vec<u8>, writer: Arc<Mutex<OwnedWriteHalf>>, receiver: Receiver<C::Intermediate>, stream_receiver: Option<StreamReceiver<C::Intermediate>>) {
+  let self_call = match decode::<C, SelfCall<C::Intermediate>>(body) {
+    Ok(self_call) => self_call,
+    Err(e) => {
+      write_reply(&writer, reply_frame::<C>(None, Err(RemoteError::new(RemoteError::PARSE_ERROR, e)), false)).await;
+      return;
+    }
+  };
+
+  let id = Some(self_call.id);
+  let procedure = self_call.procedure.clone();
+  let call_timeout = self_call.metadata.timeout();
+  let call = Call {
+    procedure: self_call.procedure,
+    payload: self_call.payload,
+    metadata: self_call.metadata,
+  };
+
+  if call.metadata.is_notification() {
+    // notifications are not answered with a `r` frame
+    if let Err(e) = timeout(&procedure, call_timeout, receiver(call)).await {
+      warn!("notification {} failed: {}", procedure, e);
+    }
+    return;
+  }
+
+  if call.metadata.is_upload() {
+    let unsupported = RemoteError::from(anyhow::Error::from(merfolk::Error::Unsupported("uploads".into())));
+    write_reply(&writer, reply_frame::<C>(id, Err(unsupported), false)).await;
+    return;
+  }
+
+  if call.metadata.is_stream() {
+    let stream = match stream_receiver {
+      Some(stream_receiver) => timeout(&procedure, call_timeout, async { tokio::task::spawn_blocking(move || stream_receiver(call)).await? }).await,
+      None => timeout(&procedure, call_timeout, receiver(call)).await.map(|reply| ReplyStream::once(Ok(reply.payload))),
+    };
+
+    let items: Box<dyn Iterator<Item = Result<C::Intermediate>> + Send> = match stream {
+      Ok(stream) => Box::new(stream),
+      Err(e) => Box::new(std::iter::once(Err(e))),
+    };
+
+    // the items are produced by a blocking iterator, each one is written as soon as it was produced
+    let handle = Handle::current();
+    tokio::task::spawn_blocking(move || {
+      for item in items {
+        let self_reply = item.map(|payload| SelfReply { payload, metadata: Metadata::new() }).map_err(RemoteError::from);
+        // the remaining items are not produced once the caller is gone
+        if !handle.block_on(write_reply(&writer, reply_frame::<C>(id, self_reply, false))) {
+          return;
+        }
+      }
+
+      let end = C::encode(&())
+        .map(|payload| SelfReply { payload, metadata: Metadata::new() })
+        .map_err(|e| RemoteError::new(RemoteError::INTERNAL_ERROR, e));
+      handle.block_on(write_reply(&writer, reply_frame::<C>(id, end, true)));
+    })
+    .await
+    .ok();
+    return;
+  }
+
+  let self_reply = timeout(&procedure, call_timeout, receiver(call))
+    .await
+    .map(|r| SelfReply {
+      payload: r.payload,
+      metadata: r.metadata,
+    })
+    .map_err(RemoteError::from);
+
+  write_reply(&writer, reply_frame::<C>(id, self_reply, false)).await;
+}
+
+/// Reads the `r` frames of a connection opened by the client and passes them to the `pending` calls until the connection is closed.
+async fn read_replies<C: Codec>(mut reader: OwnedReadHalf, pending: Pending<C::Intermediate>, max_frame_size: usize) {
+  loop {
+    let body = match read_frame(&mut reader, max_frame_size).await {
+      Ok(Some((REPLY, body))) => body,
+      Ok(Some((kind, _))) => {
+        warn!("dropping frame of unknown kind {:?}", kind as char);
+        continue;
+      }
+      Ok(None) => {
+        info!("connection was closed by the server");
+        break;
+      }
+      Err(e) => {
+        warn!("closing connection: {}", e);
+        break;
+      }
+    };
+
+    debug!("read reply");
+
+    let frame = match decode::<C, SelfReplyFrame<C::Intermediate>>(body) {
+      Ok(frame) => frame,
+      Err(e) => {
+        warn!("dropping malformed reply: {}", e);
+        continue;
+      }
+    };
+
+    // streams stay pending until their end frame
+    let waiting = frame.id.and_then(|id| {
+      let mut pending = pending.lock().ok()?;
+      let pending = pending.as_mut()?;
+      match (pending.get(&id)?, frame.end) {
+        (Waiting::Stream(tx), false) => Some(Waiting::Stream(tx.clone())),
+        _ => pending.remove(&id),
+      }
+    });
+    match waiting {
+      Some(Waiting::Reply(tx)) => {
+        tx.send(frame.reply).ok();
+      }
+      Some(Waiting::Stream(tx)) => {
+        if !frame.end {
+          tx.send(frame.reply.map(|r| r.payload)).ok();
+        }
+      }
+      None => warn!("dropping reply for unknown call {:?}", frame.id),
+    }
+  }
+
+  // pending calls fail as their replies are lost, the next call opens a new connection
+  let closed = pending.lock().ok().and_then(|mut pending| pending.take());
+  for (_, waiting) in closed.into_iter().flatten() {
+    if let Waiting::Stream(tx) = waiting {
+      tx.send(Err(RemoteError::from(anyhow::Error::from(Error::Disconnected)))).ok();
+    }
+  }
+}
+
+/// Returns the writer and the pending calls of the open connection, or opens a new connection if there is none or it was closed.
+async fn connect<C: Codec>(connection: &Mutex<Option<Connection<C::Intermediate>>>, dial: Dial) -> Result<(Arc<Mutex<OwnedWriteHalf>>, Pending<C::Intermediate>)> {
+  // concurrent calls wait for the connection opened by the first one
+  let mut connection = connection.lock().await;
+  if let Some(open) = connection.as_ref().filter(|open| !open.is_closed()) {
+    return Ok((Arc::clone(&open.writer), Arc::clone(&open.pending)));
+  }
+
+  let mut backoff = dial.backoff;
+  let mut attempt = 1;
+  let stream = loop {
+    match TcpStream::connect(dial.speak).await {
+      Ok(stream) => break stream,
+      Err(e) if attempt < dial.attempts => {
+        debug!("connecting to {} failed: {}", dial.speak, e);
+
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(dial.max_backoff);
+        attempt += 1;
+      }
+      Err(e) => return Err(Error::Connect(dial.speak, e).into()),
+    }
+  };
+  info!("connected to {}", dial.speak);
+
+  // frames are sent right away instead of waiting to be coalesced
+  stream.set_nodelay(true).map_err(|e| Error::Connect(dial.speak, e))?;
+
+  let (reader, writer) = stream.into_split();
+  let pending: Pending<C::Intermediate> = Arc::new(std::sync::Mutex::new(Some(HashMap::new())));
+
+  let open = connection.insert(Connection {
+    writer: Arc::new(Mutex::new(writer)),
+    pending: Arc::clone(&pending),
+    reader: tokio::spawn(read_replies::<C>(reader, pending, dial.max_frame_size)),
+  });
+
+  Ok((Arc::clone(&open.writer), Arc::clone(&open.pending)))
+}
+
+impl<C: Codec> Tcp<C> {
+  /// Starts the server on the private [`Runtime`].
+  pub fn start(&mut self) -> Result<()> {
+    let handle = runtime(&mut self.runtime)?.handle().clone();
+
+    self.start_on(&handle)
+  }
+
+  /// Starts the server on the [`Runtime`] of the `handle`.
+  pub fn start_on(&mut self, handle: &Handle) -> Result<()> {
+    trace!("start Tcp Backend");
+
+    if self.server.is_some() {
+      return Err(Error::AlreadyStarted.into());
+    }
+
+    let listen = self.listen.ok_or(Error::NoListen)?;
+
+    let receiver = Arc::clone(self.receiver.as_ref().ok_or(Error::NoReceiver)?);
+    let stream_receiver = Arc::clone(&self.stream_receiver);
+    let max_frame_size = self.max_frame_size;
+
+    // the address is bound right away so binding errors are returned
+    let listener = std::net::TcpListener::bind(listen)
+      .and_then(|listener| listener.set_nonblocking(true).map(|_| listener))
+      .and_then(|listener| {
+        let _runtime = handle.enter();
+        TcpListener::from_std(listener)
+      })
+      .map_err(|e| Error::Bind(listen, e))?;
+
+    self.server = Some(handle.spawn(async move {
+      trace!("spawn listener");
+
+      // the connections are aborted with the listener when it is stopped
+      let mut connections = JoinSet::new();
+
+      loop {
+        let (stream, peer) = match listener.accept().await {
+          Ok(accepted) => accepted,
+          Err(e) => {
+            warn!("accepting connection failed: {}", e);
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            continue;
+          }
+        };
+        info!("accepted connection from {}", peer);
+
+        stream.set_nodelay(true).ok();
+        connections.spawn(serve::<C>(stream, Arc::clone(&receiver), Arc::clone(&stream_receiver), max_frame_size));
+
+        while connections.try_join_next().is_some() {}
+      }
+    }));
+    Ok(())
+  }
+
+  pub fn stop(&mut self) -> Result<()> {
+    trace!("stop tcp backend");
+    self.server.take().ok_or(Error::NotStarted)?.abort();
+    Ok(())
+  }
+
+  fn ignore_start_error(started: Result<()>) -> Result<()> {
+    if let Some(err) = started.err().map(|e| e.downcast::<Error>()) {
+      match err {
+        Ok(err) => match err {
+          Error::AlreadyStarted | Error::NoListen | Error::NoReceiver => {}
+          err => return Err(err.into()),
+        },
+        Err(err) => return Err(err),
+      }
+    };
+
+    Ok(())
+  }
+
+  fn dial(&self) -> Result<Dial> {
+    Ok(Dial {
+      speak: self.speak.ok_or(Error::NoSpeak)?,
+      attempts: self.connect_attempts,
+      backoff: self.backoff,
+      max_backoff: self.max_backoff,
+      max_frame_size: self.max_frame_size,
+    })
+  }
+
+  fn request(&self, call: Call<C::Intermediate>) -> Result<impl Future<Output = Result<Reply<C::Intermediate>>>> {
+    let dial = self.dial()?;
+    let connection = Arc::clone(&self.connection);
+
+    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+    Ok(async move {
+      let procedure = call.procedure.clone();
+      let call_timeout = call.metadata.timeout();
+      let notification = call.metadata.is_notification();
+
+      let self_call = SelfCall {
+        id,
+        procedure: call.procedure,
+        payload: call.payload,
+        metadata: call.metadata,
+      };
+      let self_call_frame = frame::<C, _>(CALL, &self_call)?;
+
+      // the timeout includes opening the connection
+      timeout(&procedure, call_timeout, async {
+        let (writer, pending) = connect::<C>(&connection, dial).await?;
+
+        if notification {
+          write_frame(&writer, &self_call_frame).await?;
+          debug!("sent notification c: {}", id);
+
+          return Ok(Reply {
+            payload: C::encode(&())?,
+            metadata: Metadata::new(),
+          });
+        }
+
+        let (tx, rx) = oneshot::channel();
+        wait(&pending, id, Waiting::Reply(tx))?;
+        let _forget = Forget(&pending, id);
+
+        write_frame(&writer, &self_call_frame).await?;
+        debug!("sent c: {}", id);
+
+        let self_reply = rx.await.map_err(|_| Error::Disconnected)?.map_err(|e| e.into_error(&procedure, call_timeout))?;
+
+        Ok(Reply {
+          payload: self_reply.payload,
+          metadata: self_reply.metadata,
+        })
+      })
+      .await
+    })
+  }
+
+  fn request_stream(&self, call: Call<C::Intermediate>) -> Result<impl Future<Output = Result<ReplyStream<C::Intermediate>>>> {
+    let dial = self.dial()?;
+    let connection = Arc::clone(&self.connection);
+
+    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+    Ok(async move {
+      let procedure = call.procedure.clone();
+      let call_timeout = call.metadata.timeout();
+
+      let self_call = SelfCall {
+        id,
+        procedure: call.procedure,
+        payload: call.payload,
+        metadata: call.metadata,
+      };
+      let self_call_frame = frame::<C, _>(CALL, &self_call)?;
+
+      let (writer, pending) = timeout(&procedure, call_timeout, connect::<C>(&connection, dial)).await?;
+
+      let (tx, rx) = std::sync::mpsc::channel();
+      wait(&pending, id, Waiting::Stream(tx))?;
+
+      if let Err(e) = write_frame(&writer, &self_call_frame).await {
+        drop(Forget(&pending, id));
+        return Err(e);
+      }
+      debug!("sent stream c: {}", id);
+
+      Ok(ReplyStream::receive(rx, procedure.clone(), call_timeout, move |item| {
+        item.map_err(|e| e.into_error(&procedure, call_timeout))
+      }))
+    })
+  }
+}
+
+impl<C: Codec> Backend for Tcp<C> {
+  type Intermediate = C::Intermediate;
+
+  fn register<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<Self::Intermediate>) -> Result<Reply<Self::Intermediate>> + Send + Sync + 'static,
+  {
+    trace!("register receiver");
+
+    let receiver = Arc::new(receiver);
+    self.receiver = Some(Arc::new(move |call: Call<C::Intermediate>| {
+      trace!("run receiver");
+      let receiver = Arc::clone(&receiver);
+
+      // the blocking receiver runs on its own thread so the timeout of the call can elapse, a late reply is dropped
+      Box::pin(async move {
+        debug!("calling receiver");
+        tokio::task::spawn_blocking(move || receiver(call)).await?
+      })
+    }));
+
+    Self::ignore_start_error(self.start())
+  }
+
+  fn call(&mut self, call: Call<Self::Intermediate>) -> Result<Reply<Self::Intermediate>> {
+    trace!("call backend");
+
+    info!("received outgoing call");
+
+    let request = self.request(call)?;
+    runtime(&mut self.runtime)?.block_on(request)
+  }
+
+  fn register_stream<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<Self::Intermediate>) -> Result<ReplyStream<Self::Intermediate>> + Send + Sync + 'static,
+  {
+    trace!("register stream receiver");
+
+    *self.stream_receiver.lock().map_err(|_| Error::Lock)? = Some(Arc::new(receiver));
+    Ok(())
+  }
+
+  fn call_stream(&mut self, call: Call<Self::Intermediate>) -> Result<ReplyStream<Self::Intermediate>> {
+    trace!("call stream backend");
+
+    info!("received outgoing stream call");
+
+    let request = self.request_stream(call)?;
+    runtime(&mut self.runtime)?.block_on(request)
+  }
+
+  fn serialize<T: serde::Serialize>(from: &T) -> Result<Self::Intermediate> {
+    C::encode(from)
+  }
+
+  fn deserialize<'b, T>(from: &'b Self::Intermediate) -> Result<T>
+  where
+    T: for<'de> serde::Deserialize<'de>,
+  {
+    C::decode(from)
+  }
+}
+
+impl<C: Codec> AsyncBackend for Tcp<C> {
+  type Intermediate = C::Intermediate;
+
+  fn register<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<C::Intermediate>) -> BoxFuture<'static, Result<Reply<C::Intermediate>>> + Send + Sync + 'static,
+  {
+    trace!("register async receiver");
+
+    self.receiver = Some(Arc::new(receiver));
+
+    let handle = Handle::try_current().map_err(Error::NoRuntime)?;
+    Self::ignore_start_error(self.start_on(&handle))
+  }
+
+  fn call(&self, call: Call<C::Intermediate>) -> BoxFuture<'_, Result<Reply<C::Intermediate>>> {
+    trace!("call async backend");
+
+    info!("received outgoing call");
+
+    match self.request(call) {
+      Ok(request) => Box::pin(request),
+      Err(err) => Box::pin(std::future::ready(Err(err))),
+    }
+  }
+
+  fn serialize<T: serde::Serialize>(from: &T) -> Result<Self::Intermediate> {
+    C::encode(from)
+  }
+
+  fn deserialize<T>(from: &Self::Intermediate) -> Result<T>
+  where
+    T: for<'de> serde::Deserialize<'de>,
+  {
+    C::decode(from)
+  }
+}
+
+impl<C: Codec> Drop for Tcp<C> {
+  fn drop(&mut self) {
+    if self.server.is_some() {
+      self.stop().unwrap()
+    }
+  }
+}
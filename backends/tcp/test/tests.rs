@@ -0,0 +1,266 @@
+use std::{
+  io::{Read, Write},
+  net::{IpAddr, Ipv4Addr, SocketAddr},
+  time::{Duration, Instant},
+};
+
+use merfolk::*;
+use merfolk_backend_tcp::Tcp;
+
+fn add(a: i32, b: i32) -> i32 {
+  a + b
+}
+
+fn localhost(port: u16) -> SocketAddr {
+  SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), port)
+}
+
+#[test]
+fn register_tcp() {
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver.register("add", |(a, b)| add(a, b)).unwrap();
+
+  let merfolk_caller = Mer::builder()
+    .backend(Tcp::<merfolk_codecs::Postcard>::builder_with_codec().speak(localhost(8096)).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(Tcp::<merfolk_codecs::Postcard>::builder_with_codec().listen(localhost(8096)).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let (a, b) = (rand::random::<i32>() / 2, rand::random::<i32>() / 2);
+  let result: i32 = merfolk_caller.frontend(|f| f.call("add", &(a, b)).unwrap()).unwrap();
+  assert_eq!(result, a + b);
+
+  let err = merfolk_caller.frontend(|f| f.call::<_, i32>("subtract", &(1, 2)).unwrap_err()).unwrap();
+  assert_eq!(err.downcast_ref::<RemoteError>().unwrap().code, RemoteError::PROCEDURE_NOT_FOUND);
+
+  merfolk_caller.frontend(|f| f.notify("add", &(1, 2)).unwrap()).unwrap();
+
+  // the following call reuses the connection
+  let result: i32 = merfolk_caller.frontend(|f| f.call("add", &(3, 4)).unwrap()).unwrap();
+  assert_eq!(result, 7);
+}
+
+#[test]
+fn multiplexed_tcp() {
+  use merfolk::interfaces::AsyncBackend;
+
+  tokio::runtime::Runtime::new().unwrap().block_on(async {
+    let mut receiver = Tcp::builder().listen(localhost(8097)).build().unwrap();
+    AsyncBackend::register(&mut receiver, |call: Call<String>| -> helpers::future::BoxFuture<'static, anyhow::Result<Reply<String>>> {
+      Box::pin(async move {
+        tokio::time::sleep(Duration::from_millis(call.payload.parse()?)).await;
+        Ok(Reply {
+          payload: call.payload,
+          metadata: Metadata::new(),
+        })
+      })
+    })
+    .unwrap();
+
+    let caller = Tcp::builder().speak(localhost(8097)).build().unwrap();
+    let sleep = |millis: &str| Call {
+      procedure: "sleep".to_string(),
+      payload: millis.to_string(),
+      metadata: Metadata::new(),
+    };
+
+    let start = Instant::now();
+    let (slow, fast) = tokio::join!(AsyncBackend::call(&caller, sleep("500")), async {
+      let reply = AsyncBackend::call(&caller, sleep("50")).await;
+      (reply, start.elapsed())
+    });
+
+    // the fast call is answered while the slow call is still running on the same connection
+    assert_eq!(slow.unwrap().payload, "500");
+    assert_eq!(fast.0.unwrap().payload, "50");
+    assert!(fast.1 < Duration::from_millis(400));
+    assert!(start.elapsed() < Duration::from_millis(900));
+  });
+}
+
+#[test]
+fn stream_tcp() {
+  use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+  };
+
+  let produced = Arc::new(AtomicUsize::new(0));
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver
+    .register_stream("count", |to: u32| (0..to).inspect(|_| std::thread::sleep(Duration::from_millis(100))))
+    .unwrap();
+  register_receiver
+    .register_stream("forever", {
+      let produced = Arc::clone(&produced);
+      move |()| {
+        let produced = Arc::clone(&produced);
+        std::iter::repeat_with(move || {
+          std::thread::sleep(Duration::from_millis(10));
+          produced.fetch_add(1, Ordering::SeqCst)
+        })
+      }
+    })
+    .unwrap();
+
+  let merfolk_caller = Mer::builder()
+    .backend(Tcp::builder().speak(localhost(8098)).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(Tcp::builder().listen(localhost(8098)).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let start = Instant::now();
+  let mut stream = merfolk_caller.frontend(|f| f.call_stream::<_, u32>("count", &5).unwrap()).unwrap();
+
+  // the first item is received before the last one is produced
+  assert_eq!(stream.next().unwrap().unwrap(), 0);
+  assert!(start.elapsed() < Duration::from_millis(400));
+  assert_eq!(stream.collect::<Result<Vec<_>, _>>().unwrap(), vec![1, 2, 3, 4]);
+
+  let err = merfolk_caller
+    .frontend(|f| f.call_stream::<_, u32>("missing", &()).and_then(|s| s.collect::<Result<Vec<_>, _>>()).unwrap_err())
+    .unwrap();
+  assert_eq!(err.downcast_ref::<RemoteError>().unwrap().code, RemoteError::PROCEDURE_NOT_FOUND);
+
+  // an endless stream is no longer produced once the caller closed the connection
+  let mut stream = std::net::TcpStream::connect(localhost(8098)).unwrap();
+  let call = br#"c{"id":0,"procedure":"forever","payload":"null","metadata":{"stream":"true"}}"#;
+  stream.write_all(&[&(call.len() as u32).to_le_bytes()[..], call].concat()).unwrap();
+
+  let mut len = [0; 4];
+  stream.read_exact(&mut len).unwrap();
+  drop(stream);
+
+  std::thread::sleep(Duration::from_millis(300));
+  let stopped = produced.load(Ordering::SeqCst);
+  std::thread::sleep(Duration::from_millis(300));
+  assert_eq!(produced.load(Ordering::SeqCst), stopped);
+}
+
+#[test]
+fn timeout_tcp() {
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver.register("sleep", |millis: u64| std::thread::sleep(Duration::from_millis(millis))).unwrap();
+
+  let timeout = Duration::from_millis(50);
+
+  let merfolk_caller = Mer::builder()
+    .backend(Tcp::builder().speak(localhost(8099)).build().unwrap())
+    .frontend(register_caller)
+    .timeout(timeout)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(Tcp::builder().listen(localhost(8099)).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let result: () = merfolk_caller.frontend(|f| f.call_with_timeout("sleep", &10_u64, timeout * 10).unwrap()).unwrap();
+  assert_eq!(result, ());
+
+  let err = merfolk_caller.frontend(|f| f.call::<_, ()>("sleep", &500_u64).unwrap_err()).unwrap();
+  assert!(matches!(err.downcast_ref::<merfolk::Error>(), Some(merfolk::Error::Timeout { .. })));
+
+  // the server answers with a timeout without waiting for the procedure which is still sleeping
+  let mut stream = std::net::TcpStream::connect(localhost(8099)).unwrap();
+  let call = br#"c{"id":0,"procedure":"sleep","payload":"500","metadata":{"timeout":"50"}}"#;
+
+  let start = Instant::now();
+  stream.write_all(&[&(call.len() as u32).to_le_bytes()[..], call].concat()).unwrap();
+
+  let mut len = [0; 4];
+  stream.read_exact(&mut len).unwrap();
+  let mut frame = vec![0; u32::from_le_bytes(len) as usize];
+  stream.read_exact(&mut frame).unwrap();
+
+  assert!(start.elapsed() < Duration::from_millis(400));
+  assert_eq!(frame[0], b'r');
+  assert!(String::from_utf8_lossy(&frame).contains(&format!(r#""code":{}"#, RemoteError::TIMEOUT)));
+}
+
+#[test]
+fn disconnected_tcp() {
+  use merfolk::interfaces::Backend;
+
+  let listener = std::net::TcpListener::bind(localhost(8100)).unwrap();
+
+  // the server closes the connection after reading the call without answering it
+  let server = std::thread::spawn(move || {
+    let (mut stream, _) = listener.accept().unwrap();
+
+    let mut len = [0; 4];
+    stream.read_exact(&mut len).unwrap();
+    let mut frame = vec![0; u32::from_le_bytes(len) as usize];
+    stream.read_exact(&mut frame).unwrap();
+
+    frame[0]
+  });
+
+  let mut caller = Tcp::builder().speak(localhost(8100)).build().unwrap();
+  let err = caller
+    .call(Call {
+      procedure: "add".to_string(),
+      payload: "[1,2]".to_string(),
+      metadata: Metadata::new(),
+    })
+    .unwrap_err();
+
+  assert!(matches!(err.downcast_ref::<merfolk_backend_tcp::Error>(), Some(merfolk_backend_tcp::Error::Disconnected)));
+  assert_eq!(server.join().unwrap(), b'c');
+}
+
+#[test]
+fn reconnect_tcp() {
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+
+  let merfolk_caller = Mer::builder()
+    .backend(Tcp::builder().speak(localhost(8101)).backoff(Duration::from_millis(10)).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let receiver = || {
+    let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+    register_receiver.register("add", |(a, b)| add(a, b)).unwrap();
+
+    Mer::builder()
+      .backend(Tcp::builder().listen(localhost(8101)).build().unwrap())
+      .frontend(register_receiver)
+      .build()
+      .unwrap()
+  };
+
+  let merfolk_receiver = receiver();
+  let result: i32 = merfolk_caller.frontend(|f| f.call("add", &(1, 2)).unwrap()).unwrap();
+  assert_eq!(result, 3);
+
+  // stopping the server closes the connection
+  merfolk_receiver.backend(|b| b.stop()).unwrap().unwrap();
+  std::thread::sleep(Duration::from_millis(100));
+
+  let err = merfolk_caller.frontend(|f| f.call::<_, i32>("add", &(1, 2)).unwrap_err()).unwrap();
+  assert!(matches!(err.downcast_ref::<merfolk_backend_tcp::Error>(), Some(merfolk_backend_tcp::Error::Connect(..))));
+
+  // the next call opens a new connection to the restarted server
+  let _merfolk_receiver = receiver();
+  let result: i32 = merfolk_caller.frontend(|f| f.call("add", &(3, 4)).unwrap()).unwrap();
+  assert_eq!(result, 7);
+}
@@ -0,0 +1,220 @@
+//! [`Codec`]s for merfolk.
+//!
+//! Every [`Codec`] is enabled by a feature of the same name, all features are enabled by default.
+//!
+//! | Codec           | Feature    | Intermediate |
+//! |-----------------|------------|--------------|
+//! | [`Json`]        | `json`     | `String`     |
+//! | [`Ron`]         | `ron`      | `String`     |
+//! | [`MessagePack`] | `msgpack`  | `Vec<u8>`    |
+//! | [`Cbor`]        | `cbor`     | `Vec<u8>`    |
+//! | [`Bincode`]     | `bincode`  | `Vec<u8>`    |
+//! | [`Postcard`]    | `postcard` | `Vec<u8>`    |
+//!
+//! Without the default `std` feature the crate is `no_std` and only offers the [`Postcard`] codec, the other codecs enable `std`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(any(feature = "json", feature = "ron", feature = "cbor"))]
+use alloc::string::String;
+#[cfg(any(feature = "postcard", feature = "msgpack", feature = "cbor", feature = "bincode"))]
+use alloc::vec::Vec;
+use core::fmt;
+
+use anyhow::Result;
+#[cfg(any(feature = "json", feature = "ron", feature = "msgpack", feature = "cbor", feature = "bincode", feature = "postcard"))]
+use merfolk::interfaces::Codec;
+
+#[derive(Debug)]
+pub enum Error {
+  #[cfg(feature = "json")]
+  Json(serde_json::Error),
+  #[cfg(feature = "ron")]
+  RonSerialize(ron::Error),
+  #[cfg(feature = "ron")]
+  RonDeserialize(ron::de::SpannedError),
+  #[cfg(feature = "msgpack")]
+  MessagePackSerialize(rmp_serde::encode::Error),
+  #[cfg(feature = "msgpack")]
+  MessagePackDeserialize(rmp_serde::decode::Error),
+  #[cfg(feature = "cbor")]
+  CborSerialize(String),
+  #[cfg(feature = "cbor")]
+  CborDeserialize(String),
+  #[cfg(feature = "bincode")]
+  Bincode(bincode::Error),
+  #[cfg(feature = "postcard")]
+  Postcard(postcard::Error),
+}
+
+impl fmt::Display for Error {
+  #[allow(unused_variables)]
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match *self {
+      #[cfg(feature = "json")]
+      Error::Json(ref e) => write!(f, "json failed: {}", e),
+      #[cfg(feature = "ron")]
+      Error::RonSerialize(ref e) => write!(f, "ron serializing failed: {}", e),
+      #[cfg(feature = "ron")]
+      Error::RonDeserialize(ref e) => write!(f, "ron deserializing failed: {}", e),
+      #[cfg(feature = "msgpack")]
+      Error::MessagePackSerialize(ref e) => write!(f, "messagepack serializing failed: {}", e),
+      #[cfg(feature = "msgpack")]
+      Error::MessagePackDeserialize(ref e) => write!(f, "messagepack deserializing failed: {}", e),
+      #[cfg(feature = "cbor")]
+      Error::CborSerialize(ref e) => write!(f, "cbor serializing failed: {}", e),
+      #[cfg(feature = "cbor")]
+      Error::CborDeserialize(ref e) => write!(f, "cbor deserializing failed: {}", e),
+      #[cfg(feature = "bincode")]
+      Error::Bincode(ref e) => write!(f, "bincode failed: {}", e),
+      #[cfg(feature = "postcard")]
+      Error::Postcard(ref e) => write!(f, "postcard failed: {}", e),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(not(feature = "std"))]
+impl From<Error> for anyhow::Error {
+  fn from(e: Error) -> Self {
+    anyhow::Error::msg(e)
+  }
+}
+
+/// [`Codec`] serializing to JSON using [`serde_json`].
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json;
+
+#[cfg(feature = "json")]
+impl Codec for Json {
+  type Intermediate = String;
+
+  fn encode<T: serde::Serialize>(from: &T) -> Result<String> {
+    serde_json::to_string(from).map_err(|e| Error::Json(e).into())
+  }
+
+  fn decode<T>(from: &String) -> Result<T>
+  where
+    T: for<'de> serde::Deserialize<'de>,
+  {
+    serde_json::from_str(from).map_err(|e| Error::Json(e).into())
+  }
+}
+
+/// [`Codec`] serializing to RON using [`ron`].
+#[cfg(feature = "ron")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ron;
+
+#[cfg(feature = "ron")]
+impl Codec for Ron {
+  type Intermediate = String;
+
+  fn encode<T: serde::Serialize>(from: &T) -> Result<String> {
+    ron::ser::to_string(from).map_err(|e| Error::RonSerialize(e).into())
+  }
+
+  fn decode<T>(from: &String) -> Result<T>
+  where
+    T: for<'de> serde::Deserialize<'de>,
+  {
+    ron::de::from_str(from).map_err(|e| Error::RonDeserialize(e).into())
+  }
+}
+
+/// [`Codec`] serializing to MessagePack using [`rmp_serde`].
+///
+/// Structs are serialized as maps so fields with defaults can be omitted by older peers.
+#[cfg(feature = "msgpack")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MessagePack;
+
+#[cfg(feature = "msgpack")]
+impl Codec for MessagePack {
+  type Intermediate = Vec<u8>;
+
+  fn encode<T: serde::Serialize>(from: &T) -> Result<Vec<u8>> {
+    rmp_serde::to_vec_named(from).map_err(|e| Error::MessagePackSerialize(e).into())
+  }
+
+  fn decode<T>(from: &Vec<u8>) -> Result<T>
+  where
+    T: for<'de> serde::Deserialize<'de>,
+  {
+    rmp_serde::from_slice(from).map_err(|e| Error::MessagePackDeserialize(e).into())
+  }
+}
+
+/// [`Codec`] serializing to CBOR using [`ciborium`].
+#[cfg(feature = "cbor")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Cbor;
+
+#[cfg(feature = "cbor")]
+impl Codec for Cbor {
+  type Intermediate = Vec<u8>;
+
+  fn encode<T: serde::Serialize>(from: &T) -> Result<Vec<u8>> {
+    let mut to = vec![];
+    ciborium::ser::into_writer(from, &mut to).map_err(|e| Error::CborSerialize(e.to_string()))?;
+    Ok(to)
+  }
+
+  fn decode<T>(from: &Vec<u8>) -> Result<T>
+  where
+    T: for<'de> serde::Deserialize<'de>,
+  {
+    ciborium::de::from_reader(from.as_slice()).map_err(|e| Error::CborDeserialize(e.to_string()).into())
+  }
+}
+
+/// [`Codec`] serializing to the compact binary format of [`bincode`].
+///
+/// The format is not self describing, both sides have to use the same types.
+#[cfg(feature = "bincode")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Bincode;
+
+#[cfg(feature = "bincode")]
+impl Codec for Bincode {
+  type Intermediate = Vec<u8>;
+
+  fn encode<T: serde::Serialize>(from: &T) -> Result<Vec<u8>> {
+    bincode::serialize(from).map_err(|e| Error::Bincode(e).into())
+  }
+
+  fn decode<T>(from: &Vec<u8>) -> Result<T>
+  where
+    T: for<'de> serde::Deserialize<'de>,
+  {
+    bincode::deserialize(from).map_err(|e| Error::Bincode(e).into())
+  }
+}
+
+/// [`Codec`] serializing to the compact binary format of [`postcard`], suited for embedded devices and slow links.
+///
+/// The format is not self describing, both sides have to use the same types.
+#[cfg(feature = "postcard")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Postcard;
+
+#[cfg(feature = "postcard")]
+impl Codec for Postcard {
+  type Intermediate = Vec<u8>;
+
+  fn encode<T: serde::Serialize>(from: &T) -> Result<Vec<u8>> {
+    postcard::to_allocvec(from).map_err(|e| Error::Postcard(e).into())
+  }
+
+  fn decode<T>(from: &Vec<u8>) -> Result<T>
+  where
+    T: for<'de> serde::Deserialize<'de>,
+  {
+    postcard::from_bytes(from).map_err(|e| Error::Postcard(e).into())
+  }
+}
@@ -0,0 +1,107 @@
+use merfolk::{
+  interfaces::{Codec, Encoded},
+  *,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Payload {
+  number: i32,
+  text: String,
+  optional: Option<Vec<u8>>,
+}
+
+fn payload() -> Payload {
+  Payload {
+    number: rand::random(),
+    text: "line\r\nbreak".into(),
+    optional: Some(vec![0, 1, 255]),
+  }
+}
+
+fn round_trip<C: Codec>() {
+  let payload = payload();
+
+  let encoded = C::encode(&payload).unwrap();
+  assert_eq!(C::decode::<Payload>(&encoded).unwrap(), payload);
+
+  let line = encoded.clone().into_line();
+  assert!(!line.contains('\n'));
+  assert_eq!(C::decode::<Payload>(&C::Intermediate::from_line(&line).unwrap()).unwrap(), payload);
+
+  let bytes = encoded.into_bytes();
+  assert_eq!(C::decode::<Payload>(&C::Intermediate::from_bytes(bytes).unwrap()).unwrap(), payload);
+}
+
+#[test]
+fn round_trip_json() {
+  round_trip::<merfolk_codecs::Json>();
+}
+
+#[test]
+fn round_trip_ron() {
+  round_trip::<merfolk_codecs::Ron>();
+}
+
+#[test]
+fn round_trip_msgpack() {
+  round_trip::<merfolk_codecs::MessagePack>();
+}
+
+#[test]
+fn round_trip_cbor() {
+  round_trip::<merfolk_codecs::Cbor>();
+}
+
+#[test]
+fn round_trip_bincode() {
+  round_trip::<merfolk_codecs::Bincode>();
+}
+
+#[test]
+fn round_trip_postcard() {
+  round_trip::<merfolk_codecs::Postcard>();
+}
+
+#[test]
+fn invalid_hex_line() {
+  assert!(Vec::<u8>::from_line("0").is_err());
+  assert!(Vec::<u8>::from_line("zz").is_err());
+}
+
+fn register_in_process<C: Codec>() {
+  use merfolk_backend_in_process::{InProcess, InProcessChannel};
+  use tokio::sync::mpsc::{channel, Receiver, Sender};
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder().build().unwrap();
+  register_receiver.register("echo", |payload: Payload| payload).unwrap();
+
+  let (to, from): (Sender<InProcessChannel<_>>, Receiver<InProcessChannel<_>>) = channel(1);
+
+  let merfolk_caller = Mer::builder()
+    .backend(InProcess::<C>::builder_with_codec().to(to).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(InProcess::<C>::builder_with_codec().from(from).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let payload = payload();
+  let result: Payload = merfolk_caller.frontend(|f| f.call("echo", &payload).unwrap()).unwrap();
+  assert_eq!(result, payload);
+}
+
+#[test]
+fn register_in_process_msgpack() {
+  register_in_process::<merfolk_codecs::MessagePack>();
+}
+
+#[test]
+fn register_in_process_postcard() {
+  register_in_process::<merfolk_codecs::Postcard>();
+}
@@ -0,0 +1,266 @@
+//! Framing of the packages sent by the serial `Backend`s of merfolk.
+//!
+//! The `merfolk_backend_serialport` and `merfolk_backend_embedded` backends share the [`Framing`] and the bodies of the frames so a host and a device using either backend understand each other.
+//! The crate is `no_std` unless the `std` feature is enabled.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::{format, string::String, vec, vec::Vec};
+use core::fmt;
+
+use anyhow::Result;
+use merfolk::{Metadata, RemoteError};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug)]
+/// Error type for [`Framing`] errors.
+pub enum Error {
+  MalformedFrame(String),
+  FrameTooLarge(usize),
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Error::MalformedFrame(reason) => write!(f, "malformed frame: {}", reason),
+      Error::FrameTooLarge(len) => write!(f, "frame of {} bytes is too large for the length prefix", len),
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(not(feature = "std"))]
+impl From<Error> for anyhow::Error {
+  fn from(e: Error) -> Self {
+    anyhow::Error::msg(e)
+  }
+}
+
+/// Kind of a frame carrying a call.
+pub const CALL: u8 = b'c';
+/// Kind of a frame carrying a reply or an item of a reply stream.
+pub const REPLY: u8 = b'r';
+/// Kind of a frame carrying a chunk of an upload.
+pub const CHUNK: u8 = b'u';
+/// Kind of a frame acknowledging a consumed chunk of an upload.
+pub const ACK: u8 = b'a';
+
+/// Bytes marking the start of a [`Framing::LengthPrefix`] frame.
+const MAGIC: [u8; 2] = [0xa5, 0x5a];
+
+const CRC: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
+
+/// Addresses of a frame on a multi-drop bus. They are sent in front of the body as `destination` and `source` byte, or as four hexadecimal digits for [`Framing::Lines`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Route {
+  pub source: u8,
+  pub destination: u8,
+}
+
+/// Body of a [`CALL`] frame. The `id` correlates the [`SelfReplyFrame`]s, [`SelfChunkFrame`]s and [`SelfAckFrame`]s to the call.
+#[derive(Serialize, Deserialize)]
+pub struct SelfCall<T> {
+  pub id: u64,
+  pub procedure: String,
+  pub payload: T,
+  #[serde(default)]
+  pub metadata: Metadata,
+}
+
+/// Reply carried by a [`SelfReplyFrame`].
+#[derive(Serialize, Deserialize)]
+pub struct SelfReply<T> {
+  pub payload: T,
+  #[serde(default)]
+  pub metadata: Metadata,
+}
+
+/// Body of a [`REPLY`] frame. The `id` correlates the reply to the [`SelfCall`] and is `None` if the call could not be deserialized.
+///
+/// Streaming calls are answered with a frame per item followed by a frame with `end` set.
+#[derive(Serialize, Deserialize)]
+pub struct SelfReplyFrame<T> {
+  pub id: Option<u64>,
+  pub reply: Result<SelfReply<T>, RemoteError>,
+  #[serde(default)]
+  pub end: bool,
+}
+
+/// Body of a [`CHUNK`] frame of the upload following the [`SelfCall`] with the `id`. The `chunk` is `None` for the frame marking the end of the upload.
+#[derive(Serialize, Deserialize)]
+pub struct SelfChunkFrame<T> {
+  pub id: u64,
+  pub chunk: Option<Result<T, RemoteError>>,
+}
+
+/// Body of an [`ACK`] frame acknowledging that the receiving procedure consumed a chunk of the upload with the `id`.
+#[derive(Serialize, Deserialize)]
+pub struct SelfAckFrame {
+  pub id: u64,
+}
+
+/// Framing of the packages sent over the port.
+///
+/// The binary framings protect every frame with a CRC-32, frames failing the check are dropped and the reader resyncs to the next frame.
+/// They carry the bytes of the `Codec` unchanged and are best combined with a binary `Codec` like `merfolk_codecs::Postcard`.
+///
+/// Both sides have to use the same [`Framing`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Framing {
+  /// Text lines starting with the kind of the frame (`c:`, `r:`, `u:` or `a:`) and ending with `\r\n`. Binary `Codec`s are sent as hexadecimal text.
+  #[default]
+  Lines,
+  /// The kind, the bytes and the CRC of the frame COBS encoded and terminated with a `0x00` byte.
+  Cobs,
+  /// The magic `0xa5 0x5a`, the length of the kind and the bytes as little endian `u16`, the kind, the bytes and the CRC of the length, the kind and the bytes.
+  LengthPrefix,
+}
+
+impl Framing {
+  /// Wraps the `body` of a frame of the `kind` for sending. The `route` is only sent if the nodes are addressed.
+  pub fn wrap(self, kind: u8, route: Option<Route>, body: &[u8]) -> Result<Vec<u8>> {
+    let route = match (self, route) {
+      (_, None) => vec![],
+      (Framing::Lines, Some(route)) => format!("{:02x}{:02x}", route.destination, route.source).into_bytes(),
+      (_, Some(route)) => vec![route.destination, route.source],
+    };
+    let body = &[route.as_slice(), body].concat();
+
+    match self {
+      Framing::Lines => Ok([&[kind, b':'], body.as_slice(), b"\r\n"].concat()),
+      Framing::Cobs => {
+        let data = [&[kind], body.as_slice()].concat();
+        let mut frame = cobs::encode_vec(&[data.as_slice(), &CRC.checksum(&data).to_le_bytes()].concat());
+        frame.push(0);
+        Ok(frame)
+      }
+      Framing::LengthPrefix => {
+        let len = u16::try_from(body.len() + 1).map_err(|_| Error::FrameTooLarge(body.len()))?;
+        let data = [&len.to_le_bytes()[..], &[kind], body.as_slice()].concat();
+        Ok([&MAGIC[..], &data, &CRC.checksum(&data).to_le_bytes()].concat())
+      }
+    }
+  }
+
+  /// Splits the [`Route`] from the `body` of a frame of addressed nodes.
+  pub fn route(self, mut body: Vec<u8>) -> Result<(Route, Vec<u8>)> {
+    let len = match self {
+      Framing::Lines => 4,
+      _ => 2,
+    };
+    if body.len() < len {
+      return Err(Error::MalformedFrame("missing addresses".into()).into());
+    }
+
+    let rest = body.split_off(len);
+    let addresses = match self {
+      Framing::Lines => core::str::from_utf8(&body)
+        .ok()
+        .and_then(|hex| Some([u8::from_str_radix(hex.get(0..2)?, 16).ok()?, u8::from_str_radix(hex.get(2..4)?, 16).ok()?]))
+        .ok_or_else(|| Error::MalformedFrame("invalid addresses".into()))?,
+      _ => [body[0], body[1]],
+    };
+
+    Ok((
+      Route {
+        destination: addresses[0],
+        source: addresses[1],
+      },
+      rest,
+    ))
+  }
+
+  /// Removes the next frame from the `buffer` and returns its kind and body.
+  ///
+  /// Returns `None` if the `buffer` does not contain a complete frame. Malformed frames are removed from the `buffer` and returned as error so the next call resyncs to the following frame.
+  pub fn next(self, buffer: &mut Vec<u8>) -> Option<Result<(u8, Vec<u8>)>> {
+    match self {
+      Framing::Lines => {
+        let end = buffer.windows(2).position(|w| w == b"\r\n")?;
+        let part: Vec<u8> = buffer.drain(..end + 2).take(end).collect();
+
+        match part.get(0..2) {
+          Some([kind, b':']) => Some(Ok((*kind, part[2..].to_vec()))),
+          _ => Some(Err(Error::MalformedFrame("missing kind prefix".into()).into())),
+        }
+      }
+      Framing::Cobs => loop {
+        let end = buffer.iter().position(|b| *b == 0)?;
+        let part: Vec<u8> = buffer.drain(..end + 1).take(end).collect();
+
+        // consecutive delimiters are allowed to flush the line
+        if part.is_empty() {
+          continue;
+        }
+
+        let data = match cobs::decode_vec(&part) {
+          Ok(data) => data,
+          Err(e) => return Some(Err(Error::MalformedFrame(format!("invalid COBS encoding: {:?}", e)).into())),
+        };
+
+        return Some(checked(&data).map(|data| (data[0], data[1..].to_vec())));
+      },
+      Framing::LengthPrefix => {
+        let skipped = resync(buffer, 0);
+        if skipped > 0 {
+          return Some(Err(Error::MalformedFrame(format!("skipped {} bytes before magic", skipped)).into()));
+        }
+
+        let len = usize::from(u16::from_le_bytes([*buffer.get(2)?, *buffer.get(3)?]));
+        if len == 0 {
+          resync(buffer, 1);
+          return Some(Err(Error::MalformedFrame("empty frame".into()).into()));
+        }
+
+        let total = MAGIC.len() + 2 + len + 4;
+        if buffer.len() < total {
+          return None;
+        }
+
+        match checked(&buffer[MAGIC.len()..total]) {
+          Ok(data) => {
+            let frame = (data[2], data[3..].to_vec());
+            buffer.drain(..total);
+            Some(Ok(frame))
+          }
+          Err(e) => {
+            // the length may be damaged, so the next magic is searched within the frame
+            resync(buffer, 1);
+            Some(Err(e))
+          }
+        }
+      }
+    }
+  }
+}
+
+/// Removes the bytes before the first [`MAGIC`] found at or after `from` from the `buffer` and returns their number.
+///
+/// A trailing first byte of the [`MAGIC`] is kept as it can be completed by the next read.
+fn resync(buffer: &mut Vec<u8>, from: usize) -> usize {
+  let start = match buffer.get(from..).and_then(|rest| rest.windows(2).position(|w| w == MAGIC)) {
+    Some(start) => from + start,
+    None => buffer.len() - usize::from(buffer.len() > from && buffer.last() == Some(&MAGIC[0])),
+  };
+
+  buffer.drain(..start);
+  start
+}
+
+/// Checks the CRC at the end of the `data` and returns the `data` without it.
+fn checked(data: &[u8]) -> Result<&[u8]> {
+  if data.len() < 5 {
+    return Err(Error::MalformedFrame(format!("frame of {} bytes is too short", data.len())).into());
+  }
+
+  let (data, crc) = data.split_at(data.len() - 4);
+  if CRC.checksum(data).to_le_bytes() != crc {
+    return Err(Error::MalformedFrame("CRC mismatch".into()).into());
+  }
+
+  Ok(data)
+}
@@ -1,3 +1,10 @@
+use core::fmt::Debug;
+
+use merfolk::{
+  helpers::future::BoxFuture,
+  interfaces::{Backend, Encoded},
+  Call, ProcedureDescription, RemoteError, Reply,
+};
 pub use merfolk_frontend_derive_macros::frontend;
 #[cfg(feature = "std")]
 use thiserror::Error;
@@ -6,8 +13,11 @@ pub mod reexports {
   pub use anyhow;
   pub use derive_builder;
   pub use merfolk;
+  pub use serde;
 }
 
+pub mod schema;
+
 #[cfg(feature = "std")]
 #[derive(Debug, Error)]
 pub enum Error {
@@ -17,4 +27,138 @@ pub enum Error {
   UnknownProcedure { procedure: String },
   #[error("error locking mutex")]
   MutexLock,
+  #[error("no caller registered")]
+  NoCaller,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "std", derive(Error))]
+/// Error returned by derived callers of procedures returning `Result<T, E>`.
+///
+/// Application errors `E` returned by the procedure are kept apart from errors of the transport.
+pub enum CallError<E> {
+  #[cfg_attr(feature = "std", error("application error: {0:?}"))]
+  Application(E),
+  #[cfg_attr(feature = "std", error("transport error: {0}"))]
+  Transport(anyhow::Error),
+}
+
+impl<E> From<anyhow::Error> for CallError<E> {
+  fn from(e: anyhow::Error) -> Self {
+    CallError::Transport(e)
+  }
+}
+
+impl<E> CallError<E> {
+  /// Converts an error of a [`Call`](merfolk::Call) to a [`CallError`].
+  ///
+  /// A [`RemoteError`] with the [`APPLICATION_ERROR`](RemoteError::APPLICATION_ERROR) code is deserialized to `E`, any other error is a [`CallError::Transport`].
+  pub fn from_call<B>(e: anyhow::Error) -> Self
+  where
+    B: Backend,
+    B::Intermediate: Encoded + Debug + Send + Sync + 'static,
+    E: for<'de> serde::Deserialize<'de>,
+  {
+    match e.downcast::<RemoteError>() {
+      Ok(RemoteError { code, data: Some(data), .. }) if code == RemoteError::APPLICATION_ERROR => match B::Intermediate::from_line(&data).and_then(|data| B::deserialize::<E>(&data)) {
+        Ok(application) => CallError::Application(application),
+        Err(e) => CallError::Transport(e),
+      },
+      Ok(remote) => CallError::Transport(remote.into()),
+      Err(e) => CallError::Transport(e),
+    }
+  }
+
+  /// Converts an application error `E` returned by a procedure to a [`RemoteError`] with the [`APPLICATION_ERROR`](RemoteError::APPLICATION_ERROR) code.
+  ///
+  /// The `data` is the encoded error as a line (see [`Encoded`]) so it is carried by every [`Backend`] regardless of its [`Intermediate`](Backend::Intermediate) type.
+  pub fn to_remote<B>(application: &E) -> anyhow::Error
+  where
+    B: Backend,
+    B::Intermediate: Encoded + Debug + Send + Sync + 'static,
+    E: serde::Serialize,
+  {
+    match B::serialize(application) {
+      Ok(data) => RemoteError::new(RemoteError::APPLICATION_ERROR, "application error").with_data(data.into_line()).into(),
+      Err(e) => e,
+    }
+  }
+}
+
+type CallFn<I> = Box<dyn Fn(Call<I>) -> anyhow::Result<Reply<I>> + Send + Sync>;
+type AsyncCallFn<I> = Box<dyn Fn(Call<I>) -> BoxFuture<'static, anyhow::Result<Reply<I>>> + Send + Sync>;
+
+/// Holds the callers registered by [`Mer`](merfolk::Mer) or [`AsyncMer`](merfolk::AsyncMer) which are used by the generated caller methods.
+pub struct Caller<B: Backend> {
+  call: Option<CallFn<B::Intermediate>>,
+  async_call: Option<AsyncCallFn<B::Intermediate>>,
+}
+
+impl<B: Backend> Default for Caller<B> {
+  fn default() -> Self {
+    Self { call: None, async_call: None }
+  }
+}
+
+impl<B: Backend> Caller<B> {
+  /// Registers the caller of a [`Frontend`](merfolk::interfaces::Frontend).
+  pub fn register<T>(&mut self, caller: T)
+  where
+    T: Fn(Call<B::Intermediate>) -> anyhow::Result<Reply<B::Intermediate>> + Send + Sync + 'static,
+  {
+    self.call = Some(Box::new(caller));
+  }
+
+  /// Registers the caller of an [`AsyncFrontend`](merfolk::interfaces::AsyncFrontend).
+  pub fn register_async<T>(&mut self, caller: T)
+  where
+    T: Fn(Call<B::Intermediate>) -> BoxFuture<'static, anyhow::Result<Reply<B::Intermediate>>> + Send + Sync + 'static,
+  {
+    self.async_call = Some(Box::new(caller));
+  }
+
+  /// Passes the [`Call`] to the registered caller.
+  ///
+  /// If only an asynchronous caller is registered the current thread is blocked until the [`Reply`] is received.
+  pub fn call(&self, call: Call<B::Intermediate>) -> anyhow::Result<Reply<B::Intermediate>> {
+    match (&self.call, &self.async_call) {
+      (Some(caller), _) => caller(call),
+      #[cfg(feature = "std")]
+      (None, Some(caller)) => merfolk::helpers::future::block_on(caller(call)),
+      #[cfg(feature = "std")]
+      (None, None) => Err(Error::NoCaller.into()),
+      #[cfg(not(feature = "std"))]
+      (None, _) => Err(anyhow::Error::msg("no caller registered")),
+    }
+  }
+
+  /// Passes the [`Call`] to the registered caller without blocking if an asynchronous caller is registered.
+  pub async fn call_async(&self, call: Call<B::Intermediate>) -> anyhow::Result<Reply<B::Intermediate>> {
+    match &self.async_call {
+      Some(caller) => caller(call).await,
+      None => self.call(call),
+    }
+  }
+}
+
+/// Result of dispatching a [`Call`] to the procedures of a trait.
+///
+/// The [`Call`] is returned if it does not match any procedure of the trait.
+pub type Dispatched<I> = Result<anyhow::Result<Reply<I>>, Call<I>>;
+
+/// Dispatches incoming [`Call`]s to the procedures of a `#[frontend(target = "...")]` trait.
+///
+/// Implemented for the `target` by the [`frontend`] macro for every trait. `M` is a marker type generated for each trait so several traits can have the same `target`.
+pub trait Dispatch<B: Backend, M> {
+  fn dispatch(&self, call: Call<B::Intermediate>) -> Dispatched<B::Intermediate>;
+
+  /// Describes the procedures of the trait, used to answer [`ProcedureDescription::DISCOVER`].
+  fn procedures(&self) -> Vec<ProcedureDescription> {
+    Vec::new()
+  }
+}
+
+/// Asynchronous counterpart of [`Dispatch`].
+pub trait AsyncDispatch<B: Backend, M> {
+  fn dispatch_async(&self, call: Call<B::Intermediate>) -> BoxFuture<'_, Dispatched<B::Intermediate>>;
 }
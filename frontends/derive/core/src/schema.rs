@@ -0,0 +1,187 @@
+//! [OpenRPC](https://spec.open-rpc.org) like schema of the procedures of a `#[frontend]` trait.
+//!
+//! The schema is generated by the [`frontend`](crate::frontend) macro and returned by `<Trait>Client::schema()`.
+//! Serialized to JSON it can be used to generate clients in other languages.
+//!
+//! In contrast to JSON-RPC the arguments of procedures with [`ParamStructure::ByPosition`] and a single parameter are not wrapped in an array.
+
+use serde::{Deserialize, Serialize};
+
+/// Version of the OpenRPC specification the [`Schema`] follows.
+pub const OPENRPC_VERSION: &str = "1.2.6";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Schema of the procedures of a `#[frontend]` trait.
+pub struct Schema {
+  pub openrpc: String,
+  pub info: Info,
+  pub methods: Vec<Method>,
+}
+
+impl Schema {
+  /// Creates a new [`Schema`] without [`Method`]s.
+  pub fn new<T: ToString, V: ToString>(title: T, version: V) -> Self {
+    Self {
+      openrpc: OPENRPC_VERSION.to_string(),
+      info: Info {
+        title: title.to_string(),
+        version: version.to_string(),
+      },
+      methods: Vec::new(),
+    }
+  }
+
+  /// Adds a [`Method`].
+  pub fn method(mut self, method: Method) -> Self {
+    self.methods.push(method);
+    self
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Title and version of a [`Schema`].
+pub struct Info {
+  pub title: String,
+  pub version: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+/// How the arguments of a [`Method`] are encoded in the payload.
+pub enum ParamStructure {
+  /// The arguments are encoded as tuple (`args = "positional"`).
+  ByPosition,
+  /// The arguments are encoded as map keyed by the parameter names (`args = "named"`).
+  ByName,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Schema of a procedure.
+pub struct Method {
+  pub name: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub description: Option<String>,
+  pub params: Vec<ContentDescriptor>,
+  pub result: ContentDescriptor,
+  pub param_structure: ParamStructure,
+}
+
+impl Method {
+  /// Creates a new [`Method`] without parameters returning `null`.
+  pub fn new<N: ToString>(name: N, param_structure: ParamStructure) -> Self {
+    Self {
+      name: name.to_string(),
+      description: None,
+      params: Vec::new(),
+      result: ContentDescriptor::new("result", TypeSchema::of("null", "()")),
+      param_structure,
+    }
+  }
+
+  /// Sets the description.
+  pub fn description<D: ToString>(mut self, description: D) -> Self {
+    self.description = Some(description.to_string());
+    self
+  }
+
+  /// Adds a parameter.
+  pub fn param(mut self, param: ContentDescriptor) -> Self {
+    self.params.push(param);
+    self
+  }
+
+  /// Sets the result.
+  pub fn result(mut self, result: ContentDescriptor) -> Self {
+    self.result = result;
+    self
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// Named [`TypeSchema`] of a parameter or result.
+pub struct ContentDescriptor {
+  pub name: String,
+  pub schema: TypeSchema,
+}
+
+impl ContentDescriptor {
+  pub fn new<N: ToString>(name: N, schema: TypeSchema) -> Self {
+    Self { name: name.to_string(), schema }
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+/// Subset of [JSON Schema](https://json-schema.org) describing a Rust type.
+///
+/// Types the [`frontend`](crate::frontend) macro does not know (e.g. structs) only carry their Rust type in `x-rust-type`.
+pub struct TypeSchema {
+  #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+  pub ty: Option<String>,
+  /// Schema of the elements of an `array`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub items: Option<Box<TypeSchema>>,
+  /// Schemas of the elements of a tuple.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub prefix_items: Vec<TypeSchema>,
+  /// Schema of the values of a map.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub additional_properties: Option<Box<TypeSchema>>,
+  /// Alternatives of an `Option`.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub one_of: Vec<TypeSchema>,
+  #[serde(rename = "x-rust-type")]
+  pub rust_type: String,
+}
+
+impl TypeSchema {
+  /// Schema of a primitive JSON Schema type.
+  pub fn of<T: ToString, R: ToString>(ty: T, rust_type: R) -> Self {
+    Self {
+      ty: Some(ty.to_string()),
+      rust_type: rust_type.to_string(),
+      ..Default::default()
+    }
+  }
+
+  /// Schema of a type unknown to JSON Schema.
+  pub fn opaque<R: ToString>(rust_type: R) -> Self {
+    Self {
+      rust_type: rust_type.to_string(),
+      ..Default::default()
+    }
+  }
+
+  /// Schema of a sequence of `items`.
+  pub fn array<R: ToString>(items: TypeSchema, rust_type: R) -> Self {
+    Self {
+      items: Some(Box::new(items)),
+      ..Self::of("array", rust_type)
+    }
+  }
+
+  /// Schema of a tuple of `items`.
+  pub fn tuple<R: ToString>(items: Vec<TypeSchema>, rust_type: R) -> Self {
+    Self {
+      prefix_items: items,
+      ..Self::of("array", rust_type)
+    }
+  }
+
+  /// Schema of a map with string keys and `values`.
+  pub fn map<R: ToString>(values: TypeSchema, rust_type: R) -> Self {
+    Self {
+      additional_properties: Some(Box::new(values)),
+      ..Self::of("object", rust_type)
+    }
+  }
+
+  /// Schema of an optional `value`.
+  pub fn nullable<R: ToString>(value: TypeSchema, rust_type: R) -> Self {
+    Self {
+      one_of: vec![value, Self::of("null", "()")],
+      ..Self::opaque(rust_type)
+    }
+  }
+}
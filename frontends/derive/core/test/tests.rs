@@ -130,3 +130,559 @@ fn derive_http() {
   let (a, b) = (rand::random::<i32>() / 2, rand::random::<i32>() / 2);
   assert_eq!(merfolk_caller.frontend(|f| { f.add(a, b).unwrap() }).unwrap(), a + b);
 }
+
+#[test]
+fn derive_in_process_typed_error() {
+  #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+  enum DivisionError {
+    DivisionByZero,
+  }
+
+  #[merfolk_frontend_derive::frontend()]
+  struct Data {}
+
+  #[merfolk_frontend_derive::frontend(target = "Data")]
+  trait Receiver {
+    fn divide(a: i32, b: i32) -> Result<i32, DivisionError> {
+      if b == 0 {
+        Err(DivisionError::DivisionByZero)
+      } else {
+        Ok(a / b)
+      }
+    }
+  }
+
+  let (to, from): (
+    tokio::sync::mpsc::Sender<merfolk_backend_in_process::InProcessChannel>,
+    tokio::sync::mpsc::Receiver<merfolk_backend_in_process::InProcessChannel>,
+  ) = tokio::sync::mpsc::channel(1);
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap())
+    .frontend(Data::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  let _merfolk_register = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+    .frontend(Data::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  let a = rand::random::<i32>();
+  assert_eq!(merfolk_caller.frontend(|f| f.divide(a, 1).unwrap()).unwrap(), a);
+
+  match merfolk_caller.frontend(|f| f.divide(a, 0)).unwrap() {
+    Err(merfolk_frontend_derive::CallError::Application(err)) => assert_eq!(err, DivisionError::DivisionByZero),
+    other => panic!("expected application error, got {:?}", other),
+  }
+}
+
+#[test]
+fn derive_in_process_typed_error_postcard() {
+  #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+  enum DivisionError {
+    DivisionByZero { dividend: i32 },
+  }
+
+  #[merfolk_frontend_derive::frontend()]
+  struct Data {}
+
+  #[merfolk_frontend_derive::frontend(target = "Data")]
+  trait Receiver {
+    fn divide(a: i32, b: i32) -> Result<i32, DivisionError> {
+      if b == 0 {
+        Err(DivisionError::DivisionByZero { dividend: a })
+      } else {
+        Ok(a / b)
+      }
+    }
+  }
+
+  let (to, from) = tokio::sync::mpsc::channel::<merfolk_backend_in_process::InProcessChannel<Vec<u8>>>(1);
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::<merfolk_codecs::Postcard>::builder_with_codec().to(to).build().unwrap())
+    .frontend(Data::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  let _merfolk_register = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::<merfolk_codecs::Postcard>::builder_with_codec().from(from).build().unwrap())
+    .frontend(Data::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  // the application error is carried by the binary codec with its data
+  let a = rand::random::<i32>();
+  match merfolk_caller.frontend(|f| f.divide(a, 0)).unwrap() {
+    Err(merfolk_frontend_derive::CallError::Application(err)) => assert_eq!(err, DivisionError::DivisionByZero { dividend: a }),
+    other => panic!("expected application error, got {:?}", other),
+  }
+}
+
+#[test]
+fn derive_http_typed_error() {
+  #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+  struct NotFound {
+    key: String,
+  }
+
+  #[merfolk_frontend_derive::frontend()]
+  struct Data {}
+
+  #[merfolk_frontend_derive::frontend(target = "Data")]
+  trait Receiver {
+    fn lookup(key: String) -> Result<i32, NotFound> {
+      Err(NotFound { key })
+    }
+  }
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_http::Http::builder().speak("http://localhost:8091".parse::<hyper::Uri>().unwrap()).build().unwrap())
+    .frontend(Data::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  let _merfolk_register = Mer::builder()
+    .backend(
+      merfolk_backend_http::Http::builder()
+        .listen(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8091))
+        .build()
+        .unwrap(),
+    )
+    .frontend(Data::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  match merfolk_caller.frontend(|f| f.lookup("merfolk".to_string())).unwrap() {
+    Err(merfolk_frontend_derive::CallError::Application(err)) => assert_eq!(err, NotFound { key: "merfolk".to_string() }),
+    other => panic!("expected application error, got {:?}", other),
+  }
+}
+
+#[test]
+fn derive_in_process_async() {
+  use tokio::sync::mpsc::{channel, Sender};
+
+  #[merfolk_frontend_derive::frontend()]
+  struct Data {
+    pub offset: i32,
+  }
+
+  #[merfolk_frontend_derive::frontend(target = "Data")]
+  trait Receiver {
+    async fn add(a: i32, b: i32) -> i32 {
+      tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+      a + b
+    }
+
+    async fn add_with_offset(&self, a: i32, b: i32) -> i32 {
+      tokio::task::yield_now().await;
+      a + b + self.offset
+    }
+
+    fn subtract(a: i32, b: i32) -> i32 {
+      a - b
+    }
+  }
+
+  tokio::runtime::Runtime::new().unwrap().block_on(async {
+    let (to, from): (
+      Sender<merfolk_backend_in_process::InProcessChannel>,
+      tokio::sync::mpsc::Receiver<merfolk_backend_in_process::InProcessChannel>,
+    ) = channel(1);
+
+    let merfolk_caller = AsyncMer::builder()
+      .backend(merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap())
+      .frontend(Data::builder().offset(0).build().unwrap())
+      .build()
+      .unwrap();
+
+    let _merfolk_receiver = AsyncMer::builder()
+      .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+      .frontend(Data::builder().offset(32).build().unwrap())
+      .build()
+      .unwrap();
+
+    let (a, b) = (rand::random::<i32>() / 4, rand::random::<i32>() / 4);
+    assert_eq!(merfolk_caller.frontend().add(a, b).await.unwrap(), a + b);
+    assert_eq!(merfolk_caller.frontend().add_with_offset(a, b).await.unwrap(), a + b + 32);
+    assert_eq!(tokio::task::block_in_place(|| merfolk_caller.frontend().subtract(a, b)).unwrap(), a - b);
+  });
+}
+
+#[test]
+fn derive_in_process_async_blocking() {
+  #[merfolk_frontend_derive::frontend()]
+  struct Data {}
+
+  #[merfolk_frontend_derive::frontend(target = "Data")]
+  trait Receiver {
+    async fn add(a: i32, b: i32) -> i32 {
+      a + b
+    }
+  }
+
+  let (to, from): (
+    tokio::sync::mpsc::Sender<merfolk_backend_in_process::InProcessChannel>,
+    tokio::sync::mpsc::Receiver<merfolk_backend_in_process::InProcessChannel>,
+  ) = tokio::sync::mpsc::channel(1);
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap())
+    .frontend(Data::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  let _merfolk_register = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+    .frontend(Data::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  let (a, b) = (rand::random::<i32>() / 2, rand::random::<i32>() / 2);
+  assert_eq!(merfolk_caller.frontend(|f| merfolk::helpers::future::block_on(f.add(a, b)).unwrap()).unwrap(), a + b);
+}
+
+#[test]
+fn derive_in_process_named_arguments() {
+  #[merfolk_frontend_derive::frontend()]
+  struct DataC {}
+
+  #[merfolk_frontend_derive::frontend()]
+  struct DataR {}
+
+  #[merfolk_frontend_derive::frontend(target = "DataC", args = "named")]
+  trait Caller {
+    #[merfolk_frontend_derive::frontend(definition_only)]
+    fn greet(name: String) -> String;
+  }
+
+  #[merfolk_frontend_derive::frontend(target = "DataR", args = "named")]
+  trait Receiver {
+    fn greet(greeting: Option<String>, name: String, #[serde(default)] times: u32) -> String {
+      format!("{} {}{}", greeting.unwrap_or_else(|| "hello".to_string()), name, "!".repeat(times as usize))
+    }
+  }
+
+  let (to, from): (
+    tokio::sync::mpsc::Sender<merfolk_backend_in_process::InProcessChannel>,
+    tokio::sync::mpsc::Receiver<merfolk_backend_in_process::InProcessChannel>,
+  ) = tokio::sync::mpsc::channel(1);
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap())
+    .frontend(DataC::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  let _merfolk_register = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+    .frontend(DataR::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  assert_eq!(merfolk_caller.frontend(|f| f.greet("merfolk".to_string()).unwrap()).unwrap(), "hello merfolk");
+}
+
+#[test]
+fn derive_in_process_procedure_names() {
+  #[merfolk_frontend_derive::frontend()]
+  struct DataC {}
+
+  #[merfolk_frontend_derive::frontend()]
+  struct DataR {}
+
+  #[merfolk_frontend_derive::frontend(target = "DataC")]
+  trait Caller {
+    #[merfolk_frontend_derive::frontend(definition_only, name = "billing.create_invoice")]
+    fn create(amount: u32) -> String;
+
+    #[merfolk_frontend_derive::frontend(definition_only)]
+    fn invoice(amount: u32) -> String;
+  }
+
+  #[merfolk_frontend_derive::frontend(target = "DataR", namespace = "billing")]
+  trait Receiver {
+    #[merfolk_frontend_derive::frontend(name = "create_invoice", alias = "invoice")]
+    fn create(amount: u32) -> String {
+      format!("invoice over {}", amount)
+    }
+  }
+
+  let (to, from): (
+    tokio::sync::mpsc::Sender<merfolk_backend_in_process::InProcessChannel>,
+    tokio::sync::mpsc::Receiver<merfolk_backend_in_process::InProcessChannel>,
+  ) = tokio::sync::mpsc::channel(1);
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap())
+    .frontend(DataC::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  let _merfolk_register = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+    .frontend(DataR::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  assert_eq!(merfolk_caller.frontend(|f| f.create(42).unwrap()).unwrap(), "invoice over 42");
+  assert_eq!(merfolk_caller.frontend(|f| f.invoice(42).unwrap()).unwrap(), "invoice over 42");
+}
+
+#[test]
+fn derive_in_process_multiple_traits() {
+  #[merfolk_frontend_derive::frontend(traits(UserApi, AdminApi))]
+  struct Service {}
+
+  #[merfolk_frontend_derive::frontend(target = "Service", namespace = "user")]
+  trait UserApi {
+    fn name(id: u32) -> String {
+      format!("user {}", id)
+    }
+  }
+
+  #[merfolk_frontend_derive::frontend(target = "Service", namespace = "admin")]
+  trait AdminApi {
+    fn delete(id: u32) -> bool {
+      id != 0
+    }
+  }
+
+  let (to, from): (
+    tokio::sync::mpsc::Sender<merfolk_backend_in_process::InProcessChannel>,
+    tokio::sync::mpsc::Receiver<merfolk_backend_in_process::InProcessChannel>,
+  ) = tokio::sync::mpsc::channel(1);
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap())
+    .frontend(Service::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  let _merfolk_register = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+    .frontend(Service::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  assert_eq!(merfolk_caller.frontend(|f| f.name(42).unwrap()).unwrap(), "user 42");
+  assert!(merfolk_caller.frontend(|f| f.delete(42).unwrap()).unwrap());
+  assert!(!merfolk_caller.frontend(|f| f.delete(0).unwrap()).unwrap());
+}
+
+#[test]
+fn derive_in_process_client() {
+  #[merfolk_frontend_derive::frontend()]
+  struct Data {}
+
+  #[merfolk_frontend_derive::frontend(target = "Data")]
+  trait Receiver {
+    fn add(a: i32, b: i32) -> i32 {
+      a + b
+    }
+  }
+
+  #[merfolk_frontend_derive::frontend()]
+  trait Shared {
+    fn add(a: i32, b: i32) -> i32;
+  }
+
+  let (to, from): (
+    tokio::sync::mpsc::Sender<merfolk_backend_in_process::InProcessChannel>,
+    tokio::sync::mpsc::Receiver<merfolk_backend_in_process::InProcessChannel>,
+  ) = tokio::sync::mpsc::channel(1);
+
+  let merfolk_receiver_client = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().to(to.clone()).build().unwrap())
+    .frontend(ReceiverClient::new())
+    .build()
+    .unwrap();
+
+  let merfolk_shared_client = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap())
+    .frontend(SharedClient::new())
+    .build()
+    .unwrap();
+
+  let _merfolk_register = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+    .frontend(Data::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  let (a, b) = (rand::random::<i32>() / 2, rand::random::<i32>() / 2);
+  assert_eq!(merfolk_receiver_client.frontend(|f| f.add(a, b).unwrap()).unwrap(), a + b);
+  assert_eq!(merfolk_shared_client.frontend(|f| f.add(a, b).unwrap()).unwrap(), a + b);
+}
+
+#[test]
+fn derive_in_process_discover() {
+  #[merfolk_frontend_derive::frontend()]
+  struct DataC {}
+
+  #[merfolk_frontend_derive::frontend(traits(Math, Text))]
+  struct DataR {}
+
+  #[merfolk_frontend_derive::frontend(target = "DataC")]
+  trait Caller {
+    #[merfolk_frontend_derive::frontend(definition_only, name = "rpc.discover")]
+    fn discover() -> Vec<ProcedureDescription>;
+  }
+
+  #[merfolk_frontend_derive::frontend(target = "DataR", namespace = "math")]
+  trait Math {
+    /// Adds two numbers.
+    ///
+    /// Overflows are not checked.
+    fn add(a: i32, b: i32) -> i32 {
+      a + b
+    }
+  }
+
+  #[merfolk_frontend_derive::frontend(target = "DataR")]
+  trait Text {
+    fn join(parts: Vec<String>, separator: String) -> Result<String, String> {
+      Ok(parts.join(&separator))
+    }
+
+    fn log(message: String) {
+      println!("{}", message);
+    }
+  }
+
+  let (to, from): (
+    tokio::sync::mpsc::Sender<merfolk_backend_in_process::InProcessChannel>,
+    tokio::sync::mpsc::Receiver<merfolk_backend_in_process::InProcessChannel>,
+  ) = tokio::sync::mpsc::channel(1);
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap())
+    .frontend(DataC::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  let _merfolk_register = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+    .frontend(DataR::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  assert_eq!(
+    merfolk_caller.frontend(|f| f.discover().unwrap()).unwrap(),
+    vec![
+      ProcedureDescription::new("math.add")
+        .argument(ArgumentDescription::new("i32").name("a"))
+        .argument(ArgumentDescription::new("i32").name("b"))
+        .returns("i32")
+        .docs("Adds two numbers.\n\nOverflows are not checked."),
+      ProcedureDescription::new("join")
+        .argument(ArgumentDescription::new("Vec<String>").name("parts"))
+        .argument(ArgumentDescription::new("String").name("separator"))
+        .returns("String"),
+      ProcedureDescription::new("log").argument(ArgumentDescription::new("String").name("message")),
+    ]
+  );
+}
+
+#[test]
+fn derive_schema() {
+  use merfolk_frontend_derive::schema::{ContentDescriptor, Method, ParamStructure, Schema, TypeSchema};
+
+  #[merfolk_frontend_derive::frontend()]
+  struct Data {}
+
+  #[merfolk_frontend_derive::frontend(target = "Data", namespace = "inventory")]
+  trait Inventory {
+    /// Looks up the stock of an item.
+    fn stock(item: String, warehouses: Option<Vec<u32>>) -> Result<std::collections::HashMap<String, f64>, String> {
+      Ok([(item, warehouses.map(|w| w.len()).unwrap_or_default() as f64)].into_iter().collect())
+    }
+
+    fn clear() {}
+  }
+
+  #[merfolk_frontend_derive::frontend(args = "named")]
+  trait Named {
+    fn rename(from: String, to: (bool, char));
+  }
+
+  assert_eq!(
+    InventoryClient::<merfolk_backend_in_process::InProcess>::schema(),
+    Schema::new("Inventory", env!("CARGO_PKG_VERSION"))
+      .method(
+        Method::new("inventory.stock", ParamStructure::ByPosition)
+          .description("Looks up the stock of an item.")
+          .param(ContentDescriptor::new("item", TypeSchema::of("string", "String")))
+          .param(ContentDescriptor::new(
+            "warehouses",
+            TypeSchema::nullable(TypeSchema::array(TypeSchema::of("integer", "u32"), "Vec<u32>"), "Option<Vec<u32>>")
+          ))
+          .result(ContentDescriptor::new(
+            "result",
+            TypeSchema::map(TypeSchema::of("number", "f64"), "std::collections::HashMap<String, f64>")
+          ))
+      )
+      .method(Method::new("inventory.clear", ParamStructure::ByPosition))
+  );
+
+  assert_eq!(
+    serde_json::to_value(NamedClient::<merfolk_backend_in_process::InProcess>::schema().methods).unwrap(),
+    serde_json::json!([{
+      "name": "rename",
+      "params": [
+        { "name": "from", "schema": { "type": "string", "x-rust-type": "String" } },
+        { "name": "to", "schema": { "type": "array", "prefixItems": [{ "type": "boolean", "x-rust-type": "bool" }, { "type": "string", "x-rust-type": "char" }], "x-rust-type": "(bool, char)" } }
+      ],
+      "result": { "name": "result", "schema": { "type": "null", "x-rust-type": "()" } },
+      "paramStructure": "by-name"
+    }])
+  );
+}
+
+#[test]
+fn derive_in_process_notify() {
+  static NOTIFIED: std::sync::atomic::AtomicI32 = std::sync::atomic::AtomicI32::new(0);
+
+  #[merfolk_frontend_derive::frontend()]
+  struct Data {}
+
+  #[merfolk_frontend_derive::frontend(target = "Data")]
+  trait Receiver {
+    #[merfolk_frontend_derive::frontend(notify)]
+    fn record(value: i32) {
+      std::thread::sleep(std::time::Duration::from_millis(200));
+      NOTIFIED.store(value, std::sync::atomic::Ordering::SeqCst);
+    }
+  }
+
+  let (to, from): (
+    tokio::sync::mpsc::Sender<merfolk_backend_in_process::InProcessChannel>,
+    tokio::sync::mpsc::Receiver<merfolk_backend_in_process::InProcessChannel>,
+  ) = tokio::sync::mpsc::channel(1);
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap())
+    .frontend(ReceiverClient::new())
+    .build()
+    .unwrap();
+
+  let _merfolk_register = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+    .frontend(Data::builder().build().unwrap())
+    .build()
+    .unwrap();
+
+  let start = std::time::Instant::now();
+  merfolk_caller.frontend(|f| f.record(42).unwrap()).unwrap();
+  assert!(start.elapsed() < std::time::Duration::from_millis(200));
+  assert_eq!(NOTIFIED.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+  while NOTIFIED.load(std::sync::atomic::Ordering::SeqCst) == 0 {
+    assert!(start.elapsed() < std::time::Duration::from_secs(2));
+    std::thread::sleep(std::time::Duration::from_millis(10));
+  }
+  assert_eq!(NOTIFIED.load(std::sync::atomic::Ordering::SeqCst), 42);
+}
@@ -1,40 +1,356 @@
-use darling::{util::Flag, FromMeta};
+use darling::{
+  util::{Flag, PathList},
+  FromMeta,
+};
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 
-#[derive(Debug, FromMeta)]
+#[derive(Debug, Default, FromMeta)]
+#[darling(default)]
 pub struct Args {
-  #[darling(default)]
   pub target: Option<syn::Path>,
-  #[darling(default)]
   pub definition_only: Flag,
+  pub args: ArgsEncoding,
+  pub namespace: Option<String>,
+  pub name: Option<String>,
+  #[darling(multiple)]
+  pub alias: Vec<String>,
+  pub traits: PathList,
+  pub notify: Flag,
+}
+
+impl Args {
+  /// Parses the `#[frontend(...)]` attribute of a method and removes it from the method.
+  fn take_from(method: &mut syn::TraitItemMethod) -> Result<Option<Self>, syn::Error> {
+    let Some(index) = method.attrs.iter().position(|a| a.path.segments.last().is_some_and(|p| p.ident == "frontend")) else {
+      return Ok(None);
+    };
+    let attr = method.attrs.remove(index);
+
+    Ok(Some(Self::from_meta(&attr.parse_meta()?)?))
+  }
+}
+
+/// How the arguments of a procedure are encoded in the payload.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, FromMeta)]
+pub enum ArgsEncoding {
+  /// The arguments are encoded as tuple.
+  #[default]
+  #[darling(rename = "positional")]
+  Positional,
+  /// The arguments are encoded as map keyed by the parameter names.
+  #[darling(rename = "named")]
+  Named,
+}
+
+/// Removes the `#[serde(...)]` attributes from the parameters of `method` and returns the parameters with their attributes.
+fn take_serde_attributes(method: &mut syn::TraitItemMethod) -> Vec<(syn::PatType, Vec<syn::Attribute>)> {
+  method
+    .sig
+    .inputs
+    .iter_mut()
+    .filter_map(|a| match a {
+      syn::FnArg::Typed(t) => {
+        let (serde, rest) = t.attrs.drain(..).partition(|a| a.path.is_ident("serde"));
+        t.attrs = rest;
+        Some((t.clone(), serde))
+      }
+      syn::FnArg::Receiver(_) => None,
+    })
+    .collect()
+}
+
+/// Returns the name of the marker type of the procedures of `trait_name`.
+fn procedures_marker(trait_name: &syn::Ident) -> syn::Ident {
+  format_ident!("__{}Procedures", trait_name)
+}
+
+/// Returns the error for a [`Call`](merfolk::Call) which does not match any procedure.
+fn unknown_procedure() -> TokenStream {
+  #[cfg(feature = "std")]
+  let error = quote! { Err(::merfolk_frontend_derive::reexports::merfolk::RemoteError::new(::merfolk_frontend_derive::reexports::merfolk::RemoteError::PROCEDURE_NOT_FOUND, merfolk_frontend_derive::Error::UnknownProcedure { procedure: call.procedure }).into()) };
+  #[cfg(not(feature = "std"))]
+  let error = quote! { Err(::merfolk_frontend_derive::reexports::merfolk::RemoteError::new(::merfolk_frontend_derive::reexports::merfolk::RemoteError::PROCEDURE_NOT_FOUND, ::core::format_args!("unknown procedure: {}", call.procedure)).into()) };
+
+  error
+}
+
+/// Returns the `T` and `E` of a `Result<T, E>` return type.
+fn typed_result(output: &syn::ReturnType) -> Option<(&syn::Type, &syn::Type)> {
+  let syn::ReturnType::Type(_, ty) = output else { return None };
+  let syn::Type::Path(path) = &**ty else { return None };
+  let segment = path.path.segments.last().filter(|s| s.ident == "Result")?;
+  let syn::PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+    return None;
+  };
+
+  match arguments.args.iter().collect::<Vec<_>>().as_slice() {
+    [syn::GenericArgument::Type(ok), syn::GenericArgument::Type(err)] => Some((ok, err)),
+    _ => None,
+  }
+}
+
+/// Returns the names of the parameters of `method`.
+fn argument_names(method: &syn::TraitItemMethod) -> Vec<&syn::Ident> {
+  method
+    .sig
+    .inputs
+    .iter()
+    .filter_map(|a| match a {
+      syn::FnArg::Typed(t) => match &*t.pat {
+        syn::Pat::Ident(ident) => Some(&ident.ident),
+        _ => None,
+      },
+      syn::FnArg::Receiver(_) => None,
+    })
+    .collect()
+}
+
+/// Returns the description of `ty` with the whitespace between tokens removed where rustfmt would remove it.
+fn type_description(ty: &syn::Type) -> String {
+  quote! { #ty }
+    .to_string()
+    .replace(" :: ", "::")
+    .replace(":: ", "::")
+    .replace(" < ", "<")
+    .replace("< ", "<")
+    .replace(" <", "<")
+    .replace(" >", ">")
+    .replace(" ,", ",")
+    .replace("& ", "&")
+    .replace("( ", "(")
+    .replace(" )", ")")
+    .replace("[ ", "[")
+    .replace(" ]", "]")
+    .replace(" ;", ";")
+}
+
+/// Returns the generic type arguments of the last segment of `path`.
+fn type_arguments(path: &syn::Path) -> Vec<&syn::Type> {
+  match path.segments.last().map(|s| &s.arguments) {
+    Some(syn::PathArguments::AngleBracketed(arguments)) => arguments
+      .args
+      .iter()
+      .filter_map(|a| match a {
+        syn::GenericArgument::Type(t) => Some(t),
+        _ => None,
+      })
+      .collect(),
+    _ => vec![],
+  }
+}
+
+/// Expands the [`TypeSchema`](merfolk_frontend_derive::schema::TypeSchema) of `ty`.
+///
+/// Types are recognized by the last segment of their path, unknown types are opaque.
+fn type_schema(ty: &syn::Type) -> TokenStream {
+  let rust_type = type_description(ty);
+  let of = |json_type: &str| quote! { ::merfolk_frontend_derive::schema::TypeSchema::of(#json_type, #rust_type) };
+
+  match ty {
+    syn::Type::Reference(reference) => type_schema(&reference.elem),
+    syn::Type::Paren(paren) => type_schema(&paren.elem),
+    syn::Type::Group(group) => type_schema(&group.elem),
+    syn::Type::Tuple(tuple) if tuple.elems.is_empty() => of("null"),
+    syn::Type::Tuple(tuple) => {
+      let items = tuple.elems.iter().map(type_schema);
+      quote! { ::merfolk_frontend_derive::schema::TypeSchema::tuple(vec![#( #items ),*], #rust_type) }
+    }
+    syn::Type::Array(syn::TypeArray { elem, .. }) | syn::Type::Slice(syn::TypeSlice { elem, .. }) => {
+      let items = type_schema(elem);
+      quote! { ::merfolk_frontend_derive::schema::TypeSchema::array(#items, #rust_type) }
+    }
+    syn::Type::Path(path) => {
+      let Some(ident) = path.path.segments.last().map(|s| s.ident.to_string()) else {
+        return quote! { ::merfolk_frontend_derive::schema::TypeSchema::opaque(#rust_type) };
+      };
+      let arguments = type_arguments(&path.path);
+
+      match (ident.as_str(), arguments.as_slice()) {
+        ("i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128" | "usize", _) => of("integer"),
+        ("f32" | "f64", _) => of("number"),
+        ("bool", _) => of("boolean"),
+        ("String" | "str" | "char", _) => of("string"),
+        ("Box" | "Rc" | "Arc" | "Cow", [.., inner]) => type_schema(inner),
+        ("Option", [inner]) => {
+          let value = type_schema(inner);
+          quote! { ::merfolk_frontend_derive::schema::TypeSchema::nullable(#value, #rust_type) }
+        }
+        ("Vec" | "VecDeque" | "LinkedList" | "HashSet" | "BTreeSet", [inner]) => {
+          let items = type_schema(inner);
+          quote! { ::merfolk_frontend_derive::schema::TypeSchema::array(#items, #rust_type) }
+        }
+        ("HashMap" | "BTreeMap", [_, value]) => {
+          let values = type_schema(value);
+          quote! { ::merfolk_frontend_derive::schema::TypeSchema::map(#values, #rust_type) }
+        }
+        _ => quote! { ::merfolk_frontend_derive::schema::TypeSchema::opaque(#rust_type) },
+      }
+    }
+    _ => quote! { ::merfolk_frontend_derive::schema::TypeSchema::opaque(#rust_type) },
+  }
+}
+
+/// Returns the type of the reply payload of `method`.
+fn reply_type(method: &syn::TraitItemMethod) -> Option<&syn::Type> {
+  match (typed_result(&method.sig.output), &method.sig.output) {
+    (Some((ok, _)), _) => Some(ok),
+    (None, syn::ReturnType::Type(_, ty)) => Some(ty),
+    (None, syn::ReturnType::Default) => None,
+  }
+}
+
+/// Returns the joined `#[doc = "..."]` attributes of `method`.
+fn docs(method: &syn::TraitItemMethod) -> Option<String> {
+  let lines: Vec<String> = method
+    .attrs
+    .iter()
+    .filter(|a| a.path.is_ident("doc"))
+    .filter_map(|a| match a.parse_meta() {
+      Ok(syn::Meta::NameValue(syn::MetaNameValue { lit: syn::Lit::Str(doc), .. })) => Some(doc.value().strip_prefix(' ').map(str::to_string).unwrap_or_else(|| doc.value())),
+      _ => None,
+    })
+    .collect();
+
+  match lines.is_empty() {
+    true => None,
+    false => Some(lines.join("\n")),
+  }
+}
+
+/// Renames all occurrences of the lifetime `'from` to `'to`.
+fn rename_lifetime(tokens: TokenStream, from: &str, to: &str) -> TokenStream {
+  let mut renamed = TokenStream::new();
+  let mut tokens = tokens.into_iter().peekable();
+  while let Some(token) = tokens.next() {
+    match token {
+      proc_macro2::TokenTree::Punct(punct) if punct.as_char() == '\'' => match tokens.peek() {
+        Some(proc_macro2::TokenTree::Ident(ident)) if ident == from => {
+          let lifetime = syn::Lifetime::new(&format!("'{}", to), ident.span());
+          tokens.next();
+          renamed.extend(quote! { #lifetime });
+        }
+        _ => renamed.extend(Some(proc_macro2::TokenTree::Punct(punct))),
+      },
+      proc_macro2::TokenTree::Group(group) => {
+        let mut renamed_group = proc_macro2::Group::new(group.delimiter(), rename_lifetime(group.stream(), from, to));
+        renamed_group.set_span(group.span());
+        renamed.extend(Some(proc_macro2::TokenTree::Group(renamed_group)));
+      }
+      token => renamed.extend(Some(token)),
+    }
+  }
+  renamed
+}
+
+/// Returns a `PhantomData` type using all `generics`.
+fn phantom_type(generics: &syn::Generics) -> TokenStream {
+  let phantom: Vec<TokenStream> = generics
+    .params
+    .iter()
+    .filter_map(|p| match p {
+      syn::GenericParam::Type(t) => {
+        let ident = &t.ident;
+        Some(quote! { #ident })
+      }
+      syn::GenericParam::Lifetime(l) => {
+        let lifetime = &l.lifetime;
+        Some(quote! { &#lifetime () })
+      }
+      syn::GenericParam::Const(_) => None,
+    })
+    .collect();
+
+  quote! { ::core::marker::PhantomData<fn() -> (#( #phantom ),*)> }
+}
+
+/// Expands the struct used to encode the arguments of a procedure as map keyed by the parameter names.
+fn expand_arguments_struct(name: &syn::Ident, generics: &syn::Generics, parameters: &[(syn::PatType, Vec<syn::Attribute>)]) -> Result<TokenStream, Vec<syn::Error>> {
+  let mut errors = vec![];
+  let fields: Vec<TokenStream> = parameters
+    .iter()
+    .filter_map(|(parameter, attrs)| match &*parameter.pat {
+      syn::Pat::Ident(ident) => {
+        let ident = &ident.ident;
+        let ty = &parameter.ty;
+        Some(quote! { #( #attrs )* #ident: #ty })
+      }
+      pat => {
+        errors.push(syn::Error::new_spanned(pat, "named arguments require identifier patterns"));
+        None
+      }
+    })
+    .collect();
+  if !errors.is_empty() {
+    return Err(errors);
+  }
+
+  let phantom = phantom_type(generics);
+  // the bounds of the trait are used instead of the bounds inferred by serde. their `for<'de>` lifetimes would shadow the `'de` lifetime of the derived `Deserialize`
+  let where_clause = generics.where_clause.as_ref().map(|w| rename_lifetime(quote! { #w }, "de", "__de"));
+
+  Ok(quote! {
+    #[doc(hidden)]
+    #[allow(non_camel_case_types)]
+    #[derive(::merfolk_frontend_derive::reexports::serde::Serialize, ::merfolk_frontend_derive::reexports::serde::Deserialize)]
+    #[serde(crate = "::merfolk_frontend_derive::reexports::serde", bound = "")]
+    struct #name #generics #where_clause {
+      #( #fields, )*
+      #[serde(skip)]
+      __phantom: #phantom,
+    }
+  })
 }
 
 pub fn expand_trait(args: &Args, input: &syn::ItemTrait) -> Result<TokenStream, Vec<syn::Error>> {
+  let mut input = input.clone();
+  let mut arguments_structs: Vec<TokenStream> = vec![];
+  let mut arguments_struct_names: Vec<(syn::Ident, syn::Ident)> = vec![];
+  let mut method_args: Vec<(syn::Ident, Args)> = vec![];
+  for item in input.items.iter_mut() {
+    if let syn::TraitItem::Method(method) = item {
+      if let Some(method_arg) = Args::take_from(method).map_err(|e| vec![e])? {
+        method_args.push((method.sig.ident.clone(), method_arg));
+      }
+      let parameters = take_serde_attributes(method);
+
+      if args.args == ArgsEncoding::Named {
+        let name = format_ident!("__{}_{}_arguments", input.ident, method.sig.ident);
+        arguments_structs.push(expand_arguments_struct(&name, &input.generics, &parameters)?);
+        arguments_struct_names.push((method.sig.ident.clone(), name));
+      }
+    }
+  }
+  let input = &input;
+
+  let definition_only = |ident: &syn::Ident| method_args.iter().any(|(method, a)| method == ident && a.definition_only.is_present());
+  let notify = |ident: &syn::Ident| method_args.iter().any(|(method, a)| method == ident && a.notify.is_present());
+  let procedure_names = |ident: &syn::Ident| -> (String, Vec<String>) {
+    let method_arg = method_args.iter().find(|(method, _)| method == ident).map(|(_, a)| a);
+    let name = method_arg.and_then(|a| a.name.clone()).unwrap_or_else(|| ident.to_string());
+    let name = match &args.namespace {
+      Some(namespace) => format!("{}.{}", namespace, name),
+      None => name,
+    };
+
+    (name, method_arg.map(|a| a.alias.clone()).unwrap_or_default())
+  };
+
   let trait_name = &input.ident;
+  let trait_name_string = trait_name.to_string();
   let trait_generics = &input.generics;
-  let service_name = args.target.as_ref().unwrap();
+  let service_name = args.target.as_ref();
   let where_clause = &trait_generics.where_clause;
 
+  let (_, trait_ty_generics, _) = trait_generics.split_for_impl();
+  let trait_turbofish = trait_ty_generics.as_turbofish();
+
   let items = &input.items;
   let filtered_items: Vec<&syn::TraitItem> = input
     .items
     .iter()
     .filter(|i| match &i {
-      syn::TraitItem::Method(m) => {
-        if let Some(attr) = m.attrs.iter().find(|a| a.path.segments.last().map_or(false, |p| p.ident == "frontend")) {
-          match Args::from_meta(&if let Ok(meta) = attr.parse_meta() {
-            meta
-          } else {
-            return true;
-          }) {
-            Ok(a) => !a.definition_only.is_present(),
-            Err(_) => true,
-          }
-        } else {
-          true
-        }
-      }
+      syn::TraitItem::Method(m) => !definition_only(&m.sig.ident),
       _ => true,
     })
     .collect();
@@ -54,31 +370,36 @@ pub fn expand_trait(args: &Args, input: &syn::ItemTrait) -> Result<TokenStream,
   impl_generic_def
     .params
     .insert(0, syn::parse_quote! { __B: ::merfolk_frontend_derive::reexports::merfolk::interfaces::Backend });
-  impl_generic_def.params.insert(0, syn::parse_quote! { '__a });
 
   let mut impl_generics = trait_generics.clone();
   impl_generics.params.insert(0, syn::parse_quote! { __B });
-  impl_generics.params.insert(0, syn::parse_quote! { '__a });
 
-  let filtered_item_methods: Vec<&syn::TraitItemMethod> = item_methods
+  let mut bounded_generic_def = impl_generic_def.clone();
+  bounded_generic_def
+    .make_where_clause()
+    .predicates
+    .push(syn::parse_quote! { __B::Intermediate: ::merfolk_frontend_derive::reexports::merfolk::interfaces::Encoded + ::core::fmt::Debug + Send + Sync + 'static });
+  let bounded_where_clause = &bounded_generic_def.where_clause;
+
+  let filtered_item_methods: Vec<&syn::TraitItemMethod> = item_methods.iter().filter(|i| !definition_only(&i.sig.ident)).collect();
+
+  let returning_notifications: Vec<syn::Error> = item_methods
     .iter()
-    .filter(|i| {
-      if let Some(attr) = i.attrs.iter().find(|a| a.path.segments.last().map_or(false, |p| p.ident == "frontend")) {
-        match Args::from_meta(&if let Ok(meta) = attr.parse_meta() {
-          meta
-        } else {
-          return true;
-        }) {
-          Ok(a) => !a.definition_only.is_present(),
-          Err(_) => true,
-        }
-      } else {
-        true
-      }
-    })
+    .filter(|i| notify(&i.sig.ident) && reply_type(i).map(type_description).filter(|r| r != "()").is_some())
+    .map(|i| syn::Error::new_spanned(&i.sig.output, "notifications must return `()`"))
     .collect();
+  if !returning_notifications.is_empty() {
+    return Err(returning_notifications);
+  }
+
+  #[cfg(not(feature = "std"))]
+  if let Some(i) = filtered_item_methods.iter().find(|i| i.sig.asyncness.is_some()) {
+    return Err(vec![syn::Error::new_spanned(i.sig.asyncness, "async procedures require the `std` feature")]);
+  }
 
-  let receiver_impl_items: Vec<TokenStream> = filtered_item_methods
+  let mut descriptions: Vec<TokenStream> = vec![];
+  let mut methods: Vec<TokenStream> = vec![];
+  let receiver_impl_items: Vec<(TokenStream, TokenStream)> = filtered_item_methods
     .iter()
     .map(|i| {
       let item_name = format_ident!("{}", &i.sig.ident);
@@ -97,46 +418,141 @@ pub fn expand_trait(args: &Args, input: &syn::ItemTrait) -> Result<TokenStream,
         .collect();
       let index = (0..arguments.len()).map(syn::Index::from);
 
+      let arguments_struct = arguments_struct_names.iter().find(|(method, _)| method == &i.sig.ident).map(|(_, name)| name);
+      let deser_type = match arguments_struct {
+        Some(name) => quote! { #name #trait_ty_generics },
+        None => quote! { (#( #arguments ),*) },
+      };
+
       let deser = quote! {
-        let deser_payload = __B::deserialize::<(#( #arguments ),*)>(&call.payload)?;
+        let deser_payload = __B::deserialize::<#deser_type>(&call.payload).map_err(|e| ::merfolk_frontend_derive::reexports::merfolk::RemoteError::new(::merfolk_frontend_derive::reexports::merfolk::RemoteError::INVALID_PARAMS, e))?;
       };
 
-      let reply = if arguments.len() != 1 {
+      let invoke = if arguments_struct.is_some() {
+        let names = argument_names(i);
         match has_self {
-          true => quote! {
-            let reply = <Self as #trait_name #impl_generics>::#item_name(self, #( deser_payload.#index),*);
-          },
-          false => quote! {
-            let reply = <Self as #trait_name #impl_generics>::#item_name(#( deser_payload.#index),*);
-          },
+          true => quote! { <Self as #trait_name #impl_generics>::#item_name(self, #( deser_payload.#names),*) },
+          false => quote! { <Self as #trait_name #impl_generics>::#item_name(#( deser_payload.#names),*) },
+        }
+      } else if arguments.len() != 1 {
+        match has_self {
+          true => quote! { <Self as #trait_name #impl_generics>::#item_name(self, #( deser_payload.#index),*) },
+          false => quote! { <Self as #trait_name #impl_generics>::#item_name(#( deser_payload.#index),*) },
         }
       } else {
         match has_self {
-          true => quote! {
-            let reply = <Self as #trait_name #impl_generics>::#item_name(self, deser_payload);
-          },
-          false => quote! {
-            let reply = <Self as #trait_name #impl_generics>::#item_name(deser_payload);
-          },
+          true => quote! { <Self as #trait_name #impl_generics>::#item_name(self, deser_payload) },
+          false => quote! { <Self as #trait_name #impl_generics>::#item_name(deser_payload) },
         }
       };
 
-      let ser = quote! {
-        let ser_reply = __B::serialize(&reply)?;
-        Ok(::merfolk_frontend_derive::reexports::merfolk::Reply { payload: ser_reply })
+      let (reply, reply_async) = match i.sig.asyncness {
+        Some(_) => (
+          quote! { let reply = ::merfolk_frontend_derive::reexports::merfolk::helpers::future::block_on(#invoke); },
+          quote! { let reply = #invoke.await; },
+        ),
+        None => (quote! { let reply = #invoke; }, quote! { let reply = #invoke; }),
       };
 
-      quote! {
-        stringify!(#item_name) => {
-          log::debug!("frontend procedure receiving: {}", stringify!(#item_name));
+      let ser = match typed_result(&i.sig.output) {
+        Some(_) => quote! {
+          match reply {
+            Ok(reply) => {
+              let ser_reply = __B::serialize(&reply)?;
+              Ok(::merfolk_frontend_derive::reexports::merfolk::Reply {
+                payload: ser_reply,
+                metadata: ::core::default::Default::default(),
+              })
+            }
+            Err(err) => Err(::merfolk_frontend_derive::CallError::to_remote::<__B>(&err)),
+          }
+        },
+        None => quote! {
+          let ser_reply = __B::serialize(&reply)?;
+          Ok(::merfolk_frontend_derive::reexports::merfolk::Reply {
+            payload: ser_reply,
+            metadata: ::core::default::Default::default(),
+          })
+        },
+      };
 
-          #deser
-          #reply
-          #ser
+      let (procedure, aliases) = procedure_names(&i.sig.ident);
+
+      let argument_descriptions = i.sig.inputs.iter().filter_map(|a| match a {
+        syn::FnArg::Typed(t) => {
+          let ty = type_description(&t.ty);
+          Some(match &*t.pat {
+            syn::Pat::Ident(ident) => {
+              let name = ident.ident.to_string();
+              quote! { .argument(::merfolk_frontend_derive::reexports::merfolk::ArgumentDescription::new(#ty).name(#name)) }
+            }
+            _ => quote! { .argument(::merfolk_frontend_derive::reexports::merfolk::ArgumentDescription::new(#ty)) },
+          })
         }
-      }
+        syn::FnArg::Receiver(_) => None,
+      });
+      let returns = reply_type(i).map(type_description).filter(|r| r != "()").map(|r| quote! { .returns(#r) });
+      let doc = docs(i);
+      let docs = doc.as_ref().map(|d| quote! { .docs(#d) });
+
+      let param_structure = match args.args {
+        ArgsEncoding::Positional => quote! { ::merfolk_frontend_derive::schema::ParamStructure::ByPosition },
+        ArgsEncoding::Named => quote! { ::merfolk_frontend_derive::schema::ParamStructure::ByName },
+      };
+      let params = i.sig.inputs.iter().enumerate().filter_map(|(index, a)| match a {
+        syn::FnArg::Typed(t) => {
+          let name = match &*t.pat {
+            syn::Pat::Ident(ident) => ident.ident.to_string(),
+            _ => format!("arg{}", index),
+          };
+          let schema = type_schema(&t.ty);
+          Some(quote! { .param(::merfolk_frontend_derive::schema::ContentDescriptor::new(#name, #schema)) })
+        }
+        syn::FnArg::Receiver(_) => None,
+      });
+      let result = reply_type(i).map(|ty| {
+        let schema = type_schema(ty);
+        quote! { .result(::merfolk_frontend_derive::schema::ContentDescriptor::new("result", #schema)) }
+      });
+      let description = doc.map(|d| quote! { .description(#d) });
+
+      methods.push(quote! {
+        ::merfolk_frontend_derive::schema::Method::new(#procedure, #param_structure)
+          #description
+          #( #params )*
+          #result
+      });
+
+      descriptions.push(quote! {
+        ::merfolk_frontend_derive::reexports::merfolk::ProcedureDescription::new(#procedure)
+          #( #argument_descriptions )*
+          #returns
+          #docs
+      });
+
+      (
+        quote! {
+          #procedure #( | #aliases )* => Ok((|| -> ::merfolk_frontend_derive::reexports::anyhow::Result<::merfolk_frontend_derive::reexports::merfolk::Reply<__B::Intermediate>> {
+            log::debug!("frontend procedure receiving: {}", #procedure);
+
+            #deser
+            #reply
+            #ser
+          })())
+        },
+        quote! {
+          #procedure #( | #aliases )* => Ok(async {
+            log::debug!("frontend procedure receiving: {}", #procedure);
+
+            #deser
+            #reply_async
+            #ser
+          }.await)
+        },
+      )
     })
     .collect();
+  let (receiver_impl_items, async_receiver_impl_items): (Vec<TokenStream>, Vec<TokenStream>) = receiver_impl_items.into_iter().unzip();
 
   let caller_impl_items: Vec<TokenStream> = item_methods
     .iter()
@@ -167,64 +583,245 @@ pub fn expand_trait(args: &Args, input: &syn::ItemTrait) -> Result<TokenStream,
       if !has_self {
         signature.insert(0, syn::parse_quote! { &self })
       }
-      let old_return_type: syn::Type = match &i.sig.output {
-        syn::ReturnType::Default => syn::parse_quote! { () },
-        syn::ReturnType::Type(_, t) => syn::parse_quote! { #t },
+      let (procedure, _) = procedure_names(item_name);
+      let metadata = match notify(item_name) {
+        true => quote! {
+          {
+            let mut metadata = ::merfolk_frontend_derive::reexports::merfolk::Metadata::new();
+            metadata.set_notification();
+            metadata
+          }
+        },
+        false => quote! { ::core::default::Default::default() },
+      };
+      let call = quote! {
+        ::merfolk_frontend_derive::reexports::merfolk::Call {
+          procedure: #procedure.to_string(),
+          payload: ser_payload,
+          metadata: #metadata,
+        }
+      };
+      let ser_payload = match arguments_struct_names.iter().find(|(method, _)| method == &i.sig.ident) {
+        Some((_, name)) => quote! {
+          let ser_payload = __B::serialize(&#name #trait_turbofish {
+            #( #arguments, )*
+            __phantom: ::core::marker::PhantomData,
+          })?;
+        },
+        None => quote! {
+          let ser_payload = __B::serialize(&(#( #arguments ),*))?;
+        },
+      };
+      let (asyncness, call) = match i.sig.asyncness {
+        Some(_) => (quote! { async }, quote! { self.__caller.call_async(#call).await }),
+        None => (quote! {}, quote! { self.__caller.call(#call) }),
       };
-      let return_type: syn::ReturnType = syn::parse_quote! { -> ::merfolk_frontend_derive::reexports::anyhow::Result<#old_return_type> };
 
-      quote! {
-        pub fn #item_name(#signature) #return_type {
-          log::debug!("frontend procedure calling: {}", stringify!(#item_name));
+      match typed_result(&i.sig.output) {
+        Some((ok_type, err_type)) => quote! {
+          pub #asyncness fn #item_name(#signature) -> ::core::result::Result<#ok_type, ::merfolk_frontend_derive::CallError<#err_type>> {
+            log::debug!("frontend procedure calling: {}", #procedure);
 
-          let ser_payload = __B::serialize(&(#( #arguments ),*))?;
+            #ser_payload
+
+            let reply = #call.map_err(::merfolk_frontend_derive::CallError::from_call::<__B>)?.payload;
+
+            Ok(__B::deserialize::<#ok_type>(&reply)?)
+          }
+        },
+        None => {
+          let old_return_type: syn::Type = match &i.sig.output {
+            syn::ReturnType::Default => syn::parse_quote! { () },
+            syn::ReturnType::Type(_, t) => syn::parse_quote! { #t },
+          };
+          let return_type: syn::ReturnType = syn::parse_quote! { -> ::merfolk_frontend_derive::reexports::anyhow::Result<#old_return_type> };
+
+          quote! {
+            pub #asyncness fn #item_name(#signature) #return_type {
+              log::debug!("frontend procedure calling: {}", #procedure);
 
-          let reply = self.__call.as_ref().unwrap()(::merfolk_frontend_derive::reexports::merfolk::Call {
-            procedure: stringify!(#item_name).to_string(),
-            payload: ser_payload,
-          })?
-          .payload;
+              #ser_payload
 
-          let deser_reply = __B::deserialize::<#old_return_type>(&reply);
-          deser_reply
+              let reply = #call?.payload;
+
+              let deser_reply = __B::deserialize::<#old_return_type>(&reply);
+              deser_reply
+            }
+          }
         }
       }
     })
     .collect();
 
-  #[cfg(feature = "std")]
-  let error = quote! { Err(merfolk_frontend_derive::Error::UnknownProcedure { procedure: call.procedure }.into()) };
-  #[cfg(not(feature = "std"))]
-  let error = quote! { Err(::merfolk_frontend_derive::reexports::anyhow::anyhow!("unknown procedure: {}", call.procedure)) };
+  let marker = procedures_marker(trait_name);
+
+  let mut async_generic_def = bounded_generic_def.clone();
+  if let Some(service_name) = service_name {
+    async_generic_def.make_where_clause().predicates.push(syn::parse_quote! { #service_name #impl_generics: Sync });
+  }
+  let async_where_clause = &async_generic_def.where_clause;
+
+  let client_name = format_ident!("{}Client", trait_name);
+  let client = expand_client(&input.vis, &client_name, trait_generics, &unknown_procedure());
+
+  let service = service_name.map(|service_name| {
+    quote! {
+      impl #impl_generic_def #trait_name #impl_generics for #service_name #impl_generics #where_clause {
+        #( #filtered_items )*
+      }
+
+      #[doc(hidden)]
+      struct #marker;
+
+      impl #impl_generic_def ::merfolk_frontend_derive::Dispatch<__B, #marker> for #service_name #impl_generics #bounded_where_clause {
+        fn dispatch(&self, call: ::merfolk_frontend_derive::reexports::merfolk::Call<__B::Intermediate>) -> ::merfolk_frontend_derive::Dispatched<__B::Intermediate> {
+          match call.procedure.as_str() {
+            #( #receiver_impl_items, )*
+            _ => Err(call),
+          }
+        }
+
+        fn procedures(&self) -> Vec<::merfolk_frontend_derive::reexports::merfolk::ProcedureDescription> {
+          ::core::iter::IntoIterator::into_iter([#( #descriptions ),*]).collect()
+        }
+      }
+
+      impl #impl_generic_def ::merfolk_frontend_derive::AsyncDispatch<__B, #marker> for #service_name #impl_generics #async_where_clause {
+        fn dispatch_async(&self, call: ::merfolk_frontend_derive::reexports::merfolk::Call<__B::Intermediate>) -> ::merfolk_frontend_derive::reexports::merfolk::helpers::future::BoxFuture<'_, ::merfolk_frontend_derive::Dispatched<__B::Intermediate>> {
+          Box::pin(async move {
+            match call.procedure.as_str() {
+              #( #async_receiver_impl_items, )*
+              _ => Err(call),
+            }
+          })
+        }
+      }
+
+      impl #impl_generic_def #service_name #impl_generics #bounded_where_clause {
+        #( #caller_impl_items )*
+      }
+    }
+  });
 
   Ok(quote! {
     trait #trait_name #impl_generic_def #where_clause {
       #( #filtered_item_methods )*
     }
 
-    impl #impl_generic_def #trait_name #impl_generics for #service_name #impl_generics #where_clause {
-      #( #filtered_items )*
+    #( #arguments_structs )*
+
+    #service
+
+    #client
+
+    impl #impl_generic_def #client_name #impl_generics #bounded_where_clause {
+      #( #caller_impl_items )*
     }
 
-    impl #impl_generic_def #service_name #impl_generics #where_clause {
-        fn __receive(&self, call: ::merfolk_frontend_derive::reexports::merfolk::Call<__B::Intermediate>) -> ::merfolk_frontend_derive::reexports::anyhow::Result<::merfolk_frontend_derive::reexports::merfolk::Reply<__B::Intermediate>> {
-          match call.procedure.as_str() {
-            #( #receiver_impl_items ),*
-            _ => {
-              #error
-            },
-          }
+    impl #impl_generic_def #client_name #impl_generics #where_clause {
+      /// Returns the schema of the procedures of the trait.
+      pub fn schema() -> ::merfolk_frontend_derive::schema::Schema {
+        ::merfolk_frontend_derive::schema::Schema::new(#trait_name_string, ::core::env!("CARGO_PKG_VERSION"))
+          #( .method(#methods) )*
+      }
+    }
+  })
+}
+
+/// Expands the client type of a trait which can only call the procedures of the trait.
+fn expand_client(vis: &syn::Visibility, client_name: &syn::Ident, trait_generics: &syn::Generics, error: &TokenStream) -> TokenStream {
+  let where_clause = &trait_generics.where_clause;
+  let phantom = phantom_type(trait_generics);
+
+  let mut impl_generic_def = trait_generics.clone();
+  impl_generic_def
+    .params
+    .insert(0, syn::parse_quote! { __B: ::merfolk_frontend_derive::reexports::merfolk::interfaces::Backend });
+
+  let mut impl_generics = trait_generics.clone();
+  impl_generics.params.insert(0, syn::parse_quote! { __B });
+
+  let mut bounded_generic_def = impl_generic_def.clone();
+  bounded_generic_def
+    .make_where_clause()
+    .predicates
+    .push(syn::parse_quote! { __B::Intermediate: ::merfolk_frontend_derive::reexports::merfolk::interfaces::Encoded + ::core::fmt::Debug + Send + Sync + 'static });
+  let bounded_where_clause = &bounded_generic_def.where_clause;
+
+  let async_where_clause = async_frontend_where_clause(&impl_generic_def, &quote! { #client_name #impl_generics });
+
+  quote! {
+    /// Client calling the procedures of the trait.
+    #vis struct #client_name #impl_generic_def #where_clause {
+      __caller: ::merfolk_frontend_derive::Caller<__B>,
+      __phantom: #phantom,
+    }
+
+    impl #impl_generic_def #client_name #impl_generics #where_clause {
+      pub fn new() -> Self {
+        Self {
+          __caller: ::core::default::Default::default(),
+          __phantom: ::core::marker::PhantomData,
         }
+      }
     }
 
+    impl #impl_generic_def ::core::default::Default for #client_name #impl_generics #where_clause {
+      fn default() -> Self {
+        Self::new()
+      }
+    }
 
-    impl #impl_generic_def #service_name #impl_generics #where_clause {
-      #( #caller_impl_items )*
+    impl #impl_generic_def ::merfolk_frontend_derive::reexports::merfolk::interfaces::Frontend for #client_name #impl_generics #bounded_where_clause {
+      type Backend = __B;
+
+      fn register<__T>(&mut self, caller: __T) -> ::merfolk_frontend_derive::reexports::anyhow::Result<()>
+      where
+        __T: Fn(::merfolk_frontend_derive::reexports::merfolk::Call<__B::Intermediate>) -> ::merfolk_frontend_derive::reexports::anyhow::Result<::merfolk_frontend_derive::reexports::merfolk::Reply<__B::Intermediate>> + Send + Sync + 'static,
+      {
+        self.__caller.register(caller);
+        Ok(())
+      }
+
+      fn receive(&self, call: ::merfolk_frontend_derive::reexports::merfolk::Call<__B::Intermediate>) -> ::merfolk_frontend_derive::reexports::anyhow::Result<::merfolk_frontend_derive::reexports::merfolk::Reply<__B::Intermediate>> {
+        #error
+      }
     }
-  })
+
+    impl #impl_generic_def ::merfolk_frontend_derive::reexports::merfolk::interfaces::AsyncFrontend for #client_name #impl_generics #async_where_clause {
+      type Backend = __B;
+
+      fn register<__T>(&mut self, caller: __T) -> ::merfolk_frontend_derive::reexports::anyhow::Result<()>
+      where
+        __T: Fn(::merfolk_frontend_derive::reexports::merfolk::Call<<__B as ::merfolk_frontend_derive::reexports::merfolk::interfaces::AsyncBackend>::Intermediate>) -> ::merfolk_frontend_derive::reexports::merfolk::helpers::future::BoxFuture<'static, ::merfolk_frontend_derive::reexports::anyhow::Result<::merfolk_frontend_derive::reexports::merfolk::Reply<<__B as ::merfolk_frontend_derive::reexports::merfolk::interfaces::AsyncBackend>::Intermediate>>> + Send + Sync + 'static,
+      {
+        self.__caller.register_async(caller);
+        Ok(())
+      }
+
+      fn receive(&self, call: ::merfolk_frontend_derive::reexports::merfolk::Call<<__B as ::merfolk_frontend_derive::reexports::merfolk::interfaces::AsyncBackend>::Intermediate>) -> ::merfolk_frontend_derive::reexports::merfolk::helpers::future::BoxFuture<'_, ::merfolk_frontend_derive::reexports::anyhow::Result<::merfolk_frontend_derive::reexports::merfolk::Reply<<__B as ::merfolk_frontend_derive::reexports::merfolk::interfaces::AsyncBackend>::Intermediate>>> {
+        Box::pin(async move { #error })
+      }
+    }
+  }
+}
+
+/// Returns the where clause of an [`AsyncFrontend`](merfolk::interfaces::AsyncFrontend) implementation for `self_type`.
+fn async_frontend_where_clause(impl_generic_def: &syn::Generics, self_type: &TokenStream) -> Option<syn::WhereClause> {
+  let mut async_generic_def = impl_generic_def.clone();
+  let async_where_clause = async_generic_def.make_where_clause();
+  async_where_clause
+    .predicates
+    .push(syn::parse_quote! { <__B as ::merfolk_frontend_derive::reexports::merfolk::interfaces::Backend>::Intermediate: ::merfolk_frontend_derive::reexports::merfolk::interfaces::Encoded + ::core::fmt::Debug + Send + Sync + 'static });
+  async_where_clause
+    .predicates
+    .push(syn::parse_quote! { __B: ::merfolk_frontend_derive::reexports::merfolk::interfaces::AsyncBackend<Intermediate = <__B as ::merfolk_frontend_derive::reexports::merfolk::interfaces::Backend>::Intermediate> });
+  async_where_clause.predicates.push(syn::parse_quote! { #self_type: Send + Sync });
+
+  async_generic_def.where_clause
 }
 
-pub fn expand_struct(input: &syn::ItemStruct) -> Result<TokenStream, Vec<syn::Error>> {
+pub fn expand_struct(args: &Args, input: &syn::ItemStruct) -> Result<TokenStream, Vec<syn::Error>> {
   let struct_name = &input.ident;
   let struct_name_builder = format_ident!("{}Builder", &input.ident);
   let struct_generics = &input.generics;
@@ -242,19 +839,44 @@ pub fn expand_struct(input: &syn::ItemStruct) -> Result<TokenStream, Vec<syn::Er
   impl_generic_def
     .params
     .insert(0, syn::parse_quote! { __B: ::merfolk_frontend_derive::reexports::merfolk::interfaces::Backend });
-  impl_generic_def.params.insert(0, syn::parse_quote! { '__a });
 
   let mut impl_generics = struct_generics.clone();
   impl_generics.params.insert(0, syn::parse_quote! { __B });
-  impl_generics.params.insert(0, syn::parse_quote! { '__a });
+
+  let mut bounded_generic_def = impl_generic_def.clone();
+  bounded_generic_def
+    .make_where_clause()
+    .predicates
+    .push(syn::parse_quote! { __B::Intermediate: ::merfolk_frontend_derive::reexports::merfolk::interfaces::Encoded + ::core::fmt::Debug + Send + Sync + 'static });
+  let bounded_where_clause = &bounded_generic_def.where_clause;
+
+  // without a list of traits the procedures of the only trait with the struct as `target` are inferred
+  let markers: Vec<TokenStream> = if args.traits.is_empty() {
+    vec![quote! { _ }]
+  } else {
+    args
+      .traits
+      .iter()
+      .map(|t| {
+        let mut marker = t.clone();
+        if let Some(last) = marker.segments.last_mut() {
+          last.ident = procedures_marker(&last.ident);
+        }
+        quote! { #marker }
+      })
+      .collect()
+  };
+  let error = unknown_procedure();
+
+  let async_where_clause = async_frontend_where_clause(&impl_generic_def, &quote! { #struct_name #impl_generics });
 
   Ok(quote! {
     #[derive(::merfolk_frontend_derive::reexports::derive_builder::Builder)]
     #[builder(pattern = "owned")]
     #[cfg_attr(not(feature = "std"), builder(no_std))]
     struct #struct_name #impl_generic_def #where_clause {
-      #[builder(private, default = "None")]
-      __call: Option<Box<dyn Fn(::merfolk_frontend_derive::reexports::merfolk::Call<__B::Intermediate>) -> ::merfolk_frontend_derive::reexports::anyhow::Result<::merfolk_frontend_derive::reexports::merfolk::Reply<__B::Intermediate>> + '__a + Send>>,
+      #[builder(private, default = "::core::default::Default::default()")]
+      __caller: ::merfolk_frontend_derive::Caller<__B>,
 
       #fields
     }
@@ -266,21 +888,71 @@ pub fn expand_struct(input: &syn::ItemStruct) -> Result<TokenStream, Vec<syn::Er
       }
     }
 
-    impl #impl_generic_def ::merfolk_frontend_derive::reexports::merfolk::interfaces::Frontend for #struct_name #impl_generics #where_clause {
+    impl #impl_generic_def ::merfolk_frontend_derive::reexports::merfolk::interfaces::Frontend for #struct_name #impl_generics #bounded_where_clause {
       type Backend = __B;
 
       fn register<__T>(&mut self, caller: __T) -> ::merfolk_frontend_derive::reexports::anyhow::Result<()>
       where
-        __T: Fn(::merfolk_frontend_derive::reexports::merfolk::Call<__B::Intermediate>) -> ::merfolk_frontend_derive::reexports::anyhow::Result<::merfolk_frontend_derive::reexports::merfolk::Reply<__B::Intermediate>> + '__a + Send,
+        __T: Fn(::merfolk_frontend_derive::reexports::merfolk::Call<__B::Intermediate>) -> ::merfolk_frontend_derive::reexports::anyhow::Result<::merfolk_frontend_derive::reexports::merfolk::Reply<__B::Intermediate>> + Send + Sync + 'static,
       {
-        self.__call = Some(Box::new(caller));
+        self.__caller.register(caller);
         Ok(())
       }
 
       fn receive(&self, call: ::merfolk_frontend_derive::reexports::merfolk::Call<__B::Intermediate>) -> ::merfolk_frontend_derive::reexports::anyhow::Result<::merfolk_frontend_derive::reexports::merfolk::Reply<__B::Intermediate>> {
         log::debug!("receiving: Call {{ procedure: {:?}, payload: ... }}", &call.procedure);
 
-        self.__receive(call).map_err(::core::convert::Into::into)
+        #(
+          let call = match ::merfolk_frontend_derive::Dispatch::<__B, #markers>::dispatch(self, call) {
+            Ok(reply) => return reply,
+            Err(call) => call,
+          };
+        )*
+
+        #error
+      }
+
+      fn procedures(&self) -> Vec<::merfolk_frontend_derive::reexports::merfolk::ProcedureDescription> {
+        let mut procedures = vec![];
+        #(
+          procedures.extend(::merfolk_frontend_derive::Dispatch::<__B, #markers>::procedures(self));
+        )*
+        procedures
+      }
+    }
+
+    impl #impl_generic_def ::merfolk_frontend_derive::reexports::merfolk::interfaces::AsyncFrontend for #struct_name #impl_generics #async_where_clause {
+      type Backend = __B;
+
+      fn register<__T>(&mut self, caller: __T) -> ::merfolk_frontend_derive::reexports::anyhow::Result<()>
+      where
+        __T: Fn(::merfolk_frontend_derive::reexports::merfolk::Call<<__B as ::merfolk_frontend_derive::reexports::merfolk::interfaces::AsyncBackend>::Intermediate>) -> ::merfolk_frontend_derive::reexports::merfolk::helpers::future::BoxFuture<'static, ::merfolk_frontend_derive::reexports::anyhow::Result<::merfolk_frontend_derive::reexports::merfolk::Reply<<__B as ::merfolk_frontend_derive::reexports::merfolk::interfaces::AsyncBackend>::Intermediate>>> + Send + Sync + 'static,
+      {
+        self.__caller.register_async(caller);
+        Ok(())
+      }
+
+      fn receive(&self, call: ::merfolk_frontend_derive::reexports::merfolk::Call<<__B as ::merfolk_frontend_derive::reexports::merfolk::interfaces::AsyncBackend>::Intermediate>) -> ::merfolk_frontend_derive::reexports::merfolk::helpers::future::BoxFuture<'_, ::merfolk_frontend_derive::reexports::anyhow::Result<::merfolk_frontend_derive::reexports::merfolk::Reply<<__B as ::merfolk_frontend_derive::reexports::merfolk::interfaces::AsyncBackend>::Intermediate>>> {
+        log::debug!("receiving: Call {{ procedure: {:?}, payload: ... }}", &call.procedure);
+
+        Box::pin(async move {
+          #(
+            let call = match ::merfolk_frontend_derive::AsyncDispatch::<__B, #markers>::dispatch_async(self, call).await {
+              Ok(reply) => return reply,
+              Err(call) => call,
+            };
+          )*
+
+          #error
+        })
+      }
+
+      fn procedures(&self) -> Vec<::merfolk_frontend_derive::reexports::merfolk::ProcedureDescription> {
+        let mut procedures = vec![];
+        #(
+          procedures.extend(::merfolk_frontend_derive::Dispatch::<__B, #markers>::procedures(self));
+        )*
+        procedures
       }
     }
   })
@@ -1,7 +1,7 @@
 use darling::FromMeta;
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, AttributeArgs, ItemStruct, ItemTrait};
+use syn::{parse_macro_input, AttributeArgs, Item};
 
 #[macro_use]
 mod frontend;
@@ -26,13 +26,9 @@ pub fn frontend(args: TokenStream, input: TokenStream) -> TokenStream {
     return input;
   }
 
-  if args_parsed.target.is_some() {
-    let input = parse_macro_input!(input as ItemTrait);
-
-    frontend::expand_trait(&args_parsed, &input).unwrap_or_else(to_compile_errors).into()
-  } else {
-    let input = parse_macro_input!(input as ItemStruct);
-
-    frontend::expand_struct(&input).unwrap_or_else(to_compile_errors).into()
+  match parse_macro_input!(input as Item) {
+    Item::Trait(input) => frontend::expand_trait(&args_parsed, &input).unwrap_or_else(to_compile_errors).into(),
+    Item::Struct(input) => frontend::expand_struct(&args_parsed, &input).unwrap_or_else(to_compile_errors).into(),
+    input => syn::Error::new_spanned(input, "expected a trait or a struct").to_compile_error().into(),
   }
 }
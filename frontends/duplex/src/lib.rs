@@ -6,7 +6,7 @@ use anyhow::Result;
 use log::trace;
 use merfolk::{
   interfaces::{Backend, Frontend},
-  Call, Reply,
+  Call, CallStream, ProcedureDescription, Reply, ReplyStream, Upload,
 };
 
 #[derive(derive_builder::Builder)]
@@ -68,4 +68,40 @@ where
 
     self.receiver.receive(call)
   }
+
+  fn register_stream<T>(&mut self, caller: T) -> Result<()>
+  where
+    T: Fn(Call<<Self::Backend as Backend>::Intermediate>) -> Result<ReplyStream<<Self::Backend as Backend>::Intermediate>> + 'static + Send + Sync,
+  {
+    trace!("duplex stream caller");
+
+    self.caller.register_stream(caller)
+  }
+
+  #[allow(clippy::type_complexity)]
+  fn receive_stream(&self, call: Call<<Self::Backend as Backend>::Intermediate>) -> Result<ReplyStream<<Self::Backend as Backend>::Intermediate>> {
+    trace!("receive stream call");
+
+    self.receiver.receive_stream(call)
+  }
+
+  #[allow(clippy::type_complexity)]
+  fn register_upload<T>(&mut self, caller: T) -> Result<()>
+  where
+    T: Fn(Call<<Self::Backend as Backend>::Intermediate>, CallStream<<Self::Backend as Backend>::Intermediate>) -> Result<Reply<<Self::Backend as Backend>::Intermediate>> + 'static + Send + Sync,
+  {
+    trace!("duplex upload caller");
+
+    self.caller.register_upload(caller)
+  }
+
+  fn receive_upload(&self, call: Call<<Self::Backend as Backend>::Intermediate>) -> Result<Upload<<Self::Backend as Backend>::Intermediate>> {
+    trace!("receive upload call");
+
+    self.receiver.receive_upload(call)
+  }
+
+  fn procedures(&self) -> Vec<ProcedureDescription> {
+    self.receiver.procedures()
+  }
 }
@@ -116,9 +116,13 @@ impl<'a, B: Backend> Frontend for Logger<'a, B> {
             Err(err) => B::serialize(&err.to_string()).unwrap(),
           };
 
+          let mut metadata = merfolk::Metadata::default();
+          metadata.set_notification();
+
           caller(Call {
             procedure: record.level().to_string(),
             payload: args,
+            metadata,
           })
           .ok();
         }),
@@ -142,6 +146,9 @@ impl<'a, B: Backend> Frontend for Logger<'a, B> {
       B::deserialize(&call.payload)?,
     );
 
-    Ok(Reply { payload: B::serialize(&())? })
+    Ok(Reply {
+      payload: B::serialize(&())?,
+      metadata: Default::default(),
+    })
   }
 }
@@ -0,0 +1,106 @@
+use std::{fmt, time::Duration};
+
+use anyhow::Result;
+use log::trace;
+use merfolk::{interfaces::Backend, BatchReply, Call, Metadata, BATCH};
+
+use crate::{Error, Register};
+
+/// [`Call`]s sent as a single batch, created with [`Register::batch`].
+pub struct Batch<'r, 'a, B: Backend> {
+  register: &'r Register<'a, B>,
+  calls: Vec<Call<B::Intermediate>>,
+  timeout: Option<Duration>,
+}
+
+impl<'r, 'a, B: Backend> Batch<'r, 'a, B> {
+  pub(crate) fn new(register: &'r Register<'a, B>) -> Self {
+    Self {
+      register,
+      calls: Vec::new(),
+      timeout: None,
+    }
+  }
+
+  /// Adds a call of the `procedure` to the batch.
+  pub fn call<C: serde::Serialize>(mut self, procedure: &str, payload: &C) -> Result<Self> {
+    self.calls.push(Call {
+      procedure: procedure.to_string(),
+      payload: B::serialize(&payload)?,
+      metadata: Metadata::default(),
+    });
+    Ok(self)
+  }
+
+  /// Sets the `timeout` of the whole batch.
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+
+  /// Sends all calls in a single [`Call`] to the reserved procedure [`BATCH`].
+  ///
+  /// Fails only if the batch itself could not be sent, the results of the single calls are returned in the [`BatchReplies`].
+  pub fn send(self) -> Result<BatchReplies<B>> {
+    trace!("call batch");
+
+    let mut metadata = Metadata::default();
+    if let Some(timeout) = self.timeout {
+      metadata.set_timeout(timeout);
+    }
+
+    let procedures: Vec<String> = self.calls.iter().map(|c| c.procedure.clone()).collect();
+    let reply = self.register.call.as_ref().ok_or(Error::CallNotRegistered)?(Call {
+      procedure: BATCH.to_string(),
+      payload: B::serialize(&self.calls)?,
+      metadata,
+    })?;
+
+    let replies: Vec<BatchReply<B::Intermediate>> = B::deserialize(&reply.payload)?;
+    if replies.len() != procedures.len() {
+      return Err(
+        Error::BatchLength {
+          calls: procedures.len(),
+          replies: replies.len(),
+        }
+        .into(),
+      );
+    }
+
+    Ok(BatchReplies {
+      procedures,
+      replies,
+      timeout: self.timeout,
+    })
+  }
+}
+
+/// Replies of a [`Batch`] in the order of its calls.
+pub struct BatchReplies<B: Backend> {
+  procedures: Vec<String>,
+  replies: Vec<BatchReply<B::Intermediate>>,
+  timeout: Option<Duration>,
+}
+
+impl<B: Backend> BatchReplies<B>
+where
+  B::Intermediate: Clone + fmt::Debug + Send + Sync + 'static,
+{
+  /// Returns the number of replies.
+  pub fn len(&self) -> usize {
+    self.replies.len()
+  }
+
+  /// Returns `true` if the batch had no calls.
+  pub fn is_empty(&self) -> bool {
+    self.replies.is_empty()
+  }
+
+  /// Returns the result of the call at `index`.
+  pub fn get<R: for<'de> serde::Deserialize<'de>>(&self, index: usize) -> Result<R> {
+    match self.replies.get(index).ok_or(Error::BatchIndex(index))? {
+      Ok(reply) => B::deserialize(&reply.payload),
+      Err(err) => Err(err.clone().into_error(&self.procedures[index], self.timeout)),
+    }
+  }
+}
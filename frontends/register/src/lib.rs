@@ -1,16 +1,21 @@
 use std::{
   collections::HashMap,
   sync::{Arc, Mutex},
+  time::Duration,
 };
 
 use anyhow::Result;
 use log::trace;
 use merfolk::{
   interfaces::{Backend, Frontend},
-  Call, Reply,
+  ArgumentDescription, Call, CallStream, Metadata, ProcedureDescription, RemoteError, Reply, ReplyStream, Upload,
 };
 use thiserror::Error;
 
+mod batch;
+
+pub use batch::{Batch, BatchReplies};
+
 #[derive(Debug, Error)]
 pub enum Error {
   #[error("backend error: {0}")]
@@ -21,6 +26,10 @@ pub enum Error {
   Lock,
   #[error("call not registered merfolk init()")]
   CallNotRegistered,
+  #[error("batch has no call at index {0}")]
+  BatchIndex(usize),
+  #[error("batch of {calls} calls was answered with {replies} replies")]
+  BatchLength { calls: usize, replies: usize },
 }
 
 #[derive(derive_builder::Builder)]
@@ -30,9 +39,28 @@ pub struct Register<'a, B: Backend> {
   #[builder(setter(name = "procedures_setter"), private, default = "Arc::new(Mutex::new(HashMap::new()))")]
   procedures: Arc<Mutex<HashMap<String, Box<dyn Fn(Call<B::Intermediate>) -> Result<Reply<B::Intermediate>> + 'a>>>>,
 
+  #[allow(clippy::type_complexity)]
+  #[builder(private, default = "Arc::new(Mutex::new(HashMap::new()))")]
+  streams: Arc<Mutex<HashMap<String, Box<dyn Fn(Call<B::Intermediate>) -> Result<ReplyStream<B::Intermediate>> + 'a>>>>,
+
+  #[allow(clippy::type_complexity)]
+  #[builder(private, default = "Arc::new(Mutex::new(HashMap::new()))")]
+  uploads: Arc<Mutex<HashMap<String, Arc<dyn Fn(Call<B::Intermediate>, CallStream<B::Intermediate>) -> Result<Reply<B::Intermediate>> + Send + Sync>>>>,
+
+  #[builder(private, default = "Arc::new(Mutex::new(HashMap::new()))")]
+  descriptions: Arc<Mutex<HashMap<String, ProcedureDescription>>>,
+
   #[allow(clippy::type_complexity)]
   #[builder(private, default = "None")]
   call: Option<Box<dyn Fn(Call<B::Intermediate>) -> Result<Reply<B::Intermediate>> + 'a + Send>>,
+
+  #[allow(clippy::type_complexity)]
+  #[builder(private, default = "None")]
+  call_stream: Option<Box<dyn Fn(Call<B::Intermediate>) -> Result<ReplyStream<B::Intermediate>> + 'a + Send>>,
+
+  #[allow(clippy::type_complexity)]
+  #[builder(private, default = "None")]
+  call_upload: Option<Box<dyn Fn(Call<B::Intermediate>, CallStream<B::Intermediate>) -> Result<Reply<B::Intermediate>> + 'a + Send>>,
 }
 
 impl<'a, B: Backend> RegisterBuilder<'a, B> {
@@ -60,8 +88,11 @@ impl<'a, B: Backend> Register<'a, B> {
     P: Fn(C) -> R + 'a,
   {
     Box::new(move |call: Call<B::Intermediate>| {
-      let reply = procedure(B::deserialize::<C>(&call.payload)?);
-      Ok(Reply { payload: B::serialize::<R>(&reply)? })
+      let reply = procedure(B::deserialize::<C>(&call.payload).map_err(|e| RemoteError::new(RemoteError::INVALID_PARAMS, e))?);
+      Ok(Reply {
+        payload: B::serialize::<R>(&reply)?,
+        metadata: Metadata::default(),
+      })
     })
   }
 
@@ -74,20 +105,233 @@ impl<'a, B: Backend> Register<'a, B> {
     self.procedures.lock().map_err(|_| Error::Lock)?.insert(
       name.to_string(),
       Box::new(move |call: Call<B::Intermediate>| {
-        let reply = procedure(B::deserialize::<C>(&call.payload)?);
-        Ok(Reply { payload: B::serialize::<R>(&reply)? })
+        let reply = procedure(B::deserialize::<C>(&call.payload).map_err(|e| RemoteError::new(RemoteError::INVALID_PARAMS, e))?);
+        Ok(Reply {
+          payload: B::serialize::<R>(&reply)?,
+          metadata: Metadata::default(),
+        })
       }),
     );
+
+    self.describe(
+      ProcedureDescription::new(name)
+        .argument(ArgumentDescription::new(std::any::type_name::<C>()))
+        .returns(std::any::type_name::<R>()),
+    )
+  }
+
+  /// Registers a procedure which streams its reply.
+  ///
+  /// The items returned by the `procedure` are serialized and sent one after another as they are produced.
+  ///
+  /// ```no_run
+  /// # use merfolk_backend_http::Http;
+  /// # use merfolk_frontend_register::Register;
+  /// # let register = Register::<Http>::builder().build().unwrap();
+  /// register.register_stream("count", |to: u32| (0..to).map(|i| i * i)).unwrap();
+  /// ```
+  pub fn register_stream<P, C, R, S>(&self, name: &str, procedure: P) -> Result<()>
+  where
+    P: Fn(C) -> S + 'a,
+    C: for<'de> serde::Deserialize<'de>,
+    R: serde::Serialize,
+    S: IntoIterator<Item = R>,
+    S::IntoIter: Send + 'static,
+  {
+    trace!("register stream procedure");
+
+    self.streams.lock().map_err(|_| Error::Lock)?.insert(
+      name.to_string(),
+      Box::new(move |call: Call<B::Intermediate>| {
+        let items = procedure(B::deserialize::<C>(&call.payload).map_err(|e| RemoteError::new(RemoteError::INVALID_PARAMS, e))?);
+        Ok(ReplyStream::new(items.into_iter().map(|item| B::serialize::<R>(&item))))
+      }),
+    );
+
+    self.describe(
+      ProcedureDescription::new(name)
+        .argument(ArgumentDescription::new(std::any::type_name::<C>()))
+        .returns(std::any::type_name::<ReplyStream<R>>()),
+    )
+  }
+
+  /// Registers a procedure which receives an upload.
+  ///
+  /// The chunks of the upload are deserialized and passed to the `procedure` as they arrive, the client side is blocked while the `procedure` has not consumed the chunks sent before.
+  ///
+  /// ```no_run
+  /// # use merfolk_backend_http::Http;
+  /// # use merfolk_frontend_register::Register;
+  /// # let register = Register::<Http>::builder().build().unwrap();
+  /// register
+  ///   .register_upload("sum", |offset: u64, chunks: merfolk::CallStream<u64>| chunks.fold(offset, |acc, chunk| acc + chunk.unwrap_or(0)))
+  ///   .unwrap();
+  /// ```
+  pub fn register_upload<P, C, K, R>(&self, name: &str, procedure: P) -> Result<()>
+  where
+    B: 'static,
+    P: Fn(C, CallStream<K>) -> R + Send + Sync + 'static,
+    C: for<'de> serde::Deserialize<'de>,
+    K: for<'de> serde::Deserialize<'de> + 'static,
+    R: serde::Serialize,
+  {
+    trace!("register upload procedure");
+
+    self.uploads.lock().map_err(|_| Error::Lock)?.insert(
+      name.to_string(),
+      Arc::new(move |call: Call<B::Intermediate>, chunks: CallStream<B::Intermediate>| {
+        let payload = B::deserialize::<C>(&call.payload).map_err(|e| RemoteError::new(RemoteError::INVALID_PARAMS, e))?;
+        let reply = procedure(payload, CallStream::new(chunks.map(|chunk| B::deserialize::<K>(&chunk?))));
+        Ok(Reply {
+          payload: B::serialize::<R>(&reply)?,
+          metadata: Metadata::default(),
+        })
+      }),
+    );
+
+    self.describe(
+      ProcedureDescription::new(name)
+        .argument(ArgumentDescription::new(std::any::type_name::<C>()))
+        .argument(ArgumentDescription::new(std::any::type_name::<CallStream<K>>()))
+        .returns(std::any::type_name::<R>()),
+    )
+  }
+
+  /// Sets the [`ProcedureDescription`] returned for the procedure with the name of the `description`.
+  ///
+  /// [`register`](Register::register) describes the procedure with the type names of its arguments and return value, this allows to add docs or argument names.
+  pub fn describe(&self, description: ProcedureDescription) -> Result<()> {
+    trace!("describe procedure");
+
+    self.descriptions.lock().map_err(|_| Error::Lock)?.insert(description.name.clone(), description);
     Ok(())
   }
 
   pub fn call<C: serde::Serialize, R: for<'de> serde::Deserialize<'de>>(&self, procedure: &str, payload: &C) -> Result<R> {
     trace!("call procedure");
 
+    self.call_with_metadata(procedure, payload, Metadata::default())
+  }
+
+  /// Calls the `procedure` and fails with [`merfolk::Error::Timeout`] if no reply is received within the `timeout`.
+  pub fn call_with_timeout<C: serde::Serialize, R: for<'de> serde::Deserialize<'de>>(&self, procedure: &str, payload: &C, timeout: Duration) -> Result<R> {
+    trace!("call procedure with timeout");
+
+    let mut metadata = Metadata::default();
+    metadata.set_timeout(timeout);
+
+    self.call_with_metadata(procedure, payload, metadata)
+  }
+
+  /// Calls the `procedure` as notification without waiting for its reply.
+  pub fn notify<C: serde::Serialize>(&self, procedure: &str, payload: &C) -> Result<()> {
+    trace!("notify procedure");
+
+    self.notify_with_metadata(procedure, payload, Metadata::default())
+  }
+
+  /// Calls the `procedure` as notification with the [`Metadata`], e.g. to broadcast it to all nodes a [`Backend`] is connected to.
+  pub fn notify_with_metadata<C: serde::Serialize>(&self, procedure: &str, payload: &C, mut metadata: Metadata) -> Result<()> {
+    trace!("notify procedure with metadata");
+
+    metadata.set_notification();
+
+    self.call.as_ref().ok_or(Error::CallNotRegistered)?(Call {
+      procedure: procedure.to_string(),
+      payload: B::serialize(&payload)?,
+      metadata,
+    })?;
+    Ok(())
+  }
+
+  /// Calls the `procedure` and returns its items as they are received.
+  ///
+  /// ```no_run
+  /// # use merfolk_backend_http::Http;
+  /// # use merfolk_frontend_register::Register;
+  /// # fn main() -> anyhow::Result<()> {
+  /// # let register = Register::<Http>::builder().build()?;
+  /// for square in register.call_stream::<_, u32>("count", &10)? {
+  ///   println!("{}", square?);
+  /// }
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn call_stream<C: serde::Serialize, R: for<'de> serde::Deserialize<'de>>(&self, procedure: &str, payload: &C) -> Result<impl Iterator<Item = Result<R>>> {
+    trace!("call stream procedure");
+
+    let stream = self.call_stream.as_ref().ok_or(Error::CallNotRegistered)?(Call {
+      procedure: procedure.to_string(),
+      payload: B::serialize(&payload)?,
+      metadata: Metadata::default(),
+    })?;
+
+    Ok(stream.map(|item| B::deserialize(&item?)))
+  }
+
+  /// Calls the `procedure` and uploads the `chunks` one after another before receiving its reply.
+  ///
+  /// The chunks are serialized as they are sent, so they can be produced lazily e.g. while reading a file.
+  ///
+  /// ```no_run
+  /// # use merfolk_backend_http::Http;
+  /// # use merfolk_frontend_register::Register;
+  /// # fn main() -> anyhow::Result<()> {
+  /// # let register = Register::<Http>::builder().build()?;
+  /// let sum: u64 = register.upload("sum", &0_u64, 0..1000_u64)?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn upload<C, K, R, S>(&self, procedure: &str, payload: &C, chunks: S) -> Result<R>
+  where
+    B: 'static,
+    C: serde::Serialize,
+    K: serde::Serialize,
+    R: for<'de> serde::Deserialize<'de>,
+    S: IntoIterator<Item = K>,
+    S::IntoIter: Send + 'static,
+  {
+    trace!("upload procedure");
+
+    let reply = self.call_upload.as_ref().ok_or(Error::CallNotRegistered)?(
+      Call {
+        procedure: procedure.to_string(),
+        payload: B::serialize(&payload)?,
+        metadata: Metadata::default(),
+      },
+      CallStream::new(chunks.into_iter().map(|chunk| B::serialize(&chunk))),
+    )?;
+
+    B::deserialize(&reply.payload)
+  }
+
+  /// Creates a [`Batch`] of calls which are sent in a single message.
+  ///
+  /// ```no_run
+  /// # use merfolk_backend_http::Http;
+  /// # use merfolk_frontend_register::Register;
+  /// # fn main() -> anyhow::Result<()> {
+  /// # let register = Register::<Http>::builder().build()?;
+  /// let replies = register.batch().call("add", &(1, 2))?.call("subtract", &(1, 2))?.send()?;
+  ///
+  /// let sum: i32 = replies.get(0)?;
+  /// let difference: i32 = replies.get(1)?;
+  /// # Ok(())
+  /// # }
+  /// ```
+  pub fn batch(&self) -> Batch<'_, 'a, B> {
+    Batch::new(self)
+  }
+
+  /// Calls the `procedure` with the [`Metadata`], e.g. to select the node a [`Backend`] sends the [`Call`] to.
+  pub fn call_with_metadata<C: serde::Serialize, R: for<'de> serde::Deserialize<'de>>(&self, procedure: &str, payload: &C, metadata: Metadata) -> Result<R> {
+    trace!("call procedure with metadata");
+
     B::deserialize(
       &self.call.as_ref().ok_or(Error::CallNotRegistered)?(Call {
         procedure: procedure.to_string(),
         payload: B::serialize(&payload)?,
+        metadata,
       })?
       .payload,
     )
@@ -107,6 +351,27 @@ impl<'a, B: Backend> Frontend for Register<'a, B> {
     Ok(())
   }
 
+  fn register_stream<T>(&mut self, caller: T) -> Result<()>
+  where
+    T: Fn(Call<<Self::Backend as Backend>::Intermediate>) -> Result<ReplyStream<<Self::Backend as Backend>::Intermediate>> + 'a + Send,
+  {
+    trace!("register stream caller");
+
+    self.call_stream = Some(Box::new(caller));
+    Ok(())
+  }
+
+  #[allow(clippy::type_complexity)]
+  fn register_upload<T>(&mut self, caller: T) -> Result<()>
+  where
+    T: Fn(Call<<Self::Backend as Backend>::Intermediate>, CallStream<<Self::Backend as Backend>::Intermediate>) -> Result<Reply<<Self::Backend as Backend>::Intermediate>> + 'a + Send,
+  {
+    trace!("register upload caller");
+
+    self.call_upload = Some(Box::new(caller));
+    Ok(())
+  }
+
   #[allow(clippy::type_complexity)]
   fn receive(&self, call: Call<<Self::Backend as Backend>::Intermediate>) -> Result<Reply<<Self::Backend as Backend>::Intermediate>> {
     trace!("receive call");
@@ -116,6 +381,54 @@ impl<'a, B: Backend> Frontend for Register<'a, B> {
       .lock()
       .map_err(|_| Error::Lock)?
       .get(&call.procedure)
-      .ok_or_else::<anyhow::Error, _>(|| Error::ProcedureNotRegistered(call.procedure.to_owned()).into())?(call)
+      .ok_or_else(|| RemoteError::new(RemoteError::PROCEDURE_NOT_FOUND, Error::ProcedureNotRegistered(call.procedure.to_owned())))?(call)
+  }
+
+  #[allow(clippy::type_complexity)]
+  fn receive_stream(&self, call: Call<<Self::Backend as Backend>::Intermediate>) -> Result<ReplyStream<<Self::Backend as Backend>::Intermediate>> {
+    trace!("receive stream call");
+
+    if let Some(procedure) = self.streams.lock().map_err(|_| Error::Lock)?.get(&call.procedure) {
+      return procedure(call);
+    }
+
+    self.receive(call).map(|reply| ReplyStream::once(Ok(reply.payload)))
+  }
+
+  fn receive_upload(&self, call: Call<<Self::Backend as Backend>::Intermediate>) -> Result<Upload<<Self::Backend as Backend>::Intermediate>> {
+    trace!("receive upload call");
+
+    let procedure = self
+      .uploads
+      .lock()
+      .map_err(|_| Error::Lock)?
+      .get(&call.procedure)
+      .cloned()
+      .ok_or_else(|| RemoteError::new(RemoteError::PROCEDURE_NOT_FOUND, Error::ProcedureNotRegistered(call.procedure.to_owned())))?;
+
+    Ok(Box::new(move |chunks| procedure(call, chunks)))
+  }
+
+  fn procedures(&self) -> Vec<ProcedureDescription> {
+    trace!("describe procedures");
+
+    let (procedures, descriptions) = match (self.procedures.lock(), self.descriptions.lock()) {
+      (Ok(procedures), Ok(descriptions)) => (procedures, descriptions),
+      _ => return Vec::new(),
+    };
+
+    let (streams, uploads) = match (self.streams.lock(), self.uploads.lock()) {
+      (Ok(streams), Ok(uploads)) => (streams, uploads),
+      _ => return Vec::new(),
+    };
+
+    let mut described = procedures
+      .keys()
+      .chain(streams.keys())
+      .chain(uploads.keys())
+      .map(|name| descriptions.get(name).cloned().unwrap_or_else(|| ProcedureDescription::new(name)))
+      .collect::<Vec<_>>();
+    described.sort_by(|a, b| a.name.cmp(&b.name));
+    described
   }
 }
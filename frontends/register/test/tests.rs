@@ -40,6 +40,36 @@ fn register_in_process() {
   assert_eq!(result, a + b);
 }
 
+#[test]
+fn batch_length_register() {
+  use merfolk::interfaces::{Backend, Frontend};
+
+  let mut register = merfolk_frontend_register::Register::<InProcess>::builder().build().unwrap();
+
+  // the batch of two calls is answered with three replies
+  Frontend::register(&mut register, |_| {
+    let replies: Vec<BatchReply<String>> = (0..3)
+      .map(|_| {
+        Ok(Reply {
+          payload: "3".to_string(),
+          metadata: Metadata::default(),
+        })
+      })
+      .collect();
+    Ok(Reply {
+      payload: <InProcess>::serialize(&replies)?,
+      metadata: Metadata::default(),
+    })
+  })
+  .unwrap();
+
+  let err = register.batch().call("add", &(1, 2)).unwrap().call("add", &(1, 2)).unwrap().send().map(|_| ()).unwrap_err();
+  assert!(matches!(
+    err.downcast_ref::<merfolk_frontend_register::Error>(),
+    Some(merfolk_frontend_register::Error::BatchLength { calls: 2, replies: 3 })
+  ));
+}
+
 #[test]
 fn register_http() {
   let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
@@ -67,3 +97,58 @@ fn register_http() {
   let result: i32 = merfolk_caller.frontend(|f| f.call("add", &(a, b)).unwrap()).unwrap();
   assert_eq!(result, a + b);
 }
+
+#[test]
+fn register_in_process_discover() {
+  use tokio::sync::{
+    mpsc,
+    mpsc::{Receiver, Sender},
+  };
+
+  let register_caller = merfolk_frontend_register::Register::builder().build().unwrap();
+  let register_receiver = merfolk_frontend_register::Register::builder()
+    .procedures(vec![("subtract", Register::<InProcess>::make_procedure(|(a, b): (i32, i32)| a - b))].into_iter().collect())
+    .build()
+    .unwrap();
+  register_receiver.register("add", |(a, b)| add(a, b)).unwrap();
+  register_receiver.register_stream("count", |to: u32| (0..to).map(|i| i * i)).unwrap();
+  register_receiver
+    .describe(
+      ProcedureDescription::new("subtract")
+        .argument(ArgumentDescription::new("i32").name("a"))
+        .argument(ArgumentDescription::new("i32").name("b"))
+        .returns("i32")
+        .docs("Subtracts b from a."),
+    )
+    .unwrap();
+
+  let (to, from): (Sender<merfolk_backend_in_process::InProcessChannel>, Receiver<merfolk_backend_in_process::InProcessChannel>) = mpsc::channel(1);
+
+  let merfolk_caller = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().to(to).build().unwrap())
+    .frontend(register_caller)
+    .build()
+    .unwrap();
+
+  let _merfolk_receiver = Mer::builder()
+    .backend(merfolk_backend_in_process::InProcess::builder().from(from).build().unwrap())
+    .frontend(register_receiver)
+    .build()
+    .unwrap();
+
+  let procedures: Vec<ProcedureDescription> = merfolk_caller.frontend(|f| f.call(ProcedureDescription::DISCOVER, &()).unwrap()).unwrap();
+  assert_eq!(
+    procedures,
+    vec![
+      ProcedureDescription::new("add").argument(ArgumentDescription::new("(i32, i32)")).returns("i32"),
+      ProcedureDescription::new("count")
+        .argument(ArgumentDescription::new("u32"))
+        .returns("merfolk::stream::ReplyStream<u32>"),
+      ProcedureDescription::new("subtract")
+        .argument(ArgumentDescription::new("i32").name("a"))
+        .argument(ArgumentDescription::new("i32").name("b"))
+        .returns("i32")
+        .docs("Subtracts b from a."),
+    ]
+  );
+}
@@ -0,0 +1,269 @@
+//! Generates typed TypeScript clients from the [`Schema`] of `#[frontend]` traits.
+//!
+//! The generated client speaks the wire format of the [`Http`](https://docs.rs/merfolk_backend_http) backend: every call is a `POST` with the procedure in the `Procedure` header and the JSON encoded arguments as body.
+//! As `fetch` does not support unencrypted HTTP/2 the [`Http`](https://docs.rs/merfolk_backend_http) backend has to be built with the `http1` feature.
+//!
+//! The `merfolk_generator_typescript` binary reads the JSON serialized [`Schema`] (e.g. `serde_json::to_string(&CalculatorClient::<Http>::schema())`) from a file or stdin and prints the client.
+//!
+//! ```
+//! # use merfolk_frontend_derive::schema::{ContentDescriptor, Method, ParamStructure, Schema, TypeSchema};
+//! let schema = Schema::new("Calculator", "0.1.0").method(
+//!   Method::new("add", ParamStructure::ByPosition)
+//!     .param(ContentDescriptor::new("a", TypeSchema::of("integer", "i32")))
+//!     .param(ContentDescriptor::new("b", TypeSchema::of("integer", "i32")))
+//!     .result(ContentDescriptor::new("result", TypeSchema::of("integer", "i32"))),
+//! );
+//!
+//! let client = merfolk_generator_typescript::generate(&schema);
+//!
+//! assert!(client.contains("export class CalculatorClient {"));
+//! assert!(client.contains("async add(a: number, b: number): Promise<number> {"));
+//! ```
+
+use anyhow::Result;
+use merfolk_frontend_derive::schema::{Method, ParamStructure, Schema, TypeSchema};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+  #[error("could not parse schema: {0}")]
+  ParseSchema(#[from] serde_json::Error),
+}
+
+/// Words which can not be used as parameter names in TypeScript.
+const RESERVED: &[&str] = &[
+  "arguments",
+  "await",
+  "case",
+  "catch",
+  "class",
+  "const",
+  "continue",
+  "debugger",
+  "default",
+  "delete",
+  "do",
+  "else",
+  "enum",
+  "eval",
+  "export",
+  "extends",
+  "false",
+  "finally",
+  "for",
+  "function",
+  "if",
+  "implements",
+  "import",
+  "in",
+  "instanceof",
+  "interface",
+  "let",
+  "new",
+  "null",
+  "package",
+  "private",
+  "protected",
+  "public",
+  "return",
+  "static",
+  "super",
+  "switch",
+  "this",
+  "throw",
+  "true",
+  "try",
+  "typeof",
+  "var",
+  "void",
+  "while",
+  "with",
+  "yield",
+];
+
+/// Code shared by all generated clients.
+const PRELUDE: &str = r#"/** Error returned by the server side of a call. */
+export class RemoteError extends Error {
+  constructor(readonly code: number, message: string, readonly data?: unknown) {
+    super(message);
+    this.name = "RemoteError";
+  }
+}
+
+export interface ClientOptions {
+  /** Headers sent with every call, e.g. `metadata-*` headers. */
+  headers?: Record<string, string>;
+  /** Implementation of `fetch`, defaults to the global `fetch`. */
+  fetch?: typeof fetch;
+}
+"#;
+
+/// Replaces all characters which are not allowed in TypeScript identifiers.
+fn identifier(name: &str) -> String {
+  let identifier: String = name.chars().map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '$' { c } else { '_' }).collect();
+
+  match identifier.chars().next() {
+    Some(c) if c.is_ascii_digit() => format!("_{}", identifier),
+    None => "_".to_string(),
+    _ => identifier,
+  }
+}
+
+/// Returns the name of the parameter `name` which does not collide with reserved words.
+fn parameter(name: &str) -> String {
+  let parameter = identifier(name);
+  match RESERVED.contains(&parameter.as_str()) {
+    true => format!("{}_", parameter),
+    false => parameter,
+  }
+}
+
+/// Returns the TypeScript type of the `schema`.
+fn ts_type(schema: &TypeSchema) -> String {
+  if !schema.one_of.is_empty() {
+    return schema.one_of.iter().map(ts_type).collect::<Vec<_>>().join(" | ");
+  }
+
+  match schema.ty.as_deref() {
+    Some("integer" | "number") => "number".to_string(),
+    Some("string") => "string".to_string(),
+    Some("boolean") => "boolean".to_string(),
+    Some("null") => "null".to_string(),
+    Some("array") if !schema.prefix_items.is_empty() => format!("[{}]", schema.prefix_items.iter().map(ts_type).collect::<Vec<_>>().join(", ")),
+    Some("array") => match &schema.items {
+      Some(items) if items.one_of.is_empty() => format!("{}[]", ts_type(items)),
+      Some(items) => format!("({})[]", ts_type(items)),
+      None => "unknown[]".to_string(),
+    },
+    Some("object") => format!("Record<string, {}>", schema.additional_properties.as_deref().map(ts_type).unwrap_or_else(|| "unknown".to_string())),
+    _ => format!("unknown /* {} */", schema.rust_type),
+  }
+}
+
+/// Returns the TypeScript type of the result of the `method`.
+fn result_type(method: &Method) -> String {
+  match (method.result.schema.ty.as_deref(), method.result.schema.one_of.is_empty()) {
+    (Some("null"), true) => "void".to_string(),
+    _ => ts_type(&method.result.schema),
+  }
+}
+
+/// Returns the names of the client methods of the `methods`.
+///
+/// The namespace of the procedures is stripped if the names stay unique.
+fn method_names(methods: &[Method]) -> Vec<String> {
+  let short: Vec<String> = methods.iter().map(|m| identifier(m.name.rsplit('.').next().unwrap_or(&m.name))).collect();
+
+  methods
+    .iter()
+    .zip(short.iter())
+    .map(|(method, name)| match short.iter().filter(|n| *n == name).count() {
+      1 => name.clone(),
+      _ => identifier(&method.name),
+    })
+    .collect()
+}
+
+/// Expands the client method calling `method`.
+fn expand_method(name: &str, method: &Method) -> String {
+  let mut lines = vec![];
+
+  if let Some(description) = &method.description {
+    lines.push("  /**".to_string());
+    lines.extend(description.lines().map(|l| format!("   * {}", l).trim_end().to_string()));
+    lines.push("   */".to_string());
+  }
+
+  let parameters: Vec<(&str, String)> = method.params.iter().map(|p| (p.name.as_str(), parameter(&p.name))).collect();
+  let signature = method
+    .params
+    .iter()
+    .zip(parameters.iter())
+    .map(|(p, (_, ident))| format!("{}: {}", ident, ts_type(&p.schema)))
+    .collect::<Vec<_>>()
+    .join(", ");
+
+  // the arguments are encoded like the tuple or the arguments struct of the derived frontend
+  let payload = match (method.param_structure, parameters.as_slice()) {
+    (ParamStructure::ByPosition, []) => "null".to_string(),
+    (ParamStructure::ByPosition, [(_, ident)]) => ident.clone(),
+    (ParamStructure::ByPosition, parameters) => format!("[{}]", parameters.iter().map(|(_, ident)| ident.as_str()).collect::<Vec<_>>().join(", ")),
+    (ParamStructure::ByName, []) => "{}".to_string(),
+    (ParamStructure::ByName, parameters) => format!(
+      "{{ {} }}",
+      parameters
+        .iter()
+        .map(|(name, ident)| match name == ident {
+          true => ident.clone(),
+          false => format!("{}: {}", serde_json::to_string(name).unwrap_or_default(), ident),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+    ),
+  };
+
+  lines.push(format!("  async {}({}): Promise<{}> {{", name, signature, result_type(method)));
+  lines.push(format!("    return this.call({}, {});", serde_json::to_string(&method.name).unwrap_or_default(), payload));
+  lines.push("  }".to_string());
+
+  lines.join("\n")
+}
+
+/// Generates the TypeScript client of the `schema`.
+pub fn generate(schema: &Schema) -> String {
+  let class = format!("{}Client", identifier(&schema.info.title));
+  let methods: Vec<String> = method_names(&schema.methods)
+    .iter()
+    .zip(schema.methods.iter())
+    .map(|(name, method)| expand_method(name, method))
+    .collect();
+
+  let mut lines = vec![
+    format!(
+      "// Generated by merfolk_generator_typescript from the schema of {} {}. Do not edit.",
+      schema.info.title, schema.info.version
+    ),
+    String::new(),
+    PRELUDE.to_string(),
+    format!("/** Client calling the procedures of `{}` via the merfolk Http backend. */", schema.info.title),
+    format!("export class {} {{", class),
+    "  constructor(private readonly url: string, private readonly options: ClientOptions = {}) {}".to_string(),
+    String::new(),
+    r#"  private async call<T>(procedure: string, payload: unknown): Promise<T> {
+    const response = await (this.options.fetch ?? fetch)(this.url, {
+      method: "POST",
+      headers: { ...this.options.headers, "Content-Type": "application/json", Procedure: procedure },
+      body: JSON.stringify(payload),
+    });
+    const body = await response.text();
+
+    if (response.status !== 200) {
+      let error: { code: number; message: string; data?: unknown };
+      try {
+        error = JSON.parse(body);
+      } catch {
+        throw new Error(`request failed with statuscode ${response.status}`);
+      }
+      throw new RemoteError(error.code, error.message, error.data);
+    }
+
+    return JSON.parse(body) as T;
+  }"#
+      .to_string(),
+  ];
+
+  for method in methods {
+    lines.push(String::new());
+    lines.push(method);
+  }
+  lines.push("}".to_string());
+  lines.push(String::new());
+
+  lines.join("\n")
+}
+
+/// Generates the TypeScript client of the JSON serialized [`Schema`].
+pub fn generate_from_json(schema: &str) -> Result<String> {
+  let schema: Schema = serde_json::from_str(schema).map_err(Error::ParseSchema)?;
+  Ok(generate(&schema))
+}
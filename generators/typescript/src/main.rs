@@ -0,0 +1,20 @@
+//! Reads the JSON serialized schema of a `#[frontend]` trait from the file given as first argument (or stdin) and prints the TypeScript client.
+
+use std::{
+  env, fs,
+  io::{self, Read},
+};
+
+fn main() -> anyhow::Result<()> {
+  let schema = match env::args().nth(1) {
+    Some(path) if path != "-" => fs::read_to_string(path)?,
+    _ => {
+      let mut schema = String::new();
+      io::stdin().read_to_string(&mut schema)?;
+      schema
+    }
+  };
+
+  print!("{}", merfolk_generator_typescript::generate_from_json(&schema)?);
+  Ok(())
+}
@@ -0,0 +1,77 @@
+use merfolk_frontend_derive::schema::{ContentDescriptor, Method, ParamStructure, Schema, TypeSchema};
+
+#[test]
+fn generate_positional() {
+  let schema = Schema::new("Inventory", "0.1.0")
+    .method(
+      Method::new("inventory.stock", ParamStructure::ByPosition)
+        .description("Looks up the stock of an item.\n\nOverflows are not checked.")
+        .param(ContentDescriptor::new("item", TypeSchema::of("string", "String")))
+        .param(ContentDescriptor::new(
+          "in",
+          TypeSchema::nullable(TypeSchema::array(TypeSchema::of("integer", "u32"), "Vec<u32>"), "Option<Vec<u32>>"),
+        ))
+        .result(ContentDescriptor::new("result", TypeSchema::map(TypeSchema::of("number", "f64"), "HashMap<String, f64>"))),
+    )
+    .method(
+      Method::new("inventory.find", ParamStructure::ByPosition)
+        .param(ContentDescriptor::new("item", TypeSchema::opaque("Item")))
+        .result(ContentDescriptor::new(
+          "result",
+          TypeSchema::tuple(vec![TypeSchema::of("boolean", "bool"), TypeSchema::opaque("Item")], "(bool, Item)"),
+        )),
+    )
+    .method(Method::new("inventory.clear", ParamStructure::ByPosition))
+    .method(Method::new("billing.clear", ParamStructure::ByPosition));
+
+  let client = merfolk_generator_typescript::generate(&schema);
+
+  assert!(client.contains("export class InventoryClient {"));
+  assert!(client.contains(
+    "  /**
+   * Looks up the stock of an item.
+   *
+   * Overflows are not checked.
+   */
+  async stock(item: string, in_: number[] | null): Promise<Record<string, number>> {
+    return this.call(\"inventory.stock\", [item, in_]);
+  }"
+  ));
+  assert!(client.contains(
+    "  async find(item: unknown /* Item */): Promise<[boolean, unknown /* Item */]> {
+    return this.call(\"inventory.find\", item);
+  }"
+  ));
+  assert!(client.contains(
+    "  async inventory_clear(): Promise<void> {
+    return this.call(\"inventory.clear\", null);
+  }"
+  ));
+  assert!(client.contains("  async billing_clear(): Promise<void> {"));
+}
+
+#[test]
+fn generate_named() {
+  let schema = serde_json::json!({
+    "openrpc": "1.2.6",
+    "info": { "title": "Named", "version": "0.1.0" },
+    "methods": [{
+      "name": "rename",
+      "params": [
+        { "name": "from", "schema": { "type": "string", "x-rust-type": "String" } },
+        { "name": "default", "schema": { "type": "array", "items": { "oneOf": [{ "type": "boolean", "x-rust-type": "bool" }, { "type": "null", "x-rust-type": "()" }], "x-rust-type": "Option<bool>" }, "x-rust-type": "Vec<Option<bool>>" } }
+      ],
+      "result": { "name": "result", "schema": { "type": "null", "x-rust-type": "()" } },
+      "paramStructure": "by-name"
+    }]
+  });
+
+  let client = merfolk_generator_typescript::generate_from_json(&schema.to_string()).unwrap();
+
+  assert!(client.contains(
+    "  async rename(from: string, default_: (boolean | null)[]): Promise<void> {
+    return this.call(\"rename\", { from, \"default\": default_ });
+  }"
+  ));
+  assert!(merfolk_generator_typescript::generate_from_json("{}").is_err());
+}
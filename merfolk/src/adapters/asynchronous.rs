@@ -0,0 +1,167 @@
+use alloc::{boxed::Box, vec::Vec};
+
+use anyhow::Result;
+
+use crate::{
+  access,
+  helpers::{future::block_on, future::BoxFuture, smart_lock::SmartLock},
+  interfaces::{AsyncBackend, AsyncFrontend, AsyncMiddleware, Backend, Frontend, Middleware},
+  smart_lock, Call, CallStream, Error, ProcedureDescription, Reply, ReplyStream,
+};
+
+/// Wraps a synchronous implementation to be used as its asynchronous counterpart.
+///
+/// The returned futures are resolved by calling the synchronous implementation on the thread polling them.
+///
+/// ```
+/// # use merfolk::adapters::Async;
+/// # use merfolk_backend_in_process::InProcess;
+/// # use merfolk_frontend_register::Register;
+/// let frontend = Async::new(Register::<InProcess>::builder().build().unwrap());
+///
+/// frontend.inner(|f| f.register("add", |(a, b): (i32, i32)| a + b)).unwrap().unwrap();
+/// ```
+pub struct Async<T>(SmartLock<T>);
+
+impl<T> Async<T> {
+  /// Creates a new [`Async`] adapter wrapping `inner`.
+  pub fn new(inner: T) -> Self {
+    Self(smart_lock!(inner))
+  }
+
+  /// Allows accessing the wrapped implementation.
+  pub fn inner<A, R>(&self, access: A) -> Result<R, Error>
+  where
+    A: FnOnce(&mut T) -> R,
+  {
+    Ok(access(&mut *access!(self.0).map_err(|_| Error::Lock)?))
+  }
+}
+
+impl<B: Backend> Backend for Async<B> {
+  type Intermediate = B::Intermediate;
+
+  fn register<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<Self::Intermediate>) -> Result<Reply<Self::Intermediate>> + Send + Sync + 'static,
+  {
+    access!(self.0).map_err(|_| Error::Lock)?.register(receiver)
+  }
+
+  fn call(&mut self, call: Call<Self::Intermediate>) -> Result<Reply<Self::Intermediate>> {
+    access!(self.0).map_err(|_| Error::Lock)?.call(call)
+  }
+
+  fn register_stream<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<Self::Intermediate>) -> Result<ReplyStream<Self::Intermediate>> + Send + Sync + 'static,
+  {
+    access!(self.0).map_err(|_| Error::Lock)?.register_stream(receiver)
+  }
+
+  fn call_stream(&mut self, call: Call<Self::Intermediate>) -> Result<ReplyStream<Self::Intermediate>> {
+    access!(self.0).map_err(|_| Error::Lock)?.call_stream(call)
+  }
+
+  fn register_upload<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<Self::Intermediate>, CallStream<Self::Intermediate>) -> Result<Reply<Self::Intermediate>> + Send + Sync + 'static,
+  {
+    access!(self.0).map_err(|_| Error::Lock)?.register_upload(receiver)
+  }
+
+  fn call_upload(&mut self, call: Call<Self::Intermediate>, chunks: CallStream<Self::Intermediate>) -> Result<Reply<Self::Intermediate>> {
+    access!(self.0).map_err(|_| Error::Lock)?.call_upload(call, chunks)
+  }
+
+  fn serialize<S: serde::Serialize>(from: &S) -> Result<Self::Intermediate> {
+    B::serialize(from)
+  }
+
+  fn deserialize<D>(from: &Self::Intermediate) -> Result<D>
+  where
+    D: for<'de> serde::Deserialize<'de>,
+  {
+    B::deserialize(from)
+  }
+}
+
+impl<B: Backend> AsyncBackend for Async<B>
+where
+  B::Intermediate: Send + 'static,
+{
+  type Intermediate = B::Intermediate;
+
+  fn register<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<<Self as AsyncBackend>::Intermediate>) -> BoxFuture<'static, Result<Reply<<Self as AsyncBackend>::Intermediate>>> + Send + Sync + 'static,
+  {
+    access!(self.0).map_err(|_| Error::Lock)?.register(move |call| block_on(receiver(call)))
+  }
+
+  fn call(&self, call: Call<<Self as AsyncBackend>::Intermediate>) -> BoxFuture<'_, Result<Reply<<Self as AsyncBackend>::Intermediate>>> {
+    Box::pin(async move { access!(self.0).map_err(|_| Error::Lock)?.call(call) })
+  }
+
+  fn serialize<S: serde::Serialize>(from: &S) -> Result<<Self as AsyncBackend>::Intermediate> {
+    B::serialize(from)
+  }
+
+  fn deserialize<D>(from: &<Self as AsyncBackend>::Intermediate) -> Result<D>
+  where
+    D: for<'de> serde::Deserialize<'de>,
+  {
+    B::deserialize(from)
+  }
+}
+
+impl<F: Frontend> AsyncFrontend for Async<F>
+where
+  F::Backend: AsyncBackend<Intermediate = <F::Backend as Backend>::Intermediate>,
+  <F::Backend as Backend>::Intermediate: Send,
+{
+  type Backend = F::Backend;
+
+  fn register<T>(&mut self, caller: T) -> Result<()>
+  where
+    T: Fn(Call<<Self::Backend as AsyncBackend>::Intermediate>) -> BoxFuture<'static, Result<Reply<<Self::Backend as AsyncBackend>::Intermediate>>> + Send + Sync + 'static,
+  {
+    access!(self.0).map_err(|_| Error::Lock)?.register(move |call| block_on(caller(call)))
+  }
+
+  fn receive(&self, call: Call<<Self::Backend as AsyncBackend>::Intermediate>) -> BoxFuture<'_, Result<Reply<<Self::Backend as AsyncBackend>::Intermediate>>> {
+    Box::pin(async move { access!(self.0).map_err(|_| Error::Lock)?.receive(call) })
+  }
+
+  fn procedures(&self) -> Vec<ProcedureDescription> {
+    access!(self.0).map(|f| f.procedures()).unwrap_or_default()
+  }
+}
+
+impl<M: Middleware + 'static> AsyncMiddleware for Async<M>
+where
+  M::Backend: AsyncBackend<Intermediate = <M::Backend as Backend>::Intermediate>,
+  <M::Backend as Backend>::Intermediate: Send,
+{
+  type Backend = M::Backend;
+
+  fn wrap_call(&self, call: Result<Call<<Self::Backend as AsyncBackend>::Intermediate>>) -> BoxFuture<'_, Result<Call<<Self::Backend as AsyncBackend>::Intermediate>>> {
+    Box::pin(async move { access!(self.0).map_err(|_| Error::Lock)?.wrap_call(call) })
+  }
+
+  fn wrap_reply(&self, reply: Result<Reply<<Self::Backend as AsyncBackend>::Intermediate>>) -> BoxFuture<'_, Result<Reply<<Self::Backend as AsyncBackend>::Intermediate>>> {
+    Box::pin(async move { access!(self.0).map_err(|_| Error::Lock)?.wrap_reply(reply) })
+  }
+
+  fn unwrap_call(&self, call: Result<Call<<Self::Backend as AsyncBackend>::Intermediate>>) -> BoxFuture<'_, Result<Call<<Self::Backend as AsyncBackend>::Intermediate>>> {
+    Box::pin(async move { access!(self.0).map_err(|_| Error::Lock)?.unwrap_call(call) })
+  }
+
+  fn unwrap_reply(&self, reply: Result<Reply<<Self::Backend as AsyncBackend>::Intermediate>>) -> BoxFuture<'_, Result<Reply<<Self::Backend as AsyncBackend>::Intermediate>>> {
+    Box::pin(async move { access!(self.0).map_err(|_| Error::Lock)?.unwrap_reply(reply) })
+  }
+
+  fn as_any(&self) -> &dyn core::any::Any {
+    self
+  }
+}
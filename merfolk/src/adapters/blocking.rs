@@ -0,0 +1,138 @@
+use alloc::{boxed::Box, vec::Vec};
+
+use anyhow::Result;
+
+use crate::{
+  helpers::future::{block_on, BoxFuture},
+  interfaces::{AsyncBackend, AsyncFrontend, AsyncMiddleware, Backend, Frontend, Middleware},
+  Call, ProcedureDescription, Reply,
+};
+
+/// Wraps an asynchronous implementation to be used as its synchronous counterpart.
+///
+/// Calls block the current thread using [`block_on`] until the futures returned by the asynchronous implementation are resolved.
+pub struct Blocking<T>(T);
+
+impl<T> Blocking<T> {
+  /// Creates a new [`Blocking`] adapter wrapping `inner`.
+  pub fn new(inner: T) -> Self {
+    Self(inner)
+  }
+
+  /// Returns a reference to the wrapped implementation.
+  pub fn inner(&self) -> &T {
+    &self.0
+  }
+
+  /// Returns a mutable reference to the wrapped implementation.
+  pub fn inner_mut(&mut self) -> &mut T {
+    &mut self.0
+  }
+}
+
+impl<B: AsyncBackend> Backend for Blocking<B> {
+  type Intermediate = <B as AsyncBackend>::Intermediate;
+
+  fn register<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<<Self as Backend>::Intermediate>) -> Result<Reply<<Self as Backend>::Intermediate>> + Send + Sync + 'static,
+  {
+    AsyncBackend::register(&mut self.0, move |call| {
+      let reply = receiver(call);
+      Box::pin(async move { reply })
+    })
+  }
+
+  fn call(&mut self, call: Call<<Self as Backend>::Intermediate>) -> Result<Reply<<Self as Backend>::Intermediate>> {
+    block_on(AsyncBackend::call(&self.0, call))
+  }
+
+  fn serialize<S: serde::Serialize>(from: &S) -> Result<<Self as Backend>::Intermediate> {
+    <B as AsyncBackend>::serialize(from)
+  }
+
+  fn deserialize<D>(from: &<Self as Backend>::Intermediate) -> Result<D>
+  where
+    D: for<'de> serde::Deserialize<'de>,
+  {
+    <B as AsyncBackend>::deserialize(from)
+  }
+}
+
+impl<B: AsyncBackend> AsyncBackend for Blocking<B> {
+  type Intermediate = <B as AsyncBackend>::Intermediate;
+
+  fn register<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(Call<<Self as AsyncBackend>::Intermediate>) -> BoxFuture<'static, Result<Reply<<Self as AsyncBackend>::Intermediate>>> + Send + Sync + 'static,
+  {
+    AsyncBackend::register(&mut self.0, receiver)
+  }
+
+  fn call(&self, call: Call<<Self as AsyncBackend>::Intermediate>) -> BoxFuture<'_, Result<Reply<<Self as AsyncBackend>::Intermediate>>> {
+    AsyncBackend::call(&self.0, call)
+  }
+
+  fn serialize<S: serde::Serialize>(from: &S) -> Result<<Self as AsyncBackend>::Intermediate> {
+    <B as AsyncBackend>::serialize(from)
+  }
+
+  fn deserialize<D>(from: &<Self as AsyncBackend>::Intermediate) -> Result<D>
+  where
+    D: for<'de> serde::Deserialize<'de>,
+  {
+    <B as AsyncBackend>::deserialize(from)
+  }
+}
+
+impl<F: AsyncFrontend> Frontend for Blocking<F>
+where
+  F::Backend: Backend<Intermediate = <F::Backend as AsyncBackend>::Intermediate>,
+{
+  type Backend = F::Backend;
+
+  fn register<T>(&mut self, caller: T) -> Result<()>
+  where
+    T: Fn(Call<<Self::Backend as Backend>::Intermediate>) -> Result<Reply<<Self::Backend as Backend>::Intermediate>> + Send + Sync + 'static,
+  {
+    AsyncFrontend::register(&mut self.0, move |call| {
+      let reply = caller(call);
+      Box::pin(async move { reply })
+    })
+  }
+
+  fn receive(&self, call: Call<<Self::Backend as Backend>::Intermediate>) -> Result<Reply<<Self::Backend as Backend>::Intermediate>> {
+    block_on(AsyncFrontend::receive(&self.0, call))
+  }
+
+  fn procedures(&self) -> Vec<ProcedureDescription> {
+    AsyncFrontend::procedures(&self.0)
+  }
+}
+
+impl<M: AsyncMiddleware + 'static> Middleware for Blocking<M>
+where
+  M::Backend: Backend<Intermediate = <M::Backend as AsyncBackend>::Intermediate>,
+{
+  type Backend = M::Backend;
+
+  fn wrap_call(&self, call: Result<Call<<Self::Backend as Backend>::Intermediate>>) -> Result<Call<<Self::Backend as Backend>::Intermediate>> {
+    block_on(self.0.wrap_call(call))
+  }
+
+  fn wrap_reply(&self, reply: Result<Reply<<Self::Backend as Backend>::Intermediate>>) -> Result<Reply<<Self::Backend as Backend>::Intermediate>> {
+    block_on(self.0.wrap_reply(reply))
+  }
+
+  fn unwrap_call(&self, call: Result<Call<<Self::Backend as Backend>::Intermediate>>) -> Result<Call<<Self::Backend as Backend>::Intermediate>> {
+    block_on(self.0.unwrap_call(call))
+  }
+
+  fn unwrap_reply(&self, reply: Result<Reply<<Self::Backend as Backend>::Intermediate>>) -> Result<Reply<<Self::Backend as Backend>::Intermediate>> {
+    block_on(self.0.unwrap_reply(reply))
+  }
+
+  fn as_any(&mut self) -> &mut dyn core::any::Any {
+    self
+  }
+}
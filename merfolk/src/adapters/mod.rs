@@ -0,0 +1,15 @@
+//! Adapters bridging synchronous and asynchronous implementations.
+//!
+//! * [`Async`] wraps a [`Backend`](crate::interfaces::Backend), [`Frontend`](crate::interfaces::Frontend) or [`Middleware`](crate::interfaces::Middleware)
+//!   so it can be used as [`AsyncBackend`](crate::interfaces::AsyncBackend), [`AsyncFrontend`](crate::interfaces::AsyncFrontend) or [`AsyncMiddleware`](crate::interfaces::AsyncMiddleware).
+//! * [`Blocking`] wraps an [`AsyncBackend`](crate::interfaces::AsyncBackend), [`AsyncFrontend`](crate::interfaces::AsyncFrontend) or [`AsyncMiddleware`](crate::interfaces::AsyncMiddleware)
+//!   so it can be used as [`Backend`](crate::interfaces::Backend), [`Frontend`](crate::interfaces::Frontend) or [`Middleware`](crate::interfaces::Middleware).
+//!
+//! Both adapters block the current thread using [`block_on`](crate::helpers::future::block_on) where a synchronous function has to wait for a [`Future`](core::future::Future).
+//! When used from within an async runtime the thread must be allowed to block (e.g. a multi threaded [`tokio`](https://docs.rs/tokio) runtime).
+
+mod asynchronous;
+mod blocking;
+
+pub use asynchronous::Async;
+pub use blocking::Blocking;
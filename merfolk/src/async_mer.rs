@@ -0,0 +1,242 @@
+use alloc::sync::Arc;
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::{fmt, time::Duration};
+
+use anyhow::Result;
+use log::trace;
+
+use crate::{
+  batch::{self, BATCH},
+  interfaces::{AsyncBackend, AsyncFrontend, AsyncMiddleware},
+  Call, Error, Metadata, ProcedureDescription, Reply,
+};
+
+#[derive(derive_builder::Builder)]
+#[cfg_attr(not(feature = "std"), builder(no_std))]
+#[builder(pattern = "owned", build_fn(skip))]
+/// Asynchronous RPC client and/or server type.
+///
+/// Container for [`AsyncBackend`](crate::interfaces::AsyncBackend), [`AsyncFrontend`](crate::interfaces::AsyncFrontend) and [`AsyncMiddleware`](crate::interfaces::AsyncMiddleware)s.
+///
+/// In contrast to [`Mer`](crate::Mer) the [`AsyncMer`] does not block the calling thread. All RPCs are driven by the executor of the caller.
+pub struct AsyncMer<B, F>
+where
+  B: AsyncBackend,
+  F: AsyncFrontend<Backend = B>,
+{
+  #[builder(setter(into, name = "backend_setter"), private)]
+  backend: Arc<B>,
+  #[builder(setter(into, name = "frontend_setter"), private)]
+  frontend: Arc<F>,
+
+  #[builder(setter(into, name = "middlewares_setter"), private)]
+  middlewares: Arc<Vec<Box<dyn AsyncMiddleware<Backend = B>>>>,
+
+  /// Default timeout for outgoing [`Call`]s which do not have a timeout set.
+  #[builder(setter(strip_option))]
+  timeout: Option<Duration>,
+}
+
+impl<B, F> AsyncMerBuilder<B, F>
+where
+  B: AsyncBackend,
+  B: 'static,
+  B::Intermediate: Clone + fmt::Debug + Send + Sync + 'static,
+  F: AsyncFrontend<Backend = B>,
+  F: 'static,
+{
+  pub fn backend(self, value: B) -> Self {
+    self.backend_setter(Arc::new(value))
+  }
+
+  pub fn frontend(self, value: F) -> Self {
+    self.frontend_setter(Arc::new(value))
+  }
+
+  pub fn middlewares(self, value: Vec<Box<dyn AsyncMiddleware<Backend = B>>>) -> Self {
+    self.middlewares_setter(Arc::new(value))
+  }
+
+  /// Builds a new [`AsyncMer`].
+  ///
+  /// Registers the [`AsyncBackend`](crate::interfaces::AsyncBackend), [`AsyncFrontend`](crate::interfaces::AsyncFrontend) and [`AsyncMiddleware`](crate::interfaces::AsyncMiddleware)s.
+  pub fn build(self) -> Result<AsyncMer<B, F>> {
+    trace!("AsyncMerBuilder.build()");
+
+    let mut backend = self.backend.ok_or_else(|| Error::Init("backend".into()))?;
+    let mut frontend = self.frontend.ok_or_else(|| Error::Init("frontend".into()))?;
+    let middlewares = self.middlewares.unwrap_or_default();
+    let timeout = self.timeout.flatten();
+
+    let frontend_backend = Arc::new(spin::Once::<Arc<F>>::new());
+    let middlewares_backend = Arc::clone(&middlewares);
+
+    let middlewares_frontend = Arc::clone(&middlewares);
+
+    Arc::get_mut(&mut backend)
+      .ok_or(Error::Lock)?
+      .register({
+        let frontend_backend = Arc::clone(&frontend_backend);
+        move |call: Call<B::Intermediate>| {
+          trace!("AsyncMer.backend.register()");
+          let frontend = Arc::clone(&frontend_backend);
+          let middlewares = Arc::clone(&middlewares_backend);
+
+          Box::pin(async move {
+            let receive = |call: Call<B::Intermediate>| {
+              let frontend = &frontend;
+              let middlewares = &middlewares;
+              async move {
+                let mut unwrapped = Ok(call);
+                for middleware in middlewares.iter() {
+                  unwrapped = middleware.unwrap_call(unwrapped).await;
+                }
+
+                let mut reply = match (unwrapped, frontend.get()) {
+                  (Ok(unwrapped_ok), Some(frontend)) if unwrapped_ok.procedure == ProcedureDescription::DISCOVER => {
+                    B::serialize(&frontend.procedures()).map(|payload| Reply { payload, metadata: Metadata::new() })
+                  }
+                  (Ok(unwrapped_ok), Some(frontend)) => frontend.receive(unwrapped_ok).await,
+                  (Ok(_), None) => Err(Error::Init("frontend".into()).into()),
+                  (Err(err), _) => Err(err),
+                };
+
+                for middleware in middlewares.iter() {
+                  reply = middleware.wrap_reply(reply).await;
+                }
+                reply
+              }
+            };
+
+            match call.procedure == BATCH {
+              true => {
+                let calls: Vec<Call<B::Intermediate>> = B::deserialize(&call.payload)?;
+                let mut replies = Vec::with_capacity(calls.len());
+                for call in calls {
+                  replies.push(receive(call).await);
+                }
+                B::serialize(&batch::batch_replies(replies)).map(|payload| Reply { payload, metadata: Metadata::new() })
+              }
+              false => receive(call).await,
+            }
+          })
+        }
+      })
+      .map_err::<anyhow::Error, _>(|e| Error::Register { source: e, end: "backend".into() }.into())?;
+
+    let backend_frontend = Arc::clone(&backend);
+
+    Arc::get_mut(&mut frontend)
+      .ok_or(Error::Lock)?
+      .register(move |mut call: Call<B::Intermediate>| {
+        trace!("AsyncMer.frontend.register()");
+        if let (Some(timeout), None) = (timeout, call.metadata.timeout()) {
+          call.metadata.set_timeout(timeout);
+        }
+
+        let backend = Arc::clone(&backend_frontend);
+        let middlewares = Arc::clone(&middlewares_frontend);
+
+        Box::pin(async move {
+          if call.procedure == BATCH {
+            let calls: Vec<Call<B::Intermediate>> = B::deserialize(&call.payload)?;
+            let procedures = calls.iter().map(|c| c.procedure.clone()).collect();
+            let mut wrapped = Vec::with_capacity(calls.len());
+            for call in calls {
+              let mut wrapped_call = Ok(call);
+              for middleware in middlewares.iter().rev() {
+                wrapped_call = middleware.wrap_call(wrapped_call).await;
+              }
+              wrapped.push(wrapped_call);
+            }
+            let (calls, pending) = batch::Pending::new(wrapped, procedures, call.metadata.timeout());
+
+            let reply = backend
+              .call(Call {
+                procedure: call.procedure,
+                payload: B::serialize(&calls)?,
+                metadata: call.metadata,
+              })
+              .await?;
+
+            let mut replies = Vec::new();
+            for mut unwrapped in pending.replies(B::deserialize(&reply.payload)?)? {
+              for middleware in middlewares.iter() {
+                unwrapped = middleware.unwrap_reply(unwrapped).await;
+              }
+              replies.push(unwrapped);
+            }
+            return B::serialize(&batch::batch_replies(replies)).map(|payload| Reply { payload, metadata: reply.metadata });
+          }
+
+          let mut wrapped = Ok(call);
+          for middleware in middlewares.iter().rev() {
+            wrapped = middleware.wrap_call(wrapped).await;
+          }
+
+          let mut reply = match wrapped {
+            Ok(wrapped_ok) => backend.call(wrapped_ok).await,
+            Err(err) => Err(err),
+          };
+
+          for middleware in middlewares.iter() {
+            reply = middleware.unwrap_reply(reply).await;
+          }
+          reply
+        })
+      })
+      .map_err::<anyhow::Error, _>(|e| Error::Register { source: e, end: "frontend".into() }.into())?;
+
+    frontend_backend.call_once(|| Arc::clone(&frontend));
+
+    Ok(AsyncMer {
+      backend,
+      frontend,
+      middlewares,
+      timeout,
+    })
+  }
+}
+
+/// Returns a new [`AsyncMerBuilder`]
+impl<B: AsyncBackend, F: AsyncFrontend<Backend = B>> AsyncMer<B, F> {
+  pub fn builder() -> AsyncMerBuilder<B, F> {
+    AsyncMerBuilder::default()
+  }
+}
+
+impl<B: AsyncBackend + 'static, F: AsyncFrontend<Backend = B>> AsyncMer<B, F> {
+  /// Allows accessing the [`AsyncFrontend`](crate::interfaces::AsyncFrontend).
+  pub fn frontend(&self) -> &F {
+    trace!("AsyncMer.frontend()");
+    &self.frontend
+  }
+
+  /// Allows accessing the [`AsyncBackend`](crate::interfaces::AsyncBackend).
+  pub fn backend(&self) -> &B {
+    trace!("AsyncMer.backend()");
+    &self.backend
+  }
+
+  /// Allows accessing the [`AsyncMiddleware`](crate::interfaces::AsyncMiddleware)s.
+  pub fn middlewares<M>(&self, index: usize) -> Result<&M, Error>
+  where
+    M: AsyncMiddleware<Backend = B> + 'static,
+  {
+    trace!("AsyncMer.middlewares()");
+
+    self
+      .middlewares
+      .get(index)
+      .ok_or(Error::MiddlewareIndex(index))?
+      .as_any()
+      .downcast_ref::<M>()
+      .ok_or(Error::DowncastError)
+  }
+
+  /// Returns the default timeout for outgoing [`Call`]s.
+  pub fn timeout(&self) -> Option<Duration> {
+    self.timeout
+  }
+}
@@ -0,0 +1,77 @@
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+use core::{fmt, time::Duration};
+
+use anyhow::Result;
+
+use crate::{Call, RemoteError, Reply};
+
+/// Name of the reserved procedure of a [`Call`] carrying a batch of [`Call`]s in a single backend message.
+///
+/// The payload of the [`Call`] is the serialized `Vec<Call>` and the payload of its [`Reply`] the serialized `Vec<`[`BatchReply`]`>` in the same order.
+/// [`Mer`](crate::Mer) and [`AsyncMer`](crate::AsyncMer) pass every [`Call`] of the batch through the [`Middleware`](crate::interfaces::Middleware)s on its own, so one failing [`Call`] does not fail the whole batch.
+pub const BATCH: &str = "rpc.batch";
+
+/// [`Reply`] or error of a single [`Call`] of a [`BATCH`].
+pub type BatchReply<T> = Result<Reply<T>, RemoteError<T>>;
+
+/// Calls of a [`BATCH`] on the client side.
+///
+/// Keeps the procedures and the position of the [`Call`]s which failed before they were sent.
+pub(crate) struct Pending {
+  procedures: Vec<String>,
+  failed: Vec<Option<anyhow::Error>>,
+  timeout: Option<Duration>,
+}
+
+impl Pending {
+  /// Splits the wrapped `calls` into the [`Call`]s to send and the [`Pending`] state to match the replies.
+  pub(crate) fn new<T>(calls: Vec<Result<Call<T>>>, procedures: Vec<String>, timeout: Option<Duration>) -> (Vec<Call<T>>, Self) {
+    let mut send = Vec::new();
+    let mut failed = Vec::new();
+
+    for call in calls {
+      match call {
+        Ok(call) => {
+          send.push(call);
+          failed.push(None);
+        }
+        Err(err) => failed.push(Some(err)),
+      }
+    }
+
+    (send, Self { procedures, failed, timeout })
+  }
+
+  /// Matches the `replies` of the sent [`Call`]s with the [`Call`]s which failed before they were sent.
+  pub(crate) fn replies<T>(self, replies: Vec<BatchReply<T>>) -> Result<Vec<Result<Reply<T>>>>
+  where
+    T: fmt::Debug + Send + Sync + 'static,
+  {
+    if replies.len() != self.failed.iter().filter(|f| f.is_none()).count() {
+      return Err(RemoteError::new(RemoteError::INTERNAL_ERROR, "number of batch replies does not match the number of calls").into());
+    }
+
+    let timeout = self.timeout;
+    let mut replies = replies.into_iter();
+    Ok(
+      self
+        .failed
+        .into_iter()
+        .zip(self.procedures)
+        .map(|(failed, procedure)| match failed {
+          Some(err) => Err(err),
+          None => replies.next().unwrap().map_err(|e| e.into_error(&procedure, timeout)),
+        })
+        .collect(),
+    )
+  }
+}
+
+/// Converts the results of the [`Call`]s of a batch to [`BatchReply`]s.
+pub(crate) fn batch_replies<T>(replies: Vec<Result<Reply<T>>>) -> Vec<BatchReply<T>>
+where
+  T: Clone + fmt::Debug + Send + Sync + 'static,
+{
+  replies.into_iter().map(|r| r.map_err(RemoteError::from)).collect()
+}
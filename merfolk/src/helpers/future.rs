@@ -0,0 +1,45 @@
+//! Helper types and functions for the asynchronous interfaces.
+//!
+//! The [`BoxFuture`] type is used as return type by the [`AsyncBackend`](crate::interfaces::AsyncBackend), [`AsyncFrontend`](crate::interfaces::AsyncFrontend) and [`AsyncMiddleware`](crate::interfaces::AsyncMiddleware) traits.
+
+use alloc::boxed::Box;
+use core::{future::Future, pin::Pin};
+
+/// A pinned and boxed [`Future`] which can be sent between threads.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Blocks the current thread until the [`Future`] is resolved.
+///
+/// The [`Future`] is polled on the current thread which is parked while the [`Future`] is pending.
+/// Futures relying on a specific runtime (e.g. [`tokio`](https://docs.rs/tokio)) still need to be polled from within a context of that runtime.
+#[cfg(feature = "std")]
+pub fn block_on<F: Future>(future: F) -> F::Output {
+  use std::{
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+  };
+
+  struct ThreadWaker(Thread);
+
+  impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+      self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+      self.0.unpark();
+    }
+  }
+
+  let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+  let mut context = Context::from_waker(&waker);
+  let mut future = core::pin::pin!(future);
+
+  loop {
+    match future.as_mut().poll(&mut context) {
+      Poll::Ready(output) => return output,
+      Poll::Pending => thread::park(),
+    }
+  }
+}
@@ -1,5 +1,6 @@
 //! Helper types and functions for merfolk.
 
+pub mod future;
 pub mod smart_lock;
 
 #[doc(hidden)]
@@ -0,0 +1,48 @@
+use anyhow::Result;
+
+use crate::helpers::future::BoxFuture;
+
+/// The [`AsyncBackend`] is the asynchronous counterpart of the [`Backend`](crate::interfaces::Backend).
+///
+/// Instead of blocking until a [`Reply`](crate::Reply) is available the [`AsyncBackend`] returns a [`BoxFuture`] which is driven by the executor of the caller.
+///
+/// # Incomming RPCs
+/// The [`AsyncBackend`] is acting as server.
+///
+/// Incomming calls from the client are passed on to the [`AsyncFrontend`](crate::interfaces::AsyncFrontend) as [`Call`](crate::Call)<[`Intermediate`](AsyncBackend::Intermediate)`>`.
+/// The [`Reply`](crate::Reply)<[`Intermediate`](AsyncBackend::Intermediate)`>`s are awaited and sent back to the client.
+///
+/// # Outgoing RPCs
+/// The [`AsyncBackend`] is acting as client.
+///
+/// The [`AsyncFrontend`](crate::interfaces::AsyncFrontend) passes [`Call`](crate::Call)<[`Intermediate`](AsyncBackend::Intermediate)`>`s to the [`AsyncBackend`] which sends them to the server.
+/// The returned [`BoxFuture`] resolves to the [`Reply`](crate::Reply)<[`Intermediate`](AsyncBackend::Intermediate)`>` received from the server.
+///
+/// # Adapters
+/// A synchronous [`Backend`](crate::interfaces::Backend) can be used as [`AsyncBackend`] with the [`Async`](crate::adapters::Async) adapter and vice versa with the [`Blocking`](crate::adapters::Blocking) adapter.
+///
+/// # Examples
+/// For examples look at the provided [`AsyncBackend`]s:
+/// * [`Http`](/merfolk_backend_http)
+/// * [`InProcess`](/merfolk_backend_in_process)
+/// * [`SerialPort`](/merfolk_backend_serialport)
+pub trait AsyncBackend: Send + Sync {
+  /// The Intermediate type required by the [`AsyncBackend`].
+  type Intermediate: serde::Serialize + for<'a> serde::Deserialize<'a> + Send + 'static;
+
+  /// Registers the server callback function from [`AsyncMer`](crate::AsyncMer). The callback is used to pass incomming [`Call`](crate::Call)s to the [`AsyncFrontend`](crate::interfaces::AsyncFrontend).
+  fn register<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(crate::Call<<Self as AsyncBackend>::Intermediate>) -> BoxFuture<'static, Result<crate::Reply<<Self as AsyncBackend>::Intermediate>>> + Send + Sync + 'static;
+
+  /// This function is called by the [`AsyncFrontend`](crate::interfaces::AsyncFrontend) for outgoing [`Call`](crate::Call)s.
+  fn call(&self, call: crate::Call<<Self as AsyncBackend>::Intermediate>) -> BoxFuture<'_, Result<crate::Reply<<Self as AsyncBackend>::Intermediate>>>;
+
+  /// Serializes a type `T` to the [`Intermediate`](Self::Intermediate) type.
+  fn serialize<T: serde::Serialize>(from: &T) -> Result<<Self as AsyncBackend>::Intermediate>;
+
+  /// Deserializes the [`Intermediate`](Self::Intermediate) type to a type `T`.
+  fn deserialize<T>(from: &<Self as AsyncBackend>::Intermediate) -> Result<T>
+  where
+    T: for<'de> serde::Deserialize<'de>;
+}
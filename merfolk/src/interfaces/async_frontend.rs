@@ -0,0 +1,43 @@
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use anyhow::Result;
+
+use super::AsyncBackend;
+use crate::helpers::future::BoxFuture;
+
+/// The [`AsyncFrontend`] is the asynchronous counterpart of the [`Frontend`](crate::interfaces::Frontend).
+///
+/// # Incomming RPCs
+/// The [`AsyncFrontend`] is acting as server.
+///
+/// Incomming [`Call`](crate::Call)<[`Intermediate`](AsyncBackend::Intermediate)`>`s are received from the [`AsyncBackend`].
+/// The returned [`BoxFuture`] resolves to the [`Reply`](crate::Reply)<[`Intermediate`](AsyncBackend::Intermediate)`>` which is passed on to the [`AsyncBackend`].
+///
+/// # Outgoing RPCs
+/// The [`AsyncFrontend`] is acting as client.
+///
+/// The [`AsyncFrontend`] serializes the calls via [`AsyncBackend::serialize`](crate::interfaces::AsyncBackend::serialize)
+/// and passes them to the registered caller which returns a [`BoxFuture`] resolving to the [`Reply`](crate::Reply)<[`Intermediate`](AsyncBackend::Intermediate)`>`.
+///
+/// # Adapters
+/// A synchronous [`Frontend`](crate::interfaces::Frontend) can be used as [`AsyncFrontend`] with the [`Async`](crate::adapters::Async) adapter and vice versa with the [`Blocking`](crate::adapters::Blocking) adapter.
+pub trait AsyncFrontend: Send + Sync {
+  /// The used [`AsyncBackend`].
+  type Backend: AsyncBackend;
+
+  /// Registers the client callback function from [`AsyncMer`](crate::AsyncMer). The callback is used to pass outgoing [`Call`](crate::Call)s to the [`AsyncBackend`].
+  fn register<T>(&mut self, caller: T) -> Result<()>
+  where
+    T: Fn(crate::Call<<Self::Backend as AsyncBackend>::Intermediate>) -> BoxFuture<'static, Result<crate::Reply<<Self::Backend as AsyncBackend>::Intermediate>>> + Send + Sync + 'static;
+
+  /// This function is called by the [`AsyncBackend`] for incomming [`Call`](crate::Call)s.
+  fn receive(&self, call: crate::Call<<Self::Backend as AsyncBackend>::Intermediate>) -> BoxFuture<'_, Result<crate::Reply<<Self::Backend as AsyncBackend>::Intermediate>>>;
+
+  /// Describes the procedures which can be called on the [`AsyncFrontend`].
+  ///
+  /// Used by [`AsyncMer`](crate::AsyncMer) to answer the reserved [`DISCOVER`](crate::ProcedureDescription::DISCOVER) procedure.
+  fn procedures(&self) -> Vec<crate::ProcedureDescription> {
+    Vec::new()
+  }
+}
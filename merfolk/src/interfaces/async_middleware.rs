@@ -0,0 +1,31 @@
+use anyhow::Result;
+
+use super::AsyncBackend;
+use crate::helpers::future::BoxFuture;
+
+/// The [`AsyncMiddleware`] is the asynchronous counterpart of the [`Middleware`](crate::interfaces::Middleware).
+///
+/// The functions are called in the same order as the functions of the [`Middleware`](crate::interfaces::Middleware) but return a [`BoxFuture`] which is awaited by [`AsyncMer`](crate::AsyncMer).
+///
+/// # Adapters
+/// A synchronous [`Middleware`](crate::interfaces::Middleware) can be used as [`AsyncMiddleware`] with the [`Async`](crate::adapters::Async) adapter and vice versa with the [`Blocking`](crate::adapters::Blocking) adapter.
+pub trait AsyncMiddleware: Send + Sync {
+  type Backend: AsyncBackend;
+
+  /// Wraps the outgoing call [`Call`](crate::Call)<[`Intermediate`](AsyncBackend::Intermediate)`>`
+  fn wrap_call(&self, call: Result<crate::Call<<Self::Backend as AsyncBackend>::Intermediate>>) -> BoxFuture<'_, Result<crate::Call<<Self::Backend as AsyncBackend>::Intermediate>>>;
+
+  /// Wraps the outgoing reply [`Reply`](crate::Reply)<[`Intermediate`](AsyncBackend::Intermediate)`>`
+  fn wrap_reply(&self, reply: Result<crate::Reply<<Self::Backend as AsyncBackend>::Intermediate>>) -> BoxFuture<'_, Result<crate::Reply<<Self::Backend as AsyncBackend>::Intermediate>>>;
+
+  /// Unwraps the incomming call [`Call`](crate::Call)<[`Intermediate`](AsyncBackend::Intermediate)`>`
+  fn unwrap_call(&self, call: Result<crate::Call<<Self::Backend as AsyncBackend>::Intermediate>>) -> BoxFuture<'_, Result<crate::Call<<Self::Backend as AsyncBackend>::Intermediate>>>;
+
+  /// Unwraps the imcomming reply [`Reply`](crate::Reply)<[`Intermediate`](AsyncBackend::Intermediate)`>`
+  fn unwrap_reply(&self, reply: Result<crate::Reply<<Self::Backend as AsyncBackend>::Intermediate>>) -> BoxFuture<'_, Result<crate::Reply<<Self::Backend as AsyncBackend>::Intermediate>>>;
+
+  /// return self as Any. Needed for downcasting when accessing an AsyncMiddleware through [`AsyncMer`](crate::AsyncMer).
+  ///
+  /// implement like this `fn as_any(&self) -> &dyn core::any::Any { self }`
+  fn as_any(&self) -> &dyn core::any::Any;
+}
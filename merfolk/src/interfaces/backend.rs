@@ -20,10 +20,9 @@ use anyhow::Result;
 /// * [`Http`](/merfolk_backend_http)
 /// * [`InProcess`](/merfolk_backend_in_process)
 /// * [`SerialPort`](/merfolk_backend_serialport)
-
 pub trait Backend: Send {
   /// The Intermediate type required by the [`Backend`].
-  type Intermediate: serde::Serialize + for<'a> serde::Deserialize<'a>;
+  type Intermediate: serde::Serialize + for<'a> serde::Deserialize<'a> + Send + 'static;
 
   /// Registers the server callback function from [`Mer`](crate::Mer). The callback is used to pass incomming [`Call`](crate::Call)s to the [`Frontend`](crate::interfaces::Frontend).
   fn register<T>(&mut self, receiver: T) -> Result<()>
@@ -33,6 +32,44 @@ pub trait Backend: Send {
   /// This function is called by the [`Frontend`](crate::interfaces::Frontend) for outgoing [`Call`](crate::Call)s.
   fn call(&mut self, call: crate::Call<<Self as Backend>::Intermediate>) -> Result<crate::Reply<<Self as Backend>::Intermediate>>;
 
+  /// Registers the server callback function from [`Mer`](crate::Mer) for streaming [`Call`](crate::Call)s (see [`Metadata::set_stream`](crate::Metadata::set_stream)).
+  ///
+  /// [`Backend`]s which do not support streaming ignore the callback.
+  fn register_stream<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(crate::Call<<Self as Backend>::Intermediate>) -> Result<crate::ReplyStream<<Self as Backend>::Intermediate>> + Send + Sync + 'static,
+  {
+    let _ = receiver;
+    Ok(())
+  }
+
+  /// This function is called by the [`Frontend`](crate::interfaces::Frontend) for outgoing streaming [`Call`](crate::Call)s.
+  ///
+  /// [`Backend`]s which do not support streaming return the [`Reply`](crate::Reply) of [`call`](Backend::call) as single item.
+  fn call_stream(&mut self, call: crate::Call<<Self as Backend>::Intermediate>) -> Result<crate::ReplyStream<<Self as Backend>::Intermediate>> {
+    self.call(call).map(|reply| crate::ReplyStream::once(Ok(reply.payload)))
+  }
+
+  /// Registers the server callback function from [`Mer`](crate::Mer) for uploads (see [`Metadata::set_upload`](crate::Metadata::set_upload)).
+  ///
+  /// [`Backend`]s which do not support uploads ignore the callback.
+  fn register_upload<T>(&mut self, receiver: T) -> Result<()>
+  where
+    T: Fn(crate::Call<<Self as Backend>::Intermediate>, crate::CallStream<<Self as Backend>::Intermediate>) -> Result<crate::Reply<<Self as Backend>::Intermediate>> + Send + Sync + 'static,
+  {
+    let _ = receiver;
+    Ok(())
+  }
+
+  /// This function is called by the [`Frontend`](crate::interfaces::Frontend) for outgoing uploads.
+  ///
+  /// The `chunks` are sent after the [`Call`](crate::Call) without sending them faster than the server side consumes them.
+  /// [`Backend`]s which do not support uploads fail with [`Error::Unsupported`](crate::Error::Unsupported).
+  fn call_upload(&mut self, call: crate::Call<<Self as Backend>::Intermediate>, chunks: crate::CallStream<<Self as Backend>::Intermediate>) -> Result<crate::Reply<<Self as Backend>::Intermediate>> {
+    let _ = (call, chunks);
+    Err(crate::Error::Unsupported("uploads".into()).into())
+  }
+
   /// Serializes a type `T` to the [`Intermediate`](Self::Intermediate) type.
   fn serialize<T: serde::Serialize>(from: &T) -> Result<<Self as Backend>::Intermediate>;
 
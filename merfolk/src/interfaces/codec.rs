@@ -0,0 +1,88 @@
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+use anyhow::Result;
+
+/// The [`Codec`] is responsible for serializing and deserializing the packages sent by a [`Backend`](crate::interfaces::Backend).
+///
+/// Splitting the format from the transport allows [`Backend`](crate::interfaces::Backend)s to be generic over the [`Codec`] and e.g. run a compact binary format over a serial port and JSON over Http.
+///
+/// # Examples
+/// For examples look at the provided [`Codec`]s in [`merfolk_codecs`](/merfolk_codecs):
+/// * `Json`, `Ron` encoding to `String`
+/// * `MessagePack`, `Cbor`, `Bincode`, `Postcard` encoding to `Vec<u8>`
+pub trait Codec: Send + Sync + 'static {
+  /// The type the [`Codec`] encodes to which is used as [`Intermediate`](crate::interfaces::Backend::Intermediate) type of the [`Backend`](crate::interfaces::Backend).
+  type Intermediate: Encoded + serde::Serialize + for<'a> serde::Deserialize<'a> + Clone + core::fmt::Debug + Send + Sync + 'static;
+
+  /// Encodes a type `T` to the [`Intermediate`](Self::Intermediate) type.
+  fn encode<T: serde::Serialize>(from: &T) -> Result<Self::Intermediate>;
+
+  /// Decodes the [`Intermediate`](Self::Intermediate) type to a type `T`.
+  fn decode<T>(from: &Self::Intermediate) -> Result<T>
+  where
+    T: for<'de> serde::Deserialize<'de>;
+}
+
+/// Conversions of the [`Intermediate`](Codec::Intermediate) type of a [`Codec`] used by [`Backend`](crate::interfaces::Backend)s to carry it.
+///
+/// Text [`Codec`]s are expected to encode to a single line so the text is carried unchanged by line based transports.
+pub trait Encoded: Sized {
+  /// Converts to bytes for byte based transports.
+  fn into_bytes(self) -> Vec<u8>;
+
+  /// Converts the bytes created by [`into_bytes`](Encoded::into_bytes) back.
+  fn from_bytes(bytes: Vec<u8>) -> Result<Self>;
+
+  /// Converts to a single line of text for line based transports.
+  fn into_line(self) -> String;
+
+  /// Converts the line created by [`into_line`](Encoded::into_line) back.
+  fn from_line(line: &str) -> Result<Self>;
+}
+
+impl Encoded for String {
+  fn into_bytes(self) -> Vec<u8> {
+    self.into_bytes()
+  }
+
+  fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+    String::from_utf8(bytes).map_err(|e| crate::Error::Decode(e.utf8_error()).into())
+  }
+
+  fn into_line(self) -> String {
+    self
+  }
+
+  fn from_line(line: &str) -> Result<Self> {
+    Ok(line.into())
+  }
+}
+
+/// Binary data is carried by line based transports as hexadecimal text.
+impl Encoded for Vec<u8> {
+  fn into_bytes(self) -> Vec<u8> {
+    self
+  }
+
+  fn from_bytes(bytes: Vec<u8>) -> Result<Self> {
+    Ok(bytes)
+  }
+
+  fn into_line(self) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    self.iter().flat_map(|b| [DIGITS[(b >> 4) as usize] as char, DIGITS[(b & 0xf) as usize] as char]).collect()
+  }
+
+  fn from_line(line: &str) -> Result<Self> {
+    if !line.len().is_multiple_of(2) {
+      return Err(crate::Error::Hex.into());
+    }
+
+    (0..line.len())
+      .step_by(2)
+      .map(|i| line.get(i..i + 2).and_then(|digits| u8::from_str_radix(digits, 16).ok()).ok_or_else(|| crate::Error::Hex.into()))
+      .collect()
+  }
+}
@@ -1,3 +1,6 @@
+#[cfg(not(feature = "std"))]
+use alloc::{format, vec::Vec};
+
 use anyhow::Result;
 
 use super::Backend;
@@ -24,7 +27,6 @@ use super::Backend;
 /// * [`Derive`](/merfolk_frontend_derive)
 /// * [`Logger`](/merfolk_frontend_logger)
 /// * [`Register`](/merfolk_frontend_register)
-
 pub trait Frontend: Send {
   /// The used  [`Backend`].
   type Backend: Backend;
@@ -36,4 +38,50 @@ pub trait Frontend: Send {
 
   /// This function is called by the [`Backend`] for incomming [`Call`](crate::Call)s.
   fn receive(&self, call: crate::Call<<Self::Backend as Backend>::Intermediate>) -> Result<crate::Reply<<Self::Backend as Backend>::Intermediate>>;
+
+  /// Registers the client callback function from [`Mer`](crate::Mer) for streaming [`Call`](crate::Call)s.
+  ///
+  /// [`Frontend`]s which do not make streaming calls ignore the callback.
+  fn register_stream<T>(&mut self, caller: T) -> Result<()>
+  where
+    T: Fn(crate::Call<<Self::Backend as Backend>::Intermediate>) -> Result<crate::ReplyStream<<Self::Backend as Backend>::Intermediate>> + Send + Sync + 'static,
+  {
+    let _ = caller;
+    Ok(())
+  }
+
+  /// This function is called by the [`Backend`] for incomming streaming [`Call`](crate::Call)s.
+  ///
+  /// Defaults to the [`Reply`](crate::Reply) of [`receive`](Frontend::receive) as single item.
+  fn receive_stream(&self, call: crate::Call<<Self::Backend as Backend>::Intermediate>) -> Result<crate::ReplyStream<<Self::Backend as Backend>::Intermediate>> {
+    self.receive(call).map(|reply| crate::ReplyStream::once(Ok(reply.payload)))
+  }
+
+  /// Registers the client callback function from [`Mer`](crate::Mer) for uploads.
+  ///
+  /// [`Frontend`]s which do not make uploads ignore the callback.
+  fn register_upload<T>(&mut self, caller: T) -> Result<()>
+  where
+    T: Fn(crate::Call<<Self::Backend as Backend>::Intermediate>, crate::CallStream<<Self::Backend as Backend>::Intermediate>) -> Result<crate::Reply<<Self::Backend as Backend>::Intermediate>>
+      + Send
+      + Sync
+      + 'static,
+  {
+    let _ = caller;
+    Ok(())
+  }
+
+  /// This function is called by the [`Backend`] for incomming uploads.
+  ///
+  /// Returns the [`Upload`](crate::Upload) handler consuming the chunks of the upload. Defaults to failing with [`PROCEDURE_NOT_FOUND`](crate::RemoteError::PROCEDURE_NOT_FOUND).
+  fn receive_upload(&self, call: crate::Call<<Self::Backend as Backend>::Intermediate>) -> Result<crate::Upload<<Self::Backend as Backend>::Intermediate>> {
+    Err(crate::RemoteError::new(crate::RemoteError::PROCEDURE_NOT_FOUND, format!("{} does not receive uploads", call.procedure)).into())
+  }
+
+  /// Describes the procedures which can be called on the [`Frontend`].
+  ///
+  /// Used by [`Mer`](crate::Mer) to answer the reserved [`DISCOVER`](crate::ProcedureDescription::DISCOVER) procedure.
+  fn procedures(&self) -> Vec<crate::ProcedureDescription> {
+    Vec::new()
+  }
 }
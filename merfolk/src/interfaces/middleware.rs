@@ -16,7 +16,6 @@ use super::Backend;
 /// For examples look at the provided [`Middleware`]s:
 /// * [`Authentication`](/merfolk_middleware_authentication)
 /// * [`Router`](/merfolk_middleware_router)
-
 pub trait Middleware: Send {
   type Backend: Backend;
 
@@ -1,4 +1,4 @@
-//! This module contains Traits for Backends, Frontends and Middlewares.
+//! This module contains Traits for Backends, Codecs, Frontends and Middlewares.
 
 #[doc(hidden)]
 pub mod backend;
@@ -6,6 +6,12 @@ pub mod backend;
 #[doc(inline)]
 pub use backend::Backend;
 
+#[doc(hidden)]
+pub mod codec;
+
+#[doc(inline)]
+pub use codec::{Codec, Encoded};
+
 #[doc(hidden)]
 pub mod middleware;
 
@@ -17,3 +23,21 @@ pub mod frontend;
 
 #[doc(inline)]
 pub use frontend::Frontend;
+
+#[doc(hidden)]
+pub mod async_backend;
+
+#[doc(inline)]
+pub use async_backend::AsyncBackend;
+
+#[doc(hidden)]
+pub mod async_middleware;
+
+#[doc(inline)]
+pub use async_middleware::AsyncMiddleware;
+
+#[doc(hidden)]
+pub mod async_frontend;
+
+#[doc(inline)]
+pub use async_frontend::AsyncFrontend;
@@ -0,0 +1,86 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+  string::{String, ToString},
+  vec::Vec,
+};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// Description of a procedure which can be called on a [`Frontend`](crate::interfaces::Frontend).
+///
+/// The descriptions of all procedures are returned by the reserved [`DISCOVER`](ProcedureDescription::DISCOVER) procedure which is answered by [`Mer`](crate::Mer) and [`AsyncMer`](crate::AsyncMer)
+/// using [`Frontend::procedures`](crate::interfaces::Frontend::procedures).
+///
+/// ```
+/// # use merfolk::{ArgumentDescription, ProcedureDescription};
+/// let description = ProcedureDescription::new("add")
+///   .argument(ArgumentDescription::new("i32").name("a"))
+///   .argument(ArgumentDescription::new("i32").name("b"))
+///   .returns("i32")
+///   .docs("Adds two numbers.");
+///
+/// assert_eq!(description.arguments.len(), 2);
+/// ```
+pub struct ProcedureDescription {
+  pub name: String,
+  #[serde(default)]
+  pub arguments: Vec<ArgumentDescription>,
+  #[serde(default)]
+  pub returns: Option<String>,
+  #[serde(default)]
+  pub docs: Option<String>,
+}
+
+impl ProcedureDescription {
+  /// Name of the reserved procedure returning the [`ProcedureDescription`]s of all procedures.
+  pub const DISCOVER: &'static str = "rpc.discover";
+
+  /// Creates a new [`ProcedureDescription`] without arguments, return type and docs.
+  pub fn new<N: ToString>(name: N) -> Self {
+    Self {
+      name: name.to_string(),
+      ..Default::default()
+    }
+  }
+
+  /// Adds an argument.
+  pub fn argument(mut self, argument: ArgumentDescription) -> Self {
+    self.arguments.push(argument);
+    self
+  }
+
+  /// Sets the description of the return type.
+  pub fn returns<R: ToString>(mut self, returns: R) -> Self {
+    self.returns = Some(returns.to_string());
+    self
+  }
+
+  /// Sets the docs.
+  pub fn docs<D: ToString>(mut self, docs: D) -> Self {
+    self.docs = Some(docs.to_string());
+    self
+  }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+/// Description of an argument of a procedure.
+pub struct ArgumentDescription {
+  #[serde(default)]
+  pub name: Option<String>,
+  #[serde(rename = "type")]
+  pub ty: String,
+}
+
+impl ArgumentDescription {
+  /// Creates a new [`ArgumentDescription`] of type `ty` without a name.
+  pub fn new<T: ToString>(ty: T) -> Self {
+    Self { name: None, ty: ty.to_string() }
+  }
+
+  /// Sets the name.
+  pub fn name<N: ToString>(mut self, name: N) -> Self {
+    self.name = Some(name.to_string());
+    self
+  }
+}
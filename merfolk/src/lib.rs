@@ -180,18 +180,52 @@
 //! # }
 //! ```
 //!
+//! # Async
+//! Every interface has an asynchronous counterpart: [`AsyncBackend`](crate::interfaces::AsyncBackend), [`AsyncFrontend`](crate::interfaces::AsyncFrontend) and [`AsyncMiddleware`](crate::interfaces::AsyncMiddleware).
+//! They are orchestrated by [`AsyncMer`](crate::AsyncMer) which does not block and drives all RPCs on the executor of the caller.
+//!
+//! Synchronous and asynchronous implementations can be bridged with the [`Async`](crate::adapters::Async) and [`Blocking`](crate::adapters::Blocking) adapters.
+//!
+//! # Introspection
+//! The reserved procedure [`rpc.discover`](crate::ProcedureDescription::DISCOVER) returns a [`ProcedureDescription`](crate::ProcedureDescription) of every procedure the server side [`Frontend`](crate::interfaces::Frontend) provides.
+//! It is answered by [`Mer`](crate::Mer) and [`AsyncMer`](crate::AsyncMer) with the descriptions returned by [`Frontend::procedures`](crate::interfaces::Frontend::procedures).
+//!
+//! # Streaming
+//! Procedures can answer with a [`ReplyStream`](crate::ReplyStream) instead of a single [`Reply`](crate::Reply) to send large results or progress updates incrementally.
+//! [`Mer`](crate::Mer) passes streaming [`Call`](crate::Call)s from [`Frontend::register_stream`](crate::interfaces::Frontend::register_stream) through the [`Middleware`](crate::interfaces::Middleware)s to [`Backend::call_stream`](crate::interfaces::Backend::call_stream) and on the server side to [`Frontend::receive_stream`](crate::interfaces::Frontend::receive_stream).
+//! The items of the stream are not passed through the reply [`Middleware`](crate::interfaces::Middleware)s.
+//!
+//! # Uploads
+//! A [`Call`](crate::Call) can be followed by a [`CallStream`](crate::CallStream) of chunks which the server side procedure consumes as they arrive, e.g. to upload large files.
+//! [`Mer`](crate::Mer) passes uploads from [`Frontend::register_upload`](crate::interfaces::Frontend::register_upload) through the [`Middleware`](crate::interfaces::Middleware)s to [`Backend::call_upload`](crate::interfaces::Backend::call_upload) and on the server side to the [`Upload`](crate::Upload) returned by [`Frontend::receive_upload`](crate::interfaces::Frontend::receive_upload).
+//! The [`Backend`](crate::interfaces::Backend)s apply flow control, so the client side blocks while the server side has not consumed the chunks sent before. The chunks are not passed through the [`Middleware`](crate::interfaces::Middleware)s.
+//!
+//! # Batches
+//! A [`Call`](crate::Call) to the reserved procedure [`rpc.batch`](crate::BATCH) carries several [`Call`](crate::Call)s in a single backend message, so every [`Backend`](crate::interfaces::Backend) supports batches natively.
+//! [`Mer`](crate::Mer) and [`AsyncMer`](crate::AsyncMer) pass each [`Call`](crate::Call) of the batch through the [`Middleware`](crate::interfaces::Middleware)s and the [`Frontend`](crate::interfaces::Frontend) on its own and reply with a [`BatchReply`](crate::BatchReply) per [`Call`](crate::Call) in the same order.
+//! A failing [`Call`](crate::Call) does not fail the other [`Call`](crate::Call)s of the batch.
+//!
+//! # Codecs
+//! The format of the packages is selected by a [`Codec`](crate::interfaces::Codec) the [`Backend`](crate::interfaces::Backend)s are generic over. Its [`Intermediate`](crate::interfaces::Codec::Intermediate) type is `String` for text formats and `Vec<u8>` for binary formats.
+//! The [`Backend`](crate::interfaces::Backend)s keep their previous format as default and are built with a different [`Codec`](crate::interfaces::Codec) using `builder_with_codec`, e.g. `SerialPort::<Postcard>::builder_with_codec()`.
+//! Line based transports carry binary [`Codec`](crate::interfaces::Codec)s as hexadecimal text (see [`Encoded`](crate::interfaces::Encoded)).
+//!
 //! # Provided Modules
 //! | Type                                                      | Name                                                                    | Description |
 //! |-----------------------------------------------------------|-------------------------------------------------------------------------|-------------|
+//! | [`Backend`](crate::interfaces::backend::Backend)          | [`Embedded`](https://docs.rs/merfolk_backend_embedded)                  | Communicates via any [`embedded-io`](https://docs.rs/embedded-io) port in `no_std` firmware, wire-compatible with the [`SerialPort`](https://docs.rs/merfolk_backend_serialport) backend. |
 //! | [`Backend`](crate::interfaces::backend::Backend)          | [`Http`](https://docs.rs/merfolk_backend_http)                          | Communicates via Http and in `json` format. |
 //! | [`Backend`](crate::interfaces::backend::Backend)          | [`InProcess`](https://docs.rs/merfolk_backend_in_process)               | Communicates via [`tokio`](https://docs.rs/tokio) [`channels`](https://docs.rs/tokio/1.2.0/tokio/sync/mpsc/fn.channel.html) in `json` format (mostly used for testing purposes). |
-//! | [`Backend`](crate::interfaces::backend::Backend)          | [`SerialPort`](https://docs.rs/merfolk_backend_serialport)              | Communicates via serial port (using the [`serialport`](https://docs.rs/serialport) library) in [`ron`](https://docs.rs/ron) format. |
+//! | [`Backend`](crate::interfaces::backend::Backend)          | [`SerialPort`](https://docs.rs/merfolk_backend_serialport)              | Communicates via serial port (using the [`serialport`](https://docs.rs/serialport) library) in [`ron`](https://docs.rs/ron) format by default, in text lines or COBS or length prefixed binary frames with a CRC, optionally addressing nodes on a multi-drop bus (e.g. RS-485) and reopening unplugged ports. |
+//! | [`Backend`](crate::interfaces::backend::Backend)          | [`Tcp`](https://docs.rs/merfolk_backend_tcp)                            | Communicates via persistent TCP connections in length prefixed frames in `json` format by default, multiplexing concurrent calls and reconnecting closed connections. |
+//! | [`Codec`](crate::interfaces::Codec)                       | [`Codecs`](https://docs.rs/merfolk_codecs)                              | Provides `Json`, `Ron`, `MessagePack`, `Cbor`, `Bincode` and `Postcard` codecs. |
 //! | [`Frontend`](crate::interfaces::frontend::Frontend)       | [`Derive`](https://docs.rs/merfolk_frontend_derive)                     | Provides derive macros to derive a frontend from trait definitions. |
 //! | [`Frontend`](crate::interfaces::frontend::Frontend)       | [`Duplex`](https://docs.rs/merfolk_frontend_duplex)                     | Allows for different frontends for calling and receiving RPCs. |
 //! | [`Frontend`](crate::interfaces::frontend::Frontend)       | [`Logger`](https://docs.rs/merfolk_frontend_logger)                     | Provides a frontend using the [`log`](https://docs.rs/log) facade on the client side. |
 //! | [`Frontend`](crate::interfaces::frontend::Frontend)       | [`Register`](https://docs.rs/merfolk_frontend_register)                 | Allows for manually registering procedures on the server side and calling any procedure on the client side. |
 //! | [`Middleware`](crate::interfaces::middleware::Middleware) | [`Authentication`](https://docs.rs/merfolk_middleware_authentication)   | Adds simple authentication and scopes. |
 //! | [`Middleware`](crate::interfaces::middleware::Middleware) | [`Router`](https://docs.rs/merfolk_middleware_router)                   | Adds simple routing of procedures based on the procedure name. |
+//! | Generator                                                 | [`TypeScript`](https://docs.rs/merfolk_generator_typescript)            | Generates TypeScript clients for the [`Http`](https://docs.rs/merfolk_backend_http) backend from the schema of [`Derive`](https://docs.rs/merfolk_frontend_derive) frontends. |
 //!
 //!
 //!
@@ -202,16 +236,45 @@
 
 extern crate alloc;
 
+#[cfg(feature = "std")]
+pub mod adapters;
+
 pub mod helpers;
 
 pub mod interfaces;
 
+mod async_mer;
+
+pub use async_mer::{AsyncMer, AsyncMerBuilder};
+
+mod batch;
+
+pub use batch::{BatchReply, BATCH};
+
+mod introspection;
+
+pub use introspection::{ArgumentDescription, ProcedureDescription};
+
+mod metadata;
+
+pub use metadata::Metadata;
+
+mod remote_error;
+
+pub use remote_error::RemoteError;
+
+mod stream;
+
+pub use stream::{CallStream, ReplyStream, Upload};
+
 #[cfg(test)]
 mod test;
 
 #[cfg(not(feature = "std"))]
 use alloc::{boxed::Box, string::String, vec::Vec};
 
+use core::{fmt, time::Duration};
+
 use anyhow::Result;
 use helpers::smart_lock::SmartLock;
 use log::trace;
@@ -234,26 +297,45 @@ pub enum Error {
   MiddlewareIndex(usize),
   #[cfg_attr(feature = "std", error("middleware could not be downcast"))]
   DowncastError,
+  #[cfg_attr(feature = "std", error("call to {procedure} timed out after {timeout:?}"))]
+  Timeout { procedure: String, timeout: Duration },
+  #[cfg_attr(feature = "std", error("{0} are not supported"))]
+  Unsupported(String),
+  #[cfg_attr(feature = "std", error("intermediate is not valid utf-8: {0}"))]
+  Decode(core::str::Utf8Error),
+  #[cfg_attr(feature = "std", error("intermediate is not valid hexadecimal"))]
+  Hex,
+}
+
+#[cfg(not(feature = "std"))]
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    fmt::Debug::fmt(self, f)
+  }
 }
 
 #[cfg(not(feature = "std"))]
 impl From<Error> for anyhow::Error {
   fn from(e: Error) -> Self {
-    anyhow::anyhow!(e)
+    anyhow::Error::msg(e)
   }
 }
 
-#[derive(Debug)]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
 /// Datastructure for outgoing and incoming RPC Calls.
 pub struct Call<T> {
   pub procedure: String,
   pub payload: T,
+  #[serde(default)]
+  pub metadata: Metadata,
 }
 
-#[derive(Debug)]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
 /// Data structure for outgoing and incoming RPC Replies.
 pub struct Reply<T> {
   pub payload: T,
+  #[serde(default)]
+  pub metadata: Metadata,
 }
 
 #[derive(derive_builder::Builder)]
@@ -275,12 +357,17 @@ where
   #[allow(dead_code)]
   #[builder(setter(into, name = "middlewares_setter"), private)]
   middlewares: SmartLock<Vec<Box<dyn interfaces::Middleware<Backend = B>>>>,
+
+  /// Default timeout for outgoing [`Call`]s which do not have a timeout set.
+  #[builder(setter(strip_option))]
+  timeout: Option<Duration>,
 }
 
 impl<B, F> MerBuilder<B, F>
 where
   B: interfaces::Backend,
   B: 'static,
+  B::Intermediate: Clone + fmt::Debug + Send + Sync + 'static,
   F: interfaces::Frontend<Backend = B>,
   F: 'static,
 {
@@ -305,6 +392,7 @@ where
     let backend = self.backend.ok_or_else(|| Error::Init("backend".into()))?;
     let frontend = self.frontend.ok_or_else(|| Error::Init("frontend".into()))?;
     let middlewares = self.middlewares.unwrap_or_default();
+    let timeout = self.timeout.flatten();
 
     let frontend_backend = clone_lock!(frontend);
     let middlewares_backend = clone_lock!(middlewares);
@@ -316,25 +404,64 @@ where
       .map_err::<anyhow::Error, _>(|_| Error::Lock.into())?
       .register(move |call: Call<B::Intermediate>| {
         trace!("Mer.backend.register()");
-        let middlewares_inner = access!(middlewares_backend).map_err::<anyhow::Error, _>(|_| Error::Lock.into())?;
-        let unwrapped = middlewares_inner.iter().fold(Ok(call), |acc, m| m.unwrap_call(acc));
-
-        let reply = match unwrapped {
-          Ok(unwrapped_ok) => access!(frontend_backend).map_err::<anyhow::Error, _>(|_| Error::Lock.into())?.receive(unwrapped_ok),
-          Err(err) => Err(err),
+        let receive = |call: Call<B::Intermediate>| {
+          let middlewares_inner = access!(middlewares_backend).map_err::<anyhow::Error, _>(|_| Error::Lock.into())?;
+          let unwrapped = middlewares_inner.iter().try_fold(call, |acc, m| m.unwrap_call(Ok(acc)));
+
+          let reply = match unwrapped {
+            Ok(unwrapped_ok) if unwrapped_ok.procedure == ProcedureDescription::DISCOVER => {
+              let procedures = access!(frontend_backend).map_err::<anyhow::Error, _>(|_| Error::Lock.into())?.procedures();
+              B::serialize(&procedures).map(|payload| Reply { payload, metadata: Metadata::new() })
+            }
+            Ok(unwrapped_ok) => access!(frontend_backend).map_err::<anyhow::Error, _>(|_| Error::Lock.into())?.receive(unwrapped_ok),
+            Err(err) => Err(err),
+          };
+
+          middlewares_inner.iter().fold(reply, |acc, m| m.wrap_reply(acc))
         };
 
-        middlewares_inner.iter().fold(reply, |acc, m| m.wrap_reply(acc))
+        match call.procedure == BATCH {
+          true => {
+            let calls: Vec<Call<B::Intermediate>> = B::deserialize(&call.payload)?;
+            let replies = batch::batch_replies(calls.into_iter().map(receive).collect());
+            B::serialize(&replies).map(|payload| Reply { payload, metadata: Metadata::new() })
+          }
+          false => receive(call),
+        }
       })
       .map_err::<anyhow::Error, _>(|e| Error::Register { source: e, end: "backend".into() }.into())?;
 
     access!(frontend)
       .map_err::<anyhow::Error, _>(|_| Error::Lock.into())?
-      .register(move |call: Call<B::Intermediate>| {
+      .register(move |mut call: Call<B::Intermediate>| {
         trace!("Mer.frontend.register()");
+        if let (Some(timeout), None) = (timeout, call.metadata.timeout()) {
+          call.metadata.set_timeout(timeout);
+        }
+
         let middlewares_inner = access!(middlewares_frontend).map_err::<anyhow::Error, _>(|_| Error::Lock.into())?;
 
-        let wrapped = middlewares_inner.iter().rev().fold(Ok(call), |acc, m| m.wrap_call(acc));
+        if call.procedure == BATCH {
+          let calls: Vec<Call<B::Intermediate>> = B::deserialize(&call.payload)?;
+          let procedures = calls.iter().map(|c| c.procedure.clone()).collect();
+          let wrapped = calls.into_iter().map(|c| middlewares_inner.iter().rev().try_fold(c, |acc, m| m.wrap_call(Ok(acc)))).collect();
+          let (calls, pending) = batch::Pending::new(wrapped, procedures, call.metadata.timeout());
+
+          let reply = access!(backend_frontend).map_err::<anyhow::Error, _>(|_| Error::Lock.into())?.call(Call {
+            procedure: call.procedure,
+            payload: B::serialize(&calls)?,
+            metadata: call.metadata,
+          })?;
+
+          let replies = pending
+            .replies(B::deserialize(&reply.payload)?)?
+            .into_iter()
+            .map(|r| middlewares_inner.iter().fold(r, |acc, m| m.unwrap_reply(acc)))
+            .collect();
+          return B::serialize(&batch::batch_replies(replies)).map(|payload| Reply { payload, metadata: reply.metadata });
+        }
+
+        let wrapped = middlewares_inner.iter().rev().try_fold(call, |acc, m| m.wrap_call(Ok(acc)));
 
         let reply = match wrapped {
           Ok(wrapped_ok) => access!(backend_frontend).map_err::<anyhow::Error, _>(|_| Error::Lock.into())?.call(wrapped_ok),
@@ -345,10 +472,93 @@ where
       })
       .map_err::<anyhow::Error, _>(|e| Error::Register { source: e, end: "frontend".into() }.into())?;
 
+    let frontend_stream_backend = clone_lock!(frontend);
+    let middlewares_stream_backend = clone_lock!(middlewares);
+
+    access!(backend)
+      .map_err::<anyhow::Error, _>(|_| Error::Lock.into())?
+      .register_stream(move |call: Call<B::Intermediate>| {
+        trace!("Mer.backend.register_stream()");
+        let middlewares_inner = access!(middlewares_stream_backend).map_err::<anyhow::Error, _>(|_| Error::Lock.into())?;
+        let unwrapped = middlewares_inner.iter().try_fold(call, |acc, m| m.unwrap_call(Ok(acc)))?;
+
+        access!(frontend_stream_backend).map_err::<anyhow::Error, _>(|_| Error::Lock.into())?.receive_stream(unwrapped)
+      })
+      .map_err::<anyhow::Error, _>(|e| Error::Register { source: e, end: "backend".into() }.into())?;
+
+    let backend_stream_frontend = clone_lock!(backend);
+    let middlewares_stream_frontend = clone_lock!(middlewares);
+
+    access!(frontend)
+      .map_err::<anyhow::Error, _>(|_| Error::Lock.into())?
+      .register_stream(move |mut call: Call<B::Intermediate>| {
+        trace!("Mer.frontend.register_stream()");
+        if let (Some(timeout), None) = (timeout, call.metadata.timeout()) {
+          call.metadata.set_timeout(timeout);
+        }
+        call.metadata.set_stream();
+
+        let middlewares_inner = access!(middlewares_stream_frontend).map_err::<anyhow::Error, _>(|_| Error::Lock.into())?;
+        let wrapped = middlewares_inner.iter().rev().try_fold(call, |acc, m| m.wrap_call(Ok(acc)))?;
+
+        access!(backend_stream_frontend).map_err::<anyhow::Error, _>(|_| Error::Lock.into())?.call_stream(wrapped)
+      })
+      .map_err::<anyhow::Error, _>(|e| Error::Register { source: e, end: "frontend".into() }.into())?;
+
+    let frontend_upload_backend = clone_lock!(frontend);
+    let middlewares_upload_backend = clone_lock!(middlewares);
+
+    access!(backend)
+      .map_err::<anyhow::Error, _>(|_| Error::Lock.into())?
+      .register_upload(move |call: Call<B::Intermediate>, chunks: CallStream<B::Intermediate>| {
+        trace!("Mer.backend.register_upload()");
+        let unwrapped = access!(middlewares_upload_backend)
+          .map_err::<anyhow::Error, _>(|_| Error::Lock.into())?
+          .iter()
+          .try_fold(call, |acc, m| m.unwrap_call(Ok(acc)));
+
+        let upload = match unwrapped {
+          Ok(unwrapped_ok) => access!(frontend_upload_backend).map_err::<anyhow::Error, _>(|_| Error::Lock.into())?.receive_upload(unwrapped_ok),
+          Err(err) => Err(err),
+        };
+        let reply = upload.and_then(|upload| upload(chunks));
+
+        access!(middlewares_upload_backend)
+          .map_err::<anyhow::Error, _>(|_| Error::Lock.into())?
+          .iter()
+          .fold(reply, |acc, m| m.wrap_reply(acc))
+      })
+      .map_err::<anyhow::Error, _>(|e| Error::Register { source: e, end: "backend".into() }.into())?;
+
+    let backend_upload_frontend = clone_lock!(backend);
+    let middlewares_upload_frontend = clone_lock!(middlewares);
+
+    access!(frontend)
+      .map_err::<anyhow::Error, _>(|_| Error::Lock.into())?
+      .register_upload(move |mut call: Call<B::Intermediate>, chunks: CallStream<B::Intermediate>| {
+        trace!("Mer.frontend.register_upload()");
+        if let (Some(timeout), None) = (timeout, call.metadata.timeout()) {
+          call.metadata.set_timeout(timeout);
+        }
+        call.metadata.set_upload();
+
+        let middlewares_inner = access!(middlewares_upload_frontend).map_err::<anyhow::Error, _>(|_| Error::Lock.into())?;
+        let wrapped = middlewares_inner.iter().rev().try_fold(call, |acc, m| m.wrap_call(Ok(acc)));
+
+        let reply = match wrapped {
+          Ok(wrapped_ok) => access!(backend_upload_frontend).map_err::<anyhow::Error, _>(|_| Error::Lock.into())?.call_upload(wrapped_ok, chunks),
+          Err(err) => Err(err),
+        };
+
+        middlewares_inner.iter().fold(reply, |acc, m| m.unwrap_reply(acc))
+      })
+      .map_err::<anyhow::Error, _>(|e| Error::Register { source: e, end: "frontend".into() }.into())?;
+
     Ok(Mer {
       backend: clone_lock!(backend),
       frontend: clone_lock!(frontend),
       middlewares: clone_lock!(middlewares),
+      timeout,
     })
   }
 }
@@ -446,4 +656,9 @@ impl<B: interfaces::Backend + 'static, F: interfaces::Frontend<Backend = B>> Mer
 
     Ok(access(middleware))
   }
+
+  /// Returns the default timeout for outgoing [`Call`]s.
+  pub fn timeout(&self) -> Option<Duration> {
+    self.timeout
+  }
 }
@@ -0,0 +1,141 @@
+use alloc::collections::BTreeMap;
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
+use core::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+/// Metadata attached to [`Call`](crate::Call)s and [`Reply`](crate::Reply)s.
+///
+/// The [`Metadata`] is a map of `String` keys to `String` values which is carried alongside the payload by the [`Backend`](crate::interfaces::Backend).
+/// [`Middleware`](crate::interfaces::Middleware)s can use it to attach information (e.g. credentials) without modifying the payload.
+///
+/// Keys are case insensitive and stored in lowercase so they survive transports like Http headers.
+///
+/// ```
+/// # use merfolk::Metadata;
+/// let mut metadata = Metadata::new();
+/// metadata.insert("Trace-Id", "42");
+///
+/// assert_eq!(metadata.get("trace-id"), Some("42"));
+/// assert_eq!(metadata.get_as::<i32>("trace-id"), Some(42));
+/// ```
+pub struct Metadata(BTreeMap<String, String>);
+
+impl Metadata {
+  /// Key of the timeout of a [`Call`](crate::Call) in milliseconds.
+  pub const TIMEOUT: &'static str = "timeout";
+  /// Key marking a [`Call`](crate::Call) as notification which is not answered with a [`Reply`](crate::Reply).
+  pub const NOTIFICATION: &'static str = "notification";
+  /// Key marking a [`Call`](crate::Call) as streaming which is answered with a [`ReplyStream`](crate::ReplyStream).
+  pub const STREAM: &'static str = "stream";
+  /// Key marking a [`Call`](crate::Call) as upload which is followed by the chunks of a [`CallStream`](crate::CallStream).
+  pub const UPLOAD: &'static str = "upload";
+
+  /// Creates empty [`Metadata`].
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Returns the value for `key`.
+  pub fn get(&self, key: &str) -> Option<&str> {
+    self.0.get(&key.to_lowercase()).map(|v| v.as_str())
+  }
+
+  /// Returns the value for `key` parsed to `T`.
+  pub fn get_as<T: core::str::FromStr>(&self, key: &str) -> Option<T> {
+    self.get(key).and_then(|v| v.parse().ok())
+  }
+
+  /// Inserts `value` for `key` returning the previous value.
+  pub fn insert<K: Into<String>, V: ToString>(&mut self, key: K, value: V) -> Option<String> {
+    self.0.insert(key.into().to_lowercase(), value.to_string())
+  }
+
+  /// Removes `key` returning its value.
+  pub fn remove(&mut self, key: &str) -> Option<String> {
+    self.0.remove(&key.to_lowercase())
+  }
+
+  /// Returns `true` if `key` is present.
+  pub fn contains_key(&self, key: &str) -> bool {
+    self.0.contains_key(&key.to_lowercase())
+  }
+
+  /// Returns the number of entries.
+  pub fn len(&self) -> usize {
+    self.0.len()
+  }
+
+  /// Returns `true` if there are no entries.
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  /// Returns the timeout of the [`Call`](crate::Call).
+  pub fn timeout(&self) -> Option<Duration> {
+    self.get_as::<u64>(Self::TIMEOUT).map(Duration::from_millis)
+  }
+
+  /// Sets the timeout of the [`Call`](crate::Call).
+  ///
+  /// The timeout is relative to when the [`Call`](crate::Call) is made so it does not depend on synchronized clocks when sent to the server side.
+  /// For streaming [`Call`](crate::Call)s every item of the [`ReplyStream`](crate::ReplyStream) has to be received within the timeout after the previous one.
+  pub fn set_timeout(&mut self, timeout: Duration) {
+    self.insert(Self::TIMEOUT, timeout.as_millis());
+  }
+
+  /// Returns `true` if the [`Call`](crate::Call) is a notification.
+  pub fn is_notification(&self) -> bool {
+    self.get_as::<bool>(Self::NOTIFICATION).unwrap_or(false)
+  }
+
+  /// Marks the [`Call`](crate::Call) as notification.
+  ///
+  /// [`Backend`](crate::interfaces::Backend)s send notifications without waiting for the [`Reply`](crate::Reply) of the server side and return an empty [`Reply`](crate::Reply) immediately.
+  /// The server side runs the procedure but does not send its [`Reply`](crate::Reply) or errors back.
+  pub fn set_notification(&mut self) {
+    self.insert(Self::NOTIFICATION, true);
+  }
+
+  /// Returns `true` if the [`Call`](crate::Call) is streaming.
+  pub fn is_stream(&self) -> bool {
+    self.get_as::<bool>(Self::STREAM).unwrap_or(false)
+  }
+
+  /// Marks the [`Call`](crate::Call) as streaming.
+  ///
+  /// The server side answers the [`Call`](crate::Call) with the items of a [`ReplyStream`](crate::ReplyStream) instead of a single [`Reply`](crate::Reply).
+  pub fn set_stream(&mut self) {
+    self.insert(Self::STREAM, true);
+  }
+
+  /// Returns `true` if the [`Call`](crate::Call) is an upload.
+  pub fn is_upload(&self) -> bool {
+    self.get_as::<bool>(Self::UPLOAD).unwrap_or(false)
+  }
+
+  /// Marks the [`Call`](crate::Call) as upload.
+  ///
+  /// The payload of the [`Call`](crate::Call) is followed by the chunks of a [`CallStream`](crate::CallStream) which are passed to the procedure as they arrive.
+  pub fn set_upload(&mut self) {
+    self.insert(Self::UPLOAD, true);
+  }
+
+  /// Iterates over all entries ordered by key.
+  pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+    self.0.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+  }
+}
+
+impl<K: Into<String>, V: ToString> FromIterator<(K, V)> for Metadata {
+  fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+    let mut metadata = Self::new();
+    for (key, value) in iter {
+      metadata.insert(key, value);
+    }
+    metadata
+  }
+}
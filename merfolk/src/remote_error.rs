@@ -0,0 +1,131 @@
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
+use core::{fmt, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>"))]
+/// Serializable error returned by the server side of a [`Call`](crate::Call).
+///
+/// Every [`Backend`](crate::interfaces::Backend) carries the [`RemoteError`] to the client side where it can be accessed with `downcast_ref::<RemoteError>()`.
+/// The `code` allows to tell different kinds of errors apart. The codes from `-32768` to `-32000` are reserved for [`merfolk`](crate), all other codes can be used by applications.
+/// The optional `data` has the `Intermediate` type of the [`Backend`](crate::interfaces::Backend) and can carry any serialized value.
+///
+/// ```
+/// # use merfolk::RemoteError;
+/// let error: anyhow::Error = RemoteError::new(RemoteError::PROCEDURE_NOT_FOUND, "procedure add is not registered").into();
+///
+/// assert_eq!(error.downcast_ref::<RemoteError>().unwrap().code, RemoteError::PROCEDURE_NOT_FOUND);
+/// ```
+pub struct RemoteError<T = String> {
+  pub code: i64,
+  pub message: String,
+  #[serde(default)]
+  pub data: Option<T>,
+}
+
+impl RemoteError {
+  /// The received [`Call`](crate::Call) could not be parsed.
+  pub const PARSE_ERROR: i64 = -32700;
+  /// The called procedure does not exist.
+  pub const PROCEDURE_NOT_FOUND: i64 = -32601;
+  /// The payload of the [`Call`](crate::Call) could not be deserialized to the arguments of the procedure.
+  pub const INVALID_PARAMS: i64 = -32602;
+  /// An internal error occurred.
+  pub const INTERNAL_ERROR: i64 = -32603;
+  /// The procedure or the server failed with an error without a code.
+  pub const SERVER_ERROR: i64 = -32000;
+  /// The authentication of the [`Call`](crate::Call) failed.
+  pub const AUTHENTICATION_FAILED: i64 = -32001;
+  /// The [`Call`](crate::Call) timed out on the server side.
+  pub const TIMEOUT: i64 = -32002;
+  /// The procedure returned an application error which is serialized in `data`.
+  pub const APPLICATION_ERROR: i64 = -32003;
+
+  /// Creates a new [`RemoteError`] without `data`.
+  pub fn new<M: ToString>(code: i64, message: M) -> Self {
+    Self {
+      code,
+      message: message.to_string(),
+      data: None,
+    }
+  }
+}
+
+impl<T> RemoteError<T> {
+  /// Adds `data` to the [`RemoteError`].
+  pub fn with_data<D>(self, data: D) -> RemoteError<D> {
+    RemoteError {
+      code: self.code,
+      message: self.message,
+      data: Some(data),
+    }
+  }
+
+  /// Converts the [`RemoteError`] into an [`anyhow::Error`] on the client side.
+  ///
+  /// A [`TIMEOUT`](RemoteError::TIMEOUT) of a [`Call`](crate::Call) with a `timeout` is converted to [`Error::Timeout`] so it is handled the same way as a timeout on the client side.
+  pub fn into_error(self, procedure: &str, timeout: Option<Duration>) -> anyhow::Error
+  where
+    T: fmt::Debug + Send + Sync + 'static,
+  {
+    match (self.code, timeout) {
+      (RemoteError::TIMEOUT, Some(timeout)) => Error::Timeout {
+        procedure: procedure.to_string(),
+        timeout,
+      }
+      .into(),
+      _ => self.into(),
+    }
+  }
+}
+
+impl<T> fmt::Display for RemoteError<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "remote error {}: {}", self.code, self.message)
+  }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug> std::error::Error for RemoteError<T> {}
+
+#[cfg(not(feature = "std"))]
+impl<T: fmt::Debug + Send + Sync + 'static> From<RemoteError<T>> for anyhow::Error {
+  fn from(e: RemoteError<T>) -> Self {
+    anyhow::Error::msg(e)
+  }
+}
+
+impl<T: Clone + fmt::Debug + Send + Sync + 'static> From<anyhow::Error> for RemoteError<T> {
+  /// Converts a server side error to a [`RemoteError`].
+  ///
+  /// A [`RemoteError`] is passed through (without `data` if the type of the `data` differs), [`Error::Timeout`] is converted to a [`TIMEOUT`](RemoteError::TIMEOUT) and any other error to a [`SERVER_ERROR`](RemoteError::SERVER_ERROR).
+  fn from(e: anyhow::Error) -> Self {
+    if let Some(remote) = e.downcast_ref::<RemoteError<T>>() {
+      return remote.clone();
+    }
+
+    if let Some(remote) = e.downcast_ref::<RemoteError>() {
+      return Self {
+        code: remote.code,
+        message: remote.message.clone(),
+        data: None,
+      };
+    }
+
+    let code = if let Some(Error::Timeout { .. }) = e.downcast_ref::<Error>() {
+      RemoteError::TIMEOUT
+    } else {
+      RemoteError::SERVER_ERROR
+    };
+
+    Self {
+      code,
+      message: e.to_string(),
+      data: None,
+    }
+  }
+}
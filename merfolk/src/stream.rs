@@ -0,0 +1,132 @@
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+use core::fmt;
+
+use anyhow::Result;
+
+/// Streamed reply of a [`Call`](crate::Call) marked with [`Metadata::set_stream`](crate::Metadata::set_stream).
+///
+/// The items are produced by the server side one after another and are received by the client side as they arrive, so the whole result does not have to be buffered.
+/// A failing item does not end the stream.
+///
+/// ```
+/// # use merfolk::ReplyStream;
+/// let mut stream = ReplyStream::new((0..3).map(|i| Ok(i.to_string())));
+///
+/// assert_eq!(stream.next().unwrap().unwrap(), "0");
+/// assert_eq!(stream.count(), 2);
+/// ```
+pub struct ReplyStream<T> {
+  items: Box<dyn Iterator<Item = Result<T>> + Send>,
+}
+
+impl<T> ReplyStream<T> {
+  /// Creates a new [`ReplyStream`] yielding the `items`.
+  pub fn new<I>(items: I) -> Self
+  where
+    I: IntoIterator<Item = Result<T>>,
+    I::IntoIter: Send + 'static,
+  {
+    Self { items: Box::new(items.into_iter()) }
+  }
+
+  /// Creates a new [`ReplyStream`] yielding the single `item`.
+  ///
+  /// Used to answer streaming [`Call`](crate::Call)s to procedures which do not stream their reply.
+  pub fn once(item: Result<T>) -> Self
+  where
+    T: Send + 'static,
+  {
+    Self::new(core::iter::once(item))
+  }
+
+  /// Creates a new [`ReplyStream`] yielding the items received on the `receiver` and converted with `convert`.
+  ///
+  /// Every item has to be received within the `timeout` after the previous one, otherwise the stream ends with [`Error::Timeout`](crate::Error::Timeout).
+  #[cfg(feature = "std")]
+  pub fn receive<I, F>(receiver: std::sync::mpsc::Receiver<I>, procedure: String, timeout: Option<core::time::Duration>, mut convert: F) -> Self
+  where
+    I: Send + 'static,
+    T: Send + 'static,
+    F: FnMut(I) -> Result<T> + Send + 'static,
+  {
+    let mut timed_out = false;
+    Self::new(core::iter::from_fn(move || {
+      if timed_out {
+        return None;
+      }
+
+      let item = match timeout {
+        None => receiver.recv().ok()?,
+        Some(timeout) => match receiver.recv_timeout(timeout) {
+          Ok(item) => item,
+          Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => return None,
+          Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+            timed_out = true;
+            return Some(Err(
+              crate::Error::Timeout {
+                procedure: procedure.clone(),
+                timeout,
+              }
+              .into(),
+            ));
+          }
+        },
+      };
+
+      Some(convert(item))
+    }))
+  }
+}
+
+impl<T> Iterator for ReplyStream<T> {
+  type Item = Result<T>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.items.next()
+  }
+}
+
+impl<T> fmt::Debug for ReplyStream<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("ReplyStream").finish_non_exhaustive()
+  }
+}
+
+/// Chunks uploaded with a [`Call`](crate::Call) marked with [`Metadata::set_upload`](crate::Metadata::set_upload).
+///
+/// The chunks are sent by the client side one after another and are consumed by the server side as they arrive.
+/// [`Backend`](crate::interfaces::Backend)s apply flow control so the client side does not send chunks faster than the server side consumes them.
+pub struct CallStream<T> {
+  chunks: Box<dyn Iterator<Item = Result<T>> + Send>,
+}
+
+impl<T> CallStream<T> {
+  /// Creates a new [`CallStream`] yielding the `chunks`.
+  pub fn new<I>(chunks: I) -> Self
+  where
+    I: IntoIterator<Item = Result<T>>,
+    I::IntoIter: Send + 'static,
+  {
+    Self { chunks: Box::new(chunks.into_iter()) }
+  }
+}
+
+impl<T> Iterator for CallStream<T> {
+  type Item = Result<T>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.chunks.next()
+  }
+}
+
+impl<T> fmt::Debug for CallStream<T> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("CallStream").finish_non_exhaustive()
+  }
+}
+
+/// Handler of an upload returned by [`Frontend::receive_upload`](crate::interfaces::Frontend::receive_upload).
+///
+/// The handler consumes the [`CallStream`] of the upload and returns the [`Reply`](crate::Reply). It runs without holding any lock of [`Mer`](crate::Mer) so long uploads do not block other [`Call`](crate::Call)s.
+pub type Upload<T> = Box<dyn FnOnce(CallStream<T>) -> Result<crate::Reply<T>> + Send>;
@@ -4,8 +4,10 @@ use alloc::string::String;
 use anyhow::Result;
 
 use crate::{
-  interfaces::{Backend, Frontend},
-  Call, Mer, Reply,
+  adapters::{Async, Blocking},
+  helpers::future::{block_on, BoxFuture},
+  interfaces::{AsyncBackend, AsyncFrontend, Backend, Frontend},
+  AsyncMer, Call, Error, Mer, Metadata, RemoteError, Reply,
 };
 
 struct MockBackend {}
@@ -21,7 +23,10 @@ impl Backend for MockBackend {
   }
 
   fn call(&mut self, call: Call<Self::Intermediate>) -> Result<Reply<Self::Intermediate>> {
-    Ok(Reply { payload: call.payload })
+    Ok(Reply {
+      payload: call.payload,
+      metadata: call.metadata,
+    })
   }
 
   fn serialize<T: serde::Serialize>(from: &T) -> Result<Self::Intermediate> {
@@ -49,7 +54,10 @@ impl Frontend for MockFrontend {
   }
 
   fn receive(&self, call: Call<<Self::Backend as Backend>::Intermediate>) -> Result<Reply<<Self::Backend as Backend>::Intermediate>> {
-    Ok(Reply { payload: call.payload })
+    Ok(Reply {
+      payload: call.payload,
+      metadata: call.metadata,
+    })
   }
 }
 
@@ -82,3 +90,159 @@ fn frontend() {
 fn backend() {
   setup().backend(|_b| ()).unwrap();
 }
+
+struct MockAsyncBackend {}
+
+impl AsyncBackend for MockAsyncBackend {
+  type Intermediate = String;
+
+  fn register<T>(&mut self, _receiver: T) -> Result<()>
+  where
+    T: Fn(Call<Self::Intermediate>) -> BoxFuture<'static, Result<Reply<Self::Intermediate>>> + Send + Sync + 'static,
+  {
+    Ok(())
+  }
+
+  fn call(&self, call: Call<Self::Intermediate>) -> BoxFuture<'_, Result<Reply<Self::Intermediate>>> {
+    Box::pin(async move {
+      Ok(Reply {
+        payload: call.payload,
+        metadata: call.metadata,
+      })
+    })
+  }
+
+  fn serialize<T: serde::Serialize>(from: &T) -> Result<Self::Intermediate> {
+    serde_json::to_string(from).map_err(|e| e.into())
+  }
+
+  fn deserialize<'b, T>(from: &'b Self::Intermediate) -> Result<T>
+  where
+    T: for<'de> serde::Deserialize<'de>,
+  {
+    serde_json::from_str(from).map_err(|e| e.into())
+  }
+}
+
+struct MockAsyncFrontend {}
+
+impl AsyncFrontend for MockAsyncFrontend {
+  type Backend = MockAsyncBackend;
+
+  fn register<T>(&mut self, _caller: T) -> Result<()>
+  where
+    T: Fn(Call<<Self::Backend as AsyncBackend>::Intermediate>) -> BoxFuture<'static, Result<Reply<<Self::Backend as AsyncBackend>::Intermediate>>> + Send + Sync + 'static,
+  {
+    Ok(())
+  }
+
+  fn receive(&self, call: Call<<Self::Backend as AsyncBackend>::Intermediate>) -> BoxFuture<'_, Result<Reply<<Self::Backend as AsyncBackend>::Intermediate>>> {
+    Box::pin(async move {
+      Ok(Reply {
+        payload: call.payload,
+        metadata: call.metadata,
+      })
+    })
+  }
+}
+
+fn setup_async() -> AsyncMer<MockAsyncBackend, MockAsyncFrontend> {
+  crate::AsyncMerBuilder::<MockAsyncBackend, MockAsyncFrontend>::default()
+    .backend(MockAsyncBackend {})
+    .frontend(MockAsyncFrontend {})
+    .build()
+    .unwrap()
+}
+
+#[test]
+fn async_init() {
+  setup_async();
+}
+
+#[test]
+fn async_backend() {
+  let rnd: i32 = rand::random();
+  let merfolk = setup_async();
+
+  let reply = block_on(merfolk.backend().call(Call {
+    procedure: "".into(),
+    payload: MockAsyncBackend::serialize(&rnd).unwrap(),
+    metadata: Metadata::new(),
+  }))
+  .unwrap();
+
+  assert_eq!(MockAsyncBackend::deserialize::<i32>(&reply.payload).unwrap(), rnd);
+}
+
+#[test]
+fn adapter_async() {
+  let rnd: i32 = rand::random();
+  let backend = Async::new(MockBackend {});
+
+  let reply = block_on(AsyncBackend::call(
+    &backend,
+    Call {
+      procedure: "".into(),
+      payload: rnd.to_string(),
+      metadata: vec![("Random", rnd)].into_iter().collect(),
+    },
+  ))
+  .unwrap();
+
+  assert_eq!(reply.payload, rnd.to_string());
+  assert_eq!(reply.metadata.get_as::<i32>("random"), Some(rnd));
+}
+
+#[test]
+fn adapter_blocking() {
+  let rnd: i32 = rand::random();
+  let mut backend = Blocking::new(MockAsyncBackend {});
+
+  let reply = Backend::call(
+    &mut backend,
+    Call {
+      procedure: "".into(),
+      payload: rnd.to_string(),
+      metadata: Metadata::new(),
+    },
+  )
+  .unwrap();
+
+  assert_eq!(reply.payload, rnd.to_string());
+}
+
+#[test]
+fn metadata() {
+  let rnd: i32 = rand::random();
+  let mut metadata = Metadata::new();
+
+  assert_eq!(metadata.insert("Random", rnd), None);
+  assert_eq!(metadata.insert("RANDOM", rnd), Some(rnd.to_string()));
+  assert_eq!(metadata.get("random"), Some(rnd.to_string().as_str()));
+  assert_eq!(metadata.len(), 1);
+
+  let serialized = serde_json::to_string(&metadata).unwrap();
+  assert_eq!(serialized, format!("{{\"random\":\"{}\"}}", rnd));
+  assert_eq!(serde_json::from_str::<Metadata>(&serialized).unwrap(), metadata);
+
+  assert_eq!(metadata.remove("Random"), Some(rnd.to_string()));
+  assert!(metadata.is_empty());
+}
+
+#[test]
+fn remote_error() {
+  let remote = RemoteError::new(RemoteError::PROCEDURE_NOT_FOUND, "not found").with_data("42".to_string());
+
+  let serialized = serde_json::to_string(&remote).unwrap();
+  assert_eq!(serde_json::from_str::<RemoteError>(&serialized).unwrap(), remote);
+
+  assert_eq!(RemoteError::from(anyhow::Error::from(remote.clone())), remote);
+  let typed = RemoteError::<u32>::from(anyhow::Error::from(remote.clone()));
+  assert_eq!((typed.code, typed.message.as_str(), typed.data), (RemoteError::PROCEDURE_NOT_FOUND, "not found", None));
+  assert_eq!(RemoteError::<String>::from(anyhow::anyhow!("failed")).code, RemoteError::SERVER_ERROR);
+
+  let timeout = core::time::Duration::from_millis(10);
+  let timed_out = RemoteError::<String>::from(anyhow::Error::from(Error::Timeout { procedure: "add".into(), timeout }));
+  assert_eq!(timed_out.code, RemoteError::TIMEOUT);
+  assert!(matches!(timed_out.into_error("add", Some(timeout)).downcast_ref::<Error>(), Some(Error::Timeout { .. })));
+}
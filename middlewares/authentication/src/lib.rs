@@ -3,9 +3,8 @@ use std::marker::PhantomData;
 use anyhow::Result;
 use merfolk::{
   interfaces::{Backend, Middleware},
-  Call, Reply,
+  Call, RemoteError, Reply,
 };
-use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use wildmatch::WildMatch;
 
@@ -15,6 +14,8 @@ pub enum Error {
   ScopeNotFound { procedure: String },
   #[error("auth was not provided")]
   NoAuth,
+  #[error("auth was not found in the call metadata")]
+  NoAuthMetadata,
   #[error("authentication failed for {auth} in scope {scope:?}: {source}")]
   AuthenticationFailed {
     auth: String,
@@ -62,11 +63,10 @@ impl<'a, B> Authentication<'a, B> {
   }
 }
 
-#[derive(Serialize, Deserialize)]
-struct Intermediate<B: Backend> {
-  auth: (String, String),
-  payload: B::Intermediate,
-}
+/// [`Metadata`](merfolk::Metadata) key carrying the user of the auth.
+pub const AUTH_USER_METADATA: &str = "auth-user";
+/// [`Metadata`](merfolk::Metadata) key carrying the password of the auth.
+pub const AUTH_PASSWORD_METADATA: &str = "auth-password";
 
 impl<B: Backend + 'static> Middleware for Authentication<'static, B> {
   type Backend = B;
@@ -74,14 +74,10 @@ impl<B: Backend + 'static> Middleware for Authentication<'static, B> {
   fn wrap_call(&self, call: Result<crate::Call<<Self::Backend as Backend>::Intermediate>>) -> Result<crate::Call<<Self::Backend as Backend>::Intermediate>> {
     let auth = self.auth.as_ref().ok_or(Error::NoAuth)?;
 
-    if let Ok(call) = call {
-      Ok(Call {
-        procedure: call.procedure,
-        payload: B::serialize(&Intermediate::<B> {
-          auth: (auth.0.to_string(), auth.1.to_string()),
-          payload: call.payload,
-        })?,
-      })
+    if let Ok(mut call) = call {
+      call.metadata.insert(AUTH_USER_METADATA, &auth.0);
+      call.metadata.insert(AUTH_PASSWORD_METADATA, &auth.1);
+      Ok(call)
     } else {
       call
     }
@@ -92,7 +88,7 @@ impl<B: Backend + 'static> Middleware for Authentication<'static, B> {
   }
 
   fn unwrap_call(&self, call: Result<crate::Call<<Self::Backend as Backend>::Intermediate>>) -> Result<crate::Call<<Self::Backend as Backend>::Intermediate>> {
-    if let Ok(call) = call {
+    if let Ok(mut call) = call {
       let mut scope: Vec<String> = vec![];
 
       if let Some(scopes) = &self.scopes {
@@ -103,15 +99,26 @@ impl<B: Backend + 'static> Middleware for Authentication<'static, B> {
         });
       }
 
-      let intermediate: Intermediate<B> = B::deserialize(&call.payload)?;
-
-      if let Err(err) = self.authenticator.as_ref().ok_or::<Error>(Error::NoValidatorRegistered)?(intermediate.auth, scope) {
-        Err(err)
+      let auth = match (call.metadata.remove(AUTH_USER_METADATA), call.metadata.remove(AUTH_PASSWORD_METADATA)) {
+        (Some(user), Some(password)) => (user, password),
+        _ => return Err(RemoteError::new(RemoteError::AUTHENTICATION_FAILED, Error::NoAuthMetadata).into()),
+      };
+
+      let scope_name = scope.first().cloned();
+      if let Err(err) = self.authenticator.as_ref().ok_or::<Error>(Error::NoValidatorRegistered)?((auth.0.clone(), auth.1), scope) {
+        Err(
+          RemoteError::new(
+            RemoteError::AUTHENTICATION_FAILED,
+            Error::AuthenticationFailed {
+              auth: auth.0,
+              scope: scope_name,
+              source: err,
+            },
+          )
+          .into(),
+        )
       } else {
-        Ok(Call {
-          procedure: call.procedure,
-          payload: intermediate.payload,
-        })
+        Ok(call)
       }
     } else {
       call